@@ -0,0 +1,273 @@
+//! 统一的接口响应格式
+//!
+//! 图形界面命令、HTTP 接口共用 [`ApiResponse`]：成功时 `data` 为业务数据，
+//! 失败时 `error` 携带稳定的 [`ErrorCode`]、面向用户的本地化描述以及可选的结构化详情。
+//! 启用 `ts` 特性运行测试时会导出对应的 TypeScript 类型，前端直接引用生成的定义。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::machine_id::BackupError;
+
+/// 机器可读的错误码，与 [`BackupError`] 变体一一对应
+/// 序列化值（snake_case）是对外契约，已发布的值不得修改
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum ErrorCode {
+    RegistryError,
+    RegistryWriteError,
+    NotFound,
+    ParseError,
+    StorageError,
+    BackupNotFound,
+    InvalidGuidFormat,
+    InsufficientPermissions,
+    UnsupportedPlatform,
+    GuidGenerationExhausted,
+    InvalidPreviewToken,
+    InvalidSchedule,
+    ScheduleNotFound,
+    ProfileNotFound,
+    ProfileAlreadyExists,
+    InvalidProfile,
+    PendingRevertNotFound,
+    InvalidRevertDuration,
+    InvalidSettings,
+    DataDirConflict,
+    DataDirBusy,
+    HelperFailed,
+    InvalidExport,
+    AuditLogFailed,
+    GuidAlreadyUsed,
+    /// 请求参数无效，不对应具体的 BackupError
+    InvalidRequest,
+    /// 缺少或错误的访问令牌（HTTP 接口）
+    Unauthorized,
+    /// 未预期的内部错误
+    Internal,
+}
+
+impl ErrorCode {
+    /// 序列化后的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::RegistryError => "registry_error",
+            ErrorCode::RegistryWriteError => "registry_write_error",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::ParseError => "parse_error",
+            ErrorCode::StorageError => "storage_error",
+            ErrorCode::BackupNotFound => "backup_not_found",
+            ErrorCode::InvalidGuidFormat => "invalid_guid_format",
+            ErrorCode::InsufficientPermissions => "insufficient_permissions",
+            ErrorCode::UnsupportedPlatform => "unsupported_platform",
+            ErrorCode::GuidGenerationExhausted => "guid_generation_exhausted",
+            ErrorCode::InvalidPreviewToken => "invalid_preview_token",
+            ErrorCode::InvalidSchedule => "invalid_schedule",
+            ErrorCode::ScheduleNotFound => "schedule_not_found",
+            ErrorCode::ProfileNotFound => "profile_not_found",
+            ErrorCode::ProfileAlreadyExists => "profile_already_exists",
+            ErrorCode::InvalidProfile => "invalid_profile",
+            ErrorCode::PendingRevertNotFound => "pending_revert_not_found",
+            ErrorCode::InvalidRevertDuration => "invalid_revert_duration",
+            ErrorCode::InvalidSettings => "invalid_settings",
+            ErrorCode::DataDirConflict => "data_dir_conflict",
+            ErrorCode::DataDirBusy => "data_dir_busy",
+            ErrorCode::HelperFailed => "helper_failed",
+            ErrorCode::InvalidExport => "invalid_export",
+            ErrorCode::AuditLogFailed => "audit_log_failed",
+            ErrorCode::GuidAlreadyUsed => "guid_already_used",
+            ErrorCode::InvalidRequest => "invalid_request",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
+
+impl From<&BackupError> for ErrorCode {
+    fn from(error: &BackupError) -> Self {
+        match error {
+            BackupError::RegistryError(_) => ErrorCode::RegistryError,
+            BackupError::RegistryWriteError(_) => ErrorCode::RegistryWriteError,
+            BackupError::NotFound => ErrorCode::NotFound,
+            BackupError::ParseError(_) => ErrorCode::ParseError,
+            BackupError::StorageError(_) => ErrorCode::StorageError,
+            BackupError::BackupNotFound(_) => ErrorCode::BackupNotFound,
+            BackupError::InvalidGuidFormat(_) => ErrorCode::InvalidGuidFormat,
+            BackupError::InsufficientPermissions => ErrorCode::InsufficientPermissions,
+            BackupError::UnsupportedPlatform => ErrorCode::UnsupportedPlatform,
+            BackupError::GuidGenerationExhausted(_) => ErrorCode::GuidGenerationExhausted,
+            BackupError::InvalidPreviewToken(_) => ErrorCode::InvalidPreviewToken,
+            BackupError::InvalidSchedule(_) => ErrorCode::InvalidSchedule,
+            BackupError::ScheduleNotFound(_) => ErrorCode::ScheduleNotFound,
+            BackupError::ProfileNotFound(_) => ErrorCode::ProfileNotFound,
+            BackupError::ProfileAlreadyExists(_) => ErrorCode::ProfileAlreadyExists,
+            BackupError::InvalidProfile(_) => ErrorCode::InvalidProfile,
+            BackupError::PendingRevertNotFound(_) => ErrorCode::PendingRevertNotFound,
+            BackupError::InvalidRevertDuration(_) => ErrorCode::InvalidRevertDuration,
+            BackupError::InvalidSettings(_) => ErrorCode::InvalidSettings,
+            BackupError::DataDirConflict(_) => ErrorCode::DataDirConflict,
+            BackupError::DataDirBusy(_) => ErrorCode::DataDirBusy,
+            BackupError::HelperFailed(_) => ErrorCode::HelperFailed,
+            BackupError::InvalidExport(_) => ErrorCode::InvalidExport,
+            BackupError::AuditLogFailed(_) => ErrorCode::AuditLogFailed,
+            BackupError::GuidAlreadyUsed(_) => ErrorCode::GuidAlreadyUsed,
+        }
+    }
+}
+
+/// 接口错误
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ApiError {
+    pub code: ErrorCode,
+    /// 面向用户的本地化描述
+    pub message: String,
+    /// 可选的结构化详情，例如重试次数、越界的数值
+    #[cfg_attr(feature = "ts", ts(type = "Record<string, unknown> | null"))]
+    pub details: Option<Value>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ApiError {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// 由内部错误构造，`message` 由调用方提供（通常为脱敏后的本地化描述）
+    /// 仅附带不含路径等敏感信息的数值型详情
+    pub fn from_backup_error(error: &BackupError, message: impl Into<String>) -> Self {
+        let details = match error {
+            BackupError::GuidGenerationExhausted(attempts) => Some(json!({ "attempts": attempts })),
+            BackupError::InvalidRevertDuration(seconds) => Some(json!({ "seconds": seconds })),
+            _ => None,
+        };
+        ApiError {
+            code: ErrorCode::from(error),
+            message: message.into(),
+            details,
+        }
+    }
+}
+
+/// 统一的响应外壳
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<ApiError>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn ok(data: T) -> Self {
+        ApiResponse {
+            success: true,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    pub fn err(error: ApiError) -> Self {
+        ApiResponse {
+            success: false,
+            data: None,
+            error: Some(error),
+        }
+    }
+
+    /// 按结果构造响应，错误描述由 `localize` 生成
+    pub fn from_result(
+        result: Result<T, BackupError>,
+        localize: impl FnOnce(&BackupError) -> String,
+    ) -> Self {
+        match result {
+            Ok(data) => ApiResponse::ok(data),
+            Err(e) => {
+                let message = localize(&e);
+                ApiResponse::err(ApiError::from_backup_error(&e, message))
+            }
+        }
+    }
+
+    /// 错误码，成功时为 None
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        self.error.as_ref().map(|e| e.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_names_match_serialization() {
+        let codes = [
+            ErrorCode::RegistryError,
+            ErrorCode::InsufficientPermissions,
+            ErrorCode::GuidGenerationExhausted,
+            ErrorCode::InvalidRevertDuration,
+            ErrorCode::InvalidRequest,
+            ErrorCode::Unauthorized,
+            ErrorCode::Internal,
+        ];
+        for code in codes {
+            assert_eq!(serde_json::to_value(code).unwrap(), json!(code.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_error_code_from_backup_error() {
+        assert_eq!(
+            ErrorCode::from(&BackupError::BackupNotFound("x".to_string())),
+            ErrorCode::BackupNotFound
+        );
+        assert_eq!(
+            ErrorCode::from(&BackupError::InsufficientPermissions),
+            ErrorCode::InsufficientPermissions
+        );
+    }
+
+    #[test]
+    fn test_response_envelope_shape() {
+        let ok: ApiResponse<u32> = ApiResponse::ok(7);
+        assert_eq!(
+            serde_json::to_value(&ok).unwrap(),
+            json!({ "success": true, "data": 7, "error": null })
+        );
+
+        let err: ApiResponse<u32> =
+            ApiResponse::from_result(Err(BackupError::GuidGenerationExhausted(32)), |_| {
+                "无法生成".to_string()
+            });
+        assert_eq!(err.error_code(), Some(ErrorCode::GuidGenerationExhausted));
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({
+                "success": false,
+                "data": null,
+                "error": {
+                    "code": "guid_generation_exhausted",
+                    "message": "无法生成",
+                    "details": { "attempts": 32 }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_details_do_not_leak_messages() {
+        let error = ApiError::from_backup_error(
+            &BackupError::StorageError("/home/user/.data/backups.json".to_string()),
+            "存储操作失败",
+        );
+        assert_eq!(error.details, None);
+        assert_eq!(error.code, ErrorCode::StorageError);
+    }
+}
@@ -0,0 +1,132 @@
+//! 本地 HTTP 接口的访问令牌
+//!
+//! 令牌保存在应用数据目录的 `api_token` 文件中，首次使用时随机生成。
+//! 调用方需以 `Authorization: Bearer <令牌>` 方式携带，能读取该文件即视为已授权。
+
+use std::fs;
+use std::path::Path;
+
+use rand::RngCore;
+use tracing::info;
+
+use crate::machine_id::{get_data_file_path, BackupError};
+
+/// 令牌文件名，与备份文件位于同一目录
+const API_TOKEN_FILE: &str = "api_token";
+/// 令牌随机字节数
+const TOKEN_BYTES: usize = 32;
+
+/// 读取令牌，不存在时生成并保存
+pub fn load_or_create_api_token() -> Result<String, BackupError> {
+    load_or_create_from(&get_data_file_path(API_TOKEN_FILE)?)
+}
+
+/// 从指定文件读取令牌，不存在或为空时生成并保存
+pub fn load_or_create_from(path: &Path) -> Result<String, BackupError> {
+    if path.exists() {
+        let token =
+            fs::read_to_string(path).map_err(|e| BackupError::StorageError(e.to_string()))?;
+        let token = token.trim();
+        if !token.is_empty() {
+            return Ok(token.to_string());
+        }
+    }
+
+    let mut bytes = [0u8; TOKEN_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token = hex::encode(bytes);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| BackupError::StorageError(e.to_string()))?;
+    }
+    write_private(path, &token)?;
+    info!("已生成新的 API 令牌: {}", path.display());
+    Ok(token)
+}
+
+/// 写入令牌文件，Unix 上仅属主可读写
+#[cfg(unix)]
+fn write_private(path: &Path, token: &str) -> Result<(), BackupError> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| BackupError::StorageError(e.to_string()))?;
+    // 文件已存在时 mode 不生效，需要显式收紧权限
+    file.set_permissions(fs::Permissions::from_mode(0o600))
+        .map_err(|e| BackupError::StorageError(e.to_string()))?;
+    file.write_all(token.as_bytes())
+        .map_err(|e| BackupError::StorageError(e.to_string()))
+}
+
+/// 写入令牌文件，Windows 上继承应用数据目录的访问控制
+#[cfg(not(unix))]
+fn write_private(path: &Path, token: &str) -> Result<(), BackupError> {
+    fs::write(path, token).map_err(|e| BackupError::StorageError(e.to_string()))
+}
+
+/// 校验 `Authorization` 请求头是否携带了正确的令牌
+/// 使用定长比较，避免通过响应时间逐字节猜测令牌
+pub fn bearer_matches(expected: &str, authorization: Option<&str>) -> bool {
+    let provided = match authorization.and_then(|h| h.strip_prefix("Bearer ")) {
+        Some(provided) => provided.trim().as_bytes(),
+        None => return false,
+    };
+    let expected = expected.as_bytes();
+    if provided.len() != expected.len() {
+        return false;
+    }
+    provided
+        .iter()
+        .zip(expected)
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_token_is_created_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("api_token");
+
+        let token = load_or_create_from(&path).unwrap();
+        assert_eq!(token.len(), TOKEN_BYTES * 2);
+        assert_eq!(load_or_create_from(&path).unwrap(), token);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_empty_token_file_is_regenerated() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("api_token");
+        fs::write(&path, "\n").unwrap();
+
+        let token = load_or_create_from(&path).unwrap();
+        assert!(!token.is_empty());
+        assert_eq!(fs::read_to_string(&path).unwrap(), token);
+    }
+
+    #[test]
+    fn test_bearer_matches() {
+        assert!(bearer_matches("abc123", Some("Bearer abc123")));
+        assert!(!bearer_matches("abc123", Some("Bearer abc124")));
+        assert!(!bearer_matches("abc123", Some("Bearer abc")));
+        assert!(!bearer_matches("abc123", Some("Basic abc123")));
+        assert!(!bearer_matches("abc123", None));
+    }
+}
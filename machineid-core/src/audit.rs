@@ -0,0 +1,735 @@
+//! 机器标识操作的审计日志
+//!
+//! 读取、备份、写入、恢复、删除、清空和导出操作各追加一条记录到数据目录的 `audit.log`（JSON Lines），
+//! 记录操作者（系统用户）、进程、新旧值的 SHA-256 摘要和执行结果，不保存机器码明文。
+//!
+//! 每条记录保存上一条记录的哈希，自身的哈希覆盖包括 `prev_hash` 在内的全部字段。
+//! 修改、删除或插入任何一条记录都会使哈希链断裂，由 [`verify_audit_log`] 检出。
+//! 最新记录的序号和哈希另存于 `audit.head`，删除末尾的记录同样会被检出。
+//! 本模块只提供追加接口，没有修改或删除记录的方法。
+//!
+//! 图形界面、命令行、守护进程和 HTTP 服务可能同时追加，读取末尾记录到写入完成期间持有
+//! `audit.log.lock` 上的排他文件锁，保证序号和哈希链在多个进程间连续。
+//!
+//! 哈希链不含密钥，能同时改写 `audit.log` 和 `audit.head` 的人仍可以截断后重新计算，
+//! 需要更强保证时应将 `audit.head` 定期复制到本机以外的位置。
+//!
+//! 写入和恢复机器码前会先确认审计日志可写，不可写时拒绝修改；修改后记录失败时返回
+//! [`BackupError::AuditLogFailed`]，调用方据此提示操作未被审计。
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+use crate::api::ErrorCode;
+use crate::i18n::{tr, Message};
+use crate::json_store;
+use crate::machine_id::{get_data_file_path, normalize_identity, BackupError};
+
+/// 审计日志文件名，与备份文件位于同一目录
+pub const AUDIT_LOG_FILE: &str = "audit.log";
+/// 最新记录位置的文件名，与审计日志位于同一目录
+pub const AUDIT_HEAD_FILE: &str = "audit.head";
+/// 第一条记录的 `prev_hash`
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// 追加时读取文件末尾的字节数，足以容纳最后一条完整记录
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// 被审计的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum AuditOperation {
+    Read,
+    Backup,
+    Write,
+    Restore,
+    Delete,
+    Clear,
+    /// 备份被导出到文件，`target` 为导出路径
+    Export,
+}
+
+/// 审计记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AuditEntry {
+    /// 序号，从 1 开始连续递增
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub seq: u64,
+    /// 记录时间（Unix 毫秒）
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub timestamp: u64,
+    pub operation: AuditOperation,
+    /// 执行操作的系统用户
+    pub operator: String,
+    pub pid: u32,
+    /// 执行操作的程序名称
+    pub process: String,
+    /// 操作对象，例如备份 ID
+    pub target: Option<String>,
+    /// 操作前机器码的 SHA-256 摘要
+    pub old_hash: Option<String>,
+    /// 操作后（或读取到的）机器码的 SHA-256 摘要
+    pub new_hash: Option<String>,
+    pub success: bool,
+    /// 失败时的错误码
+    pub error_code: Option<String>,
+    /// 上一条记录的哈希，第一条记录为全零
+    pub prev_hash: String,
+    /// 本条记录的哈希
+    pub hash: String,
+}
+
+/// 查询条件，均为可选
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AuditQuery {
+    pub operation: Option<AuditOperation>,
+    /// 起始时间（Unix 毫秒，含）
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub since: Option<u64>,
+    /// 结束时间（Unix 毫秒，含）
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub until: Option<u64>,
+    /// 最多返回的条数，按时间从新到旧
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub limit: Option<usize>,
+}
+
+/// 哈希链断裂的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum AuditBreak {
+    /// 无法解析为审计记录
+    Malformed,
+    /// 序号不连续，说明有记录被删除或插入
+    SequenceGap,
+    /// `prev_hash` 与上一条记录的哈希不一致
+    ChainMismatch,
+    /// 记录内容与其哈希不一致，说明记录被修改
+    HashMismatch,
+    /// 日志末尾与 `audit.head` 保存的最新记录不一致，说明末尾的记录被删除或替换
+    HeadMismatch,
+}
+
+/// 校验结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct AuditVerification {
+    pub valid: bool,
+    /// 校验通过的记录数
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub entries: u64,
+    /// 第一处异常所在的行号（从 1 开始）
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub broken_at: Option<u64>,
+    pub reason: Option<AuditBreak>,
+}
+
+/// 最新记录的序号和哈希
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct AuditHead {
+    seq: u64,
+    hash: String,
+}
+
+impl AuditEntry {
+    /// 计算哈希：对 `hash` 字段置空后的 JSON 取 SHA-256
+    fn compute_hash(&self) -> String {
+        let unsigned = AuditEntry {
+            hash: String::new(),
+            ..self.clone()
+        };
+        let content = serde_json::to_vec(&unsigned).unwrap_or_default();
+        hex::encode(Sha256::digest(content))
+    }
+
+    fn matches(&self, query: &AuditQuery) -> bool {
+        query.operation.is_none_or(|op| op == self.operation)
+            && query.since.is_none_or(|since| self.timestamp >= since)
+            && query.until.is_none_or(|until| self.timestamp <= until)
+    }
+}
+
+/// 机器码的摘要，忽略大小写和连字符，同一标识的 GUID 与 machine-id 格式得到相同摘要
+pub fn hash_value(value: &str) -> String {
+    hex::encode(Sha256::digest(normalize_identity(value).as_bytes()))
+}
+
+/// 读取文件最后一条记录，文件不存在或为空时返回 None
+fn last_entry(path: &Path) -> Result<Option<AuditEntry>, BackupError> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(BackupError::StorageError(e.to_string())),
+    };
+    let len = file
+        .metadata()
+        .map_err(|e| BackupError::StorageError(e.to_string()))?
+        .len();
+    let mut tail = Vec::new();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES)))
+        .and_then(|_| file.read_to_end(&mut tail))
+        .map_err(|e| BackupError::StorageError(e.to_string()))?;
+
+    let tail = String::from_utf8_lossy(&tail);
+    let Some(line) = tail.lines().rev().find(|line| !line.trim().is_empty()) else {
+        return Ok(None);
+    };
+    serde_json::from_str(line)
+        .map(Some)
+        .map_err(|e| BackupError::StorageError(format!("审计日志最后一条记录已损坏: {}", e)))
+}
+
+fn head_path(path: &Path) -> PathBuf {
+    path.with_file_name(AUDIT_HEAD_FILE)
+}
+
+fn open_for_append(path: &Path) -> Result<File, BackupError> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .map_err(|e| BackupError::StorageError(e.to_string()))
+}
+
+/// 补全序号和哈希后追加到日志末尾，并更新 `audit.head`
+///
+/// 末尾记录被删除后继续追加时，序号接在 `audit.head` 之后，删除留下的缺口仍能被检出。
+fn append_to(path: &Path, mut entry: AuditEntry) -> Result<AuditEntry, BackupError> {
+    let _lock = json_store::lock_exclusive(path)?;
+    let last = last_entry(path)?;
+    let head: Option<AuditHead> = json_store::load(&head_path(path))?;
+    let last_seq = last.as_ref().map_or(0, |last| last.seq);
+    entry.seq = last_seq.max(head.map_or(0, |head| head.seq)) + 1;
+    entry.prev_hash = last.map_or_else(|| GENESIS_HASH.to_string(), |last| last.hash);
+    entry.hash = entry.compute_hash();
+
+    let mut line =
+        serde_json::to_string(&entry).map_err(|e| BackupError::StorageError(e.to_string()))?;
+    line.push('\n');
+
+    let mut file = open_for_append(path)?;
+    file.write_all(line.as_bytes())
+        .and_then(|_| file.sync_data())
+        .map_err(|e| BackupError::StorageError(e.to_string()))?;
+    json_store::save(
+        &head_path(path),
+        &AuditHead {
+            seq: entry.seq,
+            hash: entry.hash.clone(),
+        },
+    )?;
+    Ok(entry)
+}
+
+/// 确认可以追加：能取得锁、打开日志文件，且末尾记录完好
+fn check_writable(path: &Path) -> Result<(), BackupError> {
+    let _lock = json_store::lock_exclusive(path)?;
+    last_entry(path)?;
+    json_store::load::<Option<AuditHead>>(&head_path(path))?;
+    open_for_append(path).map(|_| ())
+}
+
+fn query_from(path: &Path, query: &AuditQuery) -> Result<Vec<AuditEntry>, BackupError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(BackupError::StorageError(e.to_string())),
+    };
+    // 损坏的行由 verify 报告，查询时跳过
+    let matched = content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|entry| entry.matches(query));
+    Ok(match query.limit {
+        Some(limit) => matched.take(limit).collect(),
+        None => matched.collect(),
+    })
+}
+
+fn verify_from(path: &Path) -> Result<AuditVerification, BackupError> {
+    let _lock = json_store::lock_exclusive(path)?;
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(BackupError::StorageError(e.to_string())),
+    };
+
+    // audit.head 损坏视为末尾不一致
+    let head = json_store::load::<Option<AuditHead>>(&head_path(path)).unwrap_or_else(|_| {
+        Some(AuditHead {
+            seq: u64::MAX,
+            hash: String::new(),
+        })
+    });
+    let mut head_matched = head.is_none();
+
+    let mut prev_hash = GENESIS_HASH.to_string();
+    let mut entries = 0u64;
+    for (index, line) in content.lines().enumerate() {
+        let reason = match serde_json::from_str::<AuditEntry>(line) {
+            Err(_) => Some(AuditBreak::Malformed),
+            Ok(entry) if entry.seq != entries + 1 => Some(AuditBreak::SequenceGap),
+            Ok(entry) if entry.prev_hash != prev_hash => Some(AuditBreak::ChainMismatch),
+            Ok(entry) if entry.compute_hash() != entry.hash => Some(AuditBreak::HashMismatch),
+            Ok(entry) => {
+                if head.as_ref().is_some_and(|head| head.seq == entry.seq) {
+                    head_matched = head.as_ref().is_some_and(|head| head.hash == entry.hash);
+                }
+                prev_hash = entry.hash;
+                None
+            }
+        };
+        if let Some(reason) = reason {
+            return Ok(AuditVerification {
+                valid: false,
+                entries,
+                broken_at: Some(index as u64 + 1),
+                reason: Some(reason),
+            });
+        }
+        entries += 1;
+    }
+    if !head_matched {
+        return Ok(AuditVerification {
+            valid: false,
+            entries,
+            broken_at: Some(entries + 1),
+            reason: Some(AuditBreak::HeadMismatch),
+        });
+    }
+    Ok(AuditVerification {
+        valid: true,
+        entries,
+        broken_at: None,
+        reason: None,
+    })
+}
+
+/// 当前系统用户
+#[cfg(unix)]
+fn current_operator() -> String {
+    let uid = unsafe { libc::getuid() };
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buffer = vec![0 as libc::c_char; 1024];
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let found = unsafe {
+        libc::getpwuid_r(
+            uid,
+            &mut passwd,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    } == 0
+        && !result.is_null();
+    if found {
+        let name = unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) };
+        format!("{} (uid {})", name.to_string_lossy(), uid)
+    } else {
+        format!("uid {}", uid)
+    }
+}
+
+#[cfg(windows)]
+fn current_operator() -> String {
+    let user = std::env::var("USERNAME").unwrap_or_else(|_| "unknown".to_string());
+    match std::env::var("USERDOMAIN") {
+        Ok(domain) => format!("{}\\{}", domain, user),
+        Err(_) => user,
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn current_operator() -> String {
+    "unknown".to_string()
+}
+
+fn current_process() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 记录一次操作，`old_value`/`new_value` 为机器码明文，只保存其摘要
+///
+/// 审计日志写入失败不影响读取、备份等操作本身，只记录错误日志；
+/// 修改机器码的操作使用 [`record_identity_change`]。
+pub(crate) fn record(
+    operation: AuditOperation,
+    target: Option<&str>,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    error: Option<&BackupError>,
+) {
+    if let Err(e) = append(operation, target, old_value, new_value, error) {
+        error!("写入审计日志失败: {:?} {}", operation, e);
+    }
+}
+
+/// 修改机器码前确认审计日志可写，不可写时拒绝修改
+pub(crate) fn ensure_writable() -> Result<(), BackupError> {
+    get_data_file_path(AUDIT_LOG_FILE)
+        .and_then(|path| check_writable(&path))
+        .map_err(|e| {
+            error!("审计日志不可写，拒绝修改机器码: {}", e);
+            BackupError::AuditLogFailed(tr(Message::AuditLogUnavailable))
+        })
+}
+
+/// 记录一次写入或恢复，失败时返回 [`BackupError::AuditLogFailed`]
+///
+/// 此时机器码可能已被修改，调用方应将错误交给用户，而不是当作操作失败重试。
+pub(crate) fn record_identity_change(
+    operation: AuditOperation,
+    target: Option<&str>,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    error: Option<&BackupError>,
+) -> Result<(), BackupError> {
+    append(operation, target, old_value, new_value, error)
+        .map(|_| ())
+        .map_err(|e| {
+            error!("写入审计日志失败: {:?} {}", operation, e);
+            BackupError::AuditLogFailed(tr(Message::AuditLogNotRecorded))
+        })
+}
+
+fn append(
+    operation: AuditOperation,
+    target: Option<&str>,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    error: Option<&BackupError>,
+) -> Result<AuditEntry, BackupError> {
+    let entry = AuditEntry {
+        seq: 0,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        operation,
+        operator: current_operator(),
+        pid: std::process::id(),
+        process: current_process(),
+        target: target.map(str::to_string),
+        old_hash: old_value.map(hash_value),
+        new_hash: new_value.map(hash_value),
+        success: error.is_none(),
+        error_code: error.map(|e| ErrorCode::from(e).as_str().to_string()),
+        prev_hash: String::new(),
+        hash: String::new(),
+    };
+
+    append_to(&get_data_file_path(AUDIT_LOG_FILE)?, entry)
+}
+
+/// 按条件查询审计记录，按时间从新到旧排列
+pub fn query_audit_log(query: &AuditQuery) -> Result<Vec<AuditEntry>, BackupError> {
+    query_from(&get_data_file_path(AUDIT_LOG_FILE)?, query)
+}
+
+/// 最近一次成功的写入或恢复是否将机器码改为 `value`
+///
+/// 用于识别本程序其他进程（命令行、守护进程、计划任务）的写入，避免被当作外部修改。
+pub(crate) fn last_identity_change_is(value: &str) -> bool {
+    get_data_file_path(AUDIT_LOG_FILE).is_ok_and(|path| last_change_is(&path, value))
+}
+
+fn last_change_is(path: &Path, value: &str) -> bool {
+    let Ok(entries) = query_from(path, &AuditQuery::default()) else {
+        return false;
+    };
+    entries
+        .into_iter()
+        .find(|entry| {
+            entry.success
+                && matches!(
+                    entry.operation,
+                    AuditOperation::Write | AuditOperation::Restore
+                )
+        })
+        .and_then(|entry| entry.new_hash)
+        .is_some_and(|hash| hash == hash_value(value))
+}
+
+/// 校验审计日志的哈希链
+pub fn verify_audit_log() -> Result<AuditVerification, BackupError> {
+    verify_from(&get_data_file_path(AUDIT_LOG_FILE)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(operation: AuditOperation, timestamp: u64) -> AuditEntry {
+        AuditEntry {
+            seq: 0,
+            timestamp,
+            operation,
+            operator: "tester".to_string(),
+            pid: 1,
+            process: "test".to_string(),
+            target: None,
+            old_hash: Some(hash_value("550E8400-E29B-41D4-A716-446655440000")),
+            new_hash: None,
+            success: true,
+            error_code: None,
+            prev_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    fn write_log(path: &Path, count: u64) -> Vec<AuditEntry> {
+        (0..count)
+            .map(|i| append_to(path, entry(AuditOperation::Read, 1_000 + i)).unwrap())
+            .collect()
+    }
+
+    fn rewrite_lines(path: &Path, edit: impl FnOnce(&mut Vec<String>)) {
+        let mut lines: Vec<String> = fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        edit(&mut lines);
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn test_append_builds_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_LOG_FILE);
+
+        let entries = write_log(&path, 3);
+        assert_eq!(entries[0].seq, 1);
+        assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+        assert_eq!(entries[1].prev_hash, entries[0].hash);
+        assert_eq!(entries[2].seq, 3);
+
+        let verification = verify_from(&path).unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.entries, 3);
+    }
+
+    #[test]
+    fn test_verify_empty_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let verification = verify_from(&dir.path().join(AUDIT_LOG_FILE)).unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.entries, 0);
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_LOG_FILE);
+        let expect_break = |edit: &dyn Fn(&mut Vec<String>), line: u64, reason: AuditBreak| {
+            fs::remove_file(&path).ok();
+            fs::remove_file(head_path(&path)).ok();
+            write_log(&path, 4);
+            rewrite_lines(&path, |lines| edit(lines));
+            let verification = verify_from(&path).unwrap();
+            assert!(!verification.valid);
+            assert_eq!(verification.broken_at, Some(line));
+            assert_eq!(verification.reason, Some(reason));
+        };
+
+        // 修改记录内容
+        expect_break(
+            &|lines| lines[1] = lines[1].replace("\"success\":true", "\"success\":false"),
+            2,
+            AuditBreak::HashMismatch,
+        );
+        // 删除一条记录
+        expect_break(&|lines| drop(lines.remove(1)), 2, AuditBreak::SequenceGap);
+        // 修改内容后重新计算哈希，下一条记录的 prev_hash 不再匹配
+        expect_break(
+            &|lines| {
+                let mut forged: AuditEntry = serde_json::from_str(&lines[1]).unwrap();
+                forged.operator = "someone-else".to_string();
+                forged.hash = forged.compute_hash();
+                lines[1] = serde_json::to_string(&forged).unwrap();
+            },
+            3,
+            AuditBreak::ChainMismatch,
+        );
+        expect_break(
+            &|lines| lines[2] = "{".to_string(),
+            3,
+            AuditBreak::Malformed,
+        );
+    }
+
+    #[test]
+    fn test_verify_detects_truncated_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_LOG_FILE);
+        write_log(&path, 4);
+        rewrite_lines(&path, |lines| drop(lines.pop()));
+
+        let verification = verify_from(&path).unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.entries, 3);
+        assert_eq!(verification.broken_at, Some(4));
+        assert_eq!(verification.reason, Some(AuditBreak::HeadMismatch));
+
+        // 截断后继续追加，序号接在 audit.head 之后，缺口仍然可见
+        let next = append_to(&path, entry(AuditOperation::Read, 0)).unwrap();
+        assert_eq!(next.seq, 5);
+        let verification = verify_from(&path).unwrap();
+        assert_eq!(verification.broken_at, Some(4));
+        assert_eq!(verification.reason, Some(AuditBreak::SequenceGap));
+    }
+
+    #[test]
+    fn test_concurrent_appends_keep_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_LOG_FILE);
+
+        // 每次追加各自打开文件并加锁，与多个进程同时追加的情形相同
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| write_log(&path, 10));
+            }
+        });
+
+        let verification = verify_from(&path).unwrap();
+        assert!(verification.valid, "{:?}", verification);
+        assert_eq!(verification.entries, 40);
+    }
+
+    #[test]
+    fn test_append_refuses_corrupt_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_LOG_FILE);
+        write_log(&path, 1);
+        rewrite_lines(&path, |lines| lines.push("garbage".to_string()));
+        assert!(check_writable(&path).is_err());
+        assert!(append_to(&path, entry(AuditOperation::Read, 0)).is_err());
+    }
+
+    #[test]
+    fn test_query_filters_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_LOG_FILE);
+        for (operation, timestamp) in [
+            (AuditOperation::Read, 1_000),
+            (AuditOperation::Write, 2_000),
+            (AuditOperation::Read, 3_000),
+            (AuditOperation::Delete, 4_000),
+        ] {
+            append_to(&path, entry(operation, timestamp)).unwrap();
+        }
+
+        let all = query_from(&path, &AuditQuery::default()).unwrap();
+        assert_eq!(
+            all.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![4, 3, 2, 1]
+        );
+
+        let reads = query_from(
+            &path,
+            &AuditQuery {
+                operation: Some(AuditOperation::Read),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(reads.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![3, 1]);
+
+        let window = query_from(
+            &path,
+            &AuditQuery {
+                since: Some(2_000),
+                until: Some(3_000),
+                limit: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(window.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn test_last_change_is_latest_successful_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_LOG_FILE);
+        let change = |operation, value: &str, success| AuditEntry {
+            new_hash: Some(hash_value(value)),
+            success,
+            ..entry(operation, 0)
+        };
+        assert!(!last_change_is(&path, "550e8400e29b41d4a716446655440000"));
+
+        append_to(
+            &path,
+            change(
+                AuditOperation::Write,
+                "550E8400-E29B-41D4-A716-446655440000",
+                true,
+            ),
+        )
+        .unwrap();
+        // 读取和失败的写入不算
+        append_to(
+            &path,
+            change(
+                AuditOperation::Read,
+                "12345678-1234-1234-1234-123456789012",
+                true,
+            ),
+        )
+        .unwrap();
+        append_to(
+            &path,
+            change(
+                AuditOperation::Restore,
+                "12345678-1234-1234-1234-123456789012",
+                false,
+            ),
+        )
+        .unwrap();
+        assert!(last_change_is(&path, "550e8400e29b41d4a716446655440000"));
+        assert!(!last_change_is(
+            &path,
+            "12345678-1234-1234-1234-123456789012"
+        ));
+    }
+
+    #[test]
+    fn test_hash_value_normalizes_format() {
+        assert_eq!(
+            hash_value("550E8400-E29B-41D4-A716-446655440000"),
+            hash_value("550e8400e29b41d4a716446655440000")
+        );
+        assert_ne!(
+            hash_value("550e8400e29b41d4a716446655440000"),
+            hash_value("550e8400e29b41d4a716446655440001")
+        );
+    }
+}
@@ -0,0 +1,510 @@
+//! 数据目录
+//!
+//! 备份、计划任务、身份配置等数据文件统一存放在数据目录中，支持两种模式：
+//!
+//! - 便携模式：程序所在目录下的 `.data`，数据随程序一起移动
+//! - 安装模式：用户数据目录（Windows `%APPDATA%\MachineID-Manage`，
+//!   macOS `~/Library/Application Support/MachineID-Manage`，
+//!   Linux `$XDG_DATA_HOME/machineid-manage`），适用于安装在只读位置的程序
+//!
+//! 数据目录按以下顺序确定：配置中的 `data_dir`、配置中的 `data_dir_mode`、
+//! 程序目录下的 `portable` 标记文件、已存在的 `.data` 目录（兼容旧版本），其余情况使用安装模式。
+
+use std::ffi::OsStr;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::i18n::{tr, Message};
+use crate::json_store::{self, StoreLock};
+use crate::logging::{self, LOG_DIR_NAME};
+use crate::machine_id::BackupError;
+use crate::settings::{self, Settings, CONFIG_FILE_NAME};
+
+/// 便携模式标记文件，位于程序所在目录
+pub const PORTABLE_MARKER: &str = "portable";
+/// 便携模式下数据目录的名称
+const PORTABLE_DIR_NAME: &str = ".data";
+
+/// 数据目录模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum DataDirMode {
+    /// 程序所在目录下的 `.data`
+    Portable,
+    /// 系统的用户数据目录
+    Installed,
+}
+
+impl DataDirMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataDirMode::Portable => "portable",
+            DataDirMode::Installed => "installed",
+        }
+    }
+
+    /// 按名称解析，不区分大小写
+    pub fn from_name(name: &str) -> Option<DataDirMode> {
+        match name.to_ascii_lowercase().as_str() {
+            "portable" => Some(DataDirMode::Portable),
+            "installed" => Some(DataDirMode::Installed),
+            _ => None,
+        }
+    }
+}
+
+/// 当前数据目录及两种模式对应的位置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct DataDirInfo {
+    /// 当前使用的数据目录
+    pub path: PathBuf,
+    /// 当前模式，使用配置中的自定义目录时为 None
+    pub mode: Option<DataDirMode>,
+    /// 便携模式的数据目录
+    pub portable_path: Option<PathBuf>,
+    /// 安装模式的数据目录
+    pub installed_path: Option<PathBuf>,
+}
+
+/// 数据目录迁移结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct DataDirMigration {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub mode: DataDirMode,
+    /// 已迁移的文件和目录名称
+    pub moved: Vec<String>,
+}
+
+/// 程序所在目录
+fn exe_dir() -> Result<PathBuf, BackupError> {
+    let exe_path = std::env::current_exe().map_err(|e| {
+        BackupError::StorageError(tr(Message::DataDirExePathUnavailable {
+            reason: &e.to_string(),
+        }))
+    })?;
+    exe_path
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| BackupError::StorageError(tr(Message::DataDirExeDirUnavailable)))
+}
+
+/// 便携模式的数据目录
+pub fn portable_data_dir() -> Result<PathBuf, BackupError> {
+    Ok(exe_dir()?.join(PORTABLE_DIR_NAME))
+}
+
+/// 安装模式的数据目录
+#[cfg(windows)]
+pub fn installed_data_dir() -> Result<PathBuf, BackupError> {
+    std::env::var_os("APPDATA")
+        .map(|dir| PathBuf::from(dir).join("MachineID-Manage"))
+        .ok_or_else(|| BackupError::StorageError(tr(Message::DataDirAppDataUnavailable)))
+}
+
+#[cfg(target_os = "macos")]
+pub fn installed_data_dir() -> Result<PathBuf, BackupError> {
+    std::env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join("Library/Application Support/MachineID-Manage"))
+        .ok_or_else(|| BackupError::StorageError(tr(Message::DataDirHomeUnavailable)))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn installed_data_dir() -> Result<PathBuf, BackupError> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("machineid-manage"))
+        .ok_or_else(|| BackupError::StorageError(tr(Message::DataDirHomeUnavailable)))
+}
+
+#[cfg(not(any(windows, unix)))]
+pub fn installed_data_dir() -> Result<PathBuf, BackupError> {
+    Err(BackupError::StorageError(tr(Message::DataDirUnsupportedOs)))
+}
+
+/// 指定模式对应的数据目录
+pub fn data_dir_for(mode: DataDirMode) -> Result<PathBuf, BackupError> {
+    match mode {
+        DataDirMode::Portable => portable_data_dir(),
+        DataDirMode::Installed => installed_data_dir(),
+    }
+}
+
+/// 未在配置中指定模式时，根据标记文件和已有数据判断
+fn detect_mode() -> DataDirMode {
+    match exe_dir() {
+        Ok(dir) if dir.join(PORTABLE_MARKER).is_file() || dir.join(PORTABLE_DIR_NAME).is_dir() => {
+            DataDirMode::Portable
+        }
+        _ => DataDirMode::Installed,
+    }
+}
+
+/// 按配置确定数据目录
+pub fn resolve(settings: &Settings) -> Result<DataDirInfo, BackupError> {
+    let (path, mode) = match &settings.data_dir {
+        Some(dir) => (dir.clone(), None),
+        None => {
+            let mode = settings.data_dir_mode.unwrap_or_else(detect_mode);
+            (data_dir_for(mode)?, Some(mode))
+        }
+    };
+    Ok(DataDirInfo {
+        path,
+        mode,
+        portable_path: portable_data_dir().ok(),
+        installed_path: installed_data_dir().ok(),
+    })
+}
+
+/// 当前配置下的数据目录
+pub fn current_data_dir() -> Result<PathBuf, BackupError> {
+    resolve(&settings::current()).map(|info| info.path)
+}
+
+/// 将当前数据目录中的数据迁移到指定模式的目录，并将该模式保存到用户配置
+/// 目标目录中已有同名数据，或数据文件正被其他进程使用时拒绝迁移，不会覆盖任何文件；
+/// 本进程的日志文件随之切换到新的目录
+pub fn migrate_data_dir(mode: DataDirMode) -> Result<DataDirMigration, BackupError> {
+    let from = current_data_dir()?;
+    let to = data_dir_for(mode)?;
+
+    let moved = if from == to {
+        Vec::new()
+    } else {
+        move_entries(&from, &to, || {
+            let log_dir = to.join(LOG_DIR_NAME);
+            if let Err(e) = logging::redirect_log_dir(&log_dir) {
+                warn!("切换日志目录失败: {}: {}", log_dir.display(), e);
+            }
+        })?
+    };
+
+    set_portable_marker(mode == DataDirMode::Portable);
+    let saved = settings::load_saved().unwrap_or_default();
+    settings::update(Settings {
+        data_dir: None,
+        data_dir_mode: Some(mode),
+        ..saved
+    })?;
+
+    info!(
+        "数据目录已迁移: {} -> {}，共 {} 项",
+        from.display(),
+        to.display(),
+        moved.len()
+    );
+    Ok(DataDirMigration {
+        from,
+        to,
+        mode,
+        moved,
+    })
+}
+
+/// 创建或删除便携模式标记文件，失败时仅记录日志（模式已保存在用户配置中）
+fn set_portable_marker(portable: bool) {
+    let marker = match exe_dir() {
+        Ok(dir) => dir.join(PORTABLE_MARKER),
+        Err(_) => return,
+    };
+    let result = if portable {
+        fs::write(&marker, b"")
+    } else if marker.exists() {
+        fs::remove_file(&marker)
+    } else {
+        Ok(())
+    };
+    if let Err(e) = result {
+        warn!("更新便携模式标记文件失败: {}: {}", marker.display(), e);
+    }
+}
+
+/// 数据文件的锁文件（`<文件名>.lock`），只在本机进程间有效，不需要迁移
+fn is_lock_file(name: &OsStr) -> bool {
+    Path::new(name).extension() == Some(OsStr::new("lock"))
+}
+
+/// 取得数据目录中所有数据文件的锁，任一文件正被其他进程使用时返回 [`BackupError::DataDirBusy`]
+fn lock_data_files(dir: &Path) -> Result<Vec<StoreLock>, BackupError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(BackupError::StorageError(e.to_string())),
+    };
+    let mut locks = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| BackupError::StorageError(e.to_string()))?;
+        let name = entry.file_name();
+        if !is_lock_file(&name) {
+            continue;
+        }
+        let data_file = dir.join(Path::new(&name).file_stem().unwrap_or_default());
+        match json_store::try_lock_exclusive(&data_file)? {
+            Some(lock) => locks.push(lock),
+            None => return Err(BackupError::DataDirBusy(data_file.display().to_string())),
+        }
+    }
+    Ok(locks)
+}
+
+/// 删除迁移后残留在源目录中的锁文件
+fn remove_lock_files(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if is_lock_file(&entry.file_name()) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// 需要迁移的数据项：普通文件和目录，跳过用户配置文件、锁文件和套接字等特殊文件
+fn migratable_entries(dir: &Path) -> Result<Vec<fs::DirEntry>, BackupError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(BackupError::StorageError(e.to_string())),
+    };
+    let mut result = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| BackupError::StorageError(e.to_string()))?;
+        let file_type = entry
+            .file_type()
+            .map_err(|e| BackupError::StorageError(e.to_string()))?;
+        let name = entry.file_name();
+        if (file_type.is_file() || file_type.is_dir())
+            && name != CONFIG_FILE_NAME
+            && !is_lock_file(&name)
+        {
+            result.push(entry);
+        }
+    }
+    result.sort_by_key(|entry| entry.file_name());
+    Ok(result)
+}
+
+/// 将 `from` 中的数据项移动到 `to`
+/// 迁移期间持有所有数据文件的锁，其他进程无法同时修改；先全部复制，成功后调用
+/// `before_remove` 再删除源文件，复制失败时清理已复制的内容，源目录保持不变
+fn move_entries(
+    from: &Path,
+    to: &Path,
+    before_remove: impl FnOnce(),
+) -> Result<Vec<String>, BackupError> {
+    let locks = lock_data_files(from)?;
+    let entries = migratable_entries(from)?;
+    let conflicts: Vec<String> = entries
+        .iter()
+        .filter(|entry| to.join(entry.file_name()).exists())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(BackupError::DataDirConflict(format!(
+            "{}: {}",
+            to.display(),
+            conflicts.join(", ")
+        )));
+    }
+
+    fs::create_dir_all(to).map_err(|e| BackupError::StorageError(e.to_string()))?;
+    let mut copied = Vec::new();
+    for entry in &entries {
+        let target = to.join(entry.file_name());
+        if let Err(e) = copy_entry(&entry.path(), &target) {
+            for path in copied.iter().chain(std::iter::once(&target)) {
+                let _ = remove_entry(path);
+            }
+            return Err(BackupError::StorageError(e.to_string()));
+        }
+        copied.push(target);
+    }
+
+    before_remove();
+    for entry in &entries {
+        if let Err(e) = remove_entry(&entry.path()) {
+            warn!("删除已迁移的数据失败: {}: {}", entry.path().display(), e);
+        }
+    }
+    drop(locks);
+    remove_lock_files(from);
+    // 源目录已空时一并删除，避免下次启动时被识别为便携模式
+    let _ = fs::remove_dir(from);
+
+    Ok(entries
+        .iter()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect())
+}
+
+fn copy_entry(from: &Path, to: &Path) -> std::io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if (file_type.is_file() || file_type.is_dir()) && !is_lock_file(&entry.file_name()) {
+                copy_entry(&entry.path(), &to.join(entry.file_name()))?;
+            }
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+fn remove_entry(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mode_names() {
+        for mode in [DataDirMode::Portable, DataDirMode::Installed] {
+            assert_eq!(DataDirMode::from_name(mode.as_str()), Some(mode));
+            assert_eq!(
+                serde_json::to_value(mode).unwrap(),
+                serde_json::json!(mode.as_str())
+            );
+        }
+        assert_eq!(
+            DataDirMode::from_name("Portable"),
+            Some(DataDirMode::Portable)
+        );
+        assert_eq!(DataDirMode::from_name("roaming"), None);
+    }
+
+    #[test]
+    fn test_resolve_prefers_explicit_dir() {
+        let dir = TempDir::new().unwrap();
+        let settings = Settings {
+            data_dir: Some(dir.path().to_path_buf()),
+            data_dir_mode: Some(DataDirMode::Installed),
+            ..Default::default()
+        };
+        let info = resolve(&settings).unwrap();
+        assert_eq!(info.path, dir.path());
+        assert_eq!(info.mode, None);
+    }
+
+    #[test]
+    fn test_resolve_uses_configured_mode() {
+        let settings = Settings {
+            data_dir_mode: Some(DataDirMode::Portable),
+            ..Default::default()
+        };
+        let info = resolve(&settings).unwrap();
+        assert_eq!(info.mode, Some(DataDirMode::Portable));
+        assert_eq!(Some(info.path), info.portable_path);
+    }
+
+    #[test]
+    fn test_move_entries() {
+        let root = TempDir::new().unwrap();
+        let from = root.path().join("from");
+        let to = root.path().join("to");
+        fs::create_dir_all(from.join("logs")).unwrap();
+        fs::write(from.join("backups.json"), "{}").unwrap();
+        fs::write(from.join("logs").join("app.log"), "line").unwrap();
+        fs::write(from.join(CONFIG_FILE_NAME), "").unwrap();
+
+        let moved = move_entries(&from, &to, || {}).unwrap();
+        assert_eq!(moved, vec!["backups.json".to_string(), "logs".to_string()]);
+        assert_eq!(fs::read_to_string(to.join("backups.json")).unwrap(), "{}");
+        assert_eq!(
+            fs::read_to_string(to.join("logs").join("app.log")).unwrap(),
+            "line"
+        );
+        assert!(!from.join("backups.json").exists());
+        // 用户配置文件不随数据迁移
+        assert!(from.join(CONFIG_FILE_NAME).exists());
+        assert!(!to.join(CONFIG_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_move_entries_refuses_to_overwrite() {
+        let root = TempDir::new().unwrap();
+        let from = root.path().join("from");
+        let to = root.path().join("to");
+        fs::create_dir_all(&from).unwrap();
+        fs::create_dir_all(&to).unwrap();
+        fs::write(from.join("backups.json"), "new").unwrap();
+        fs::write(from.join("profiles.json"), "[]").unwrap();
+        fs::write(to.join("backups.json"), "old").unwrap();
+
+        let result = move_entries(&from, &to, || {});
+        assert!(matches!(result, Err(BackupError::DataDirConflict(_))));
+        assert_eq!(fs::read_to_string(to.join("backups.json")).unwrap(), "old");
+        assert!(from.join("profiles.json").exists());
+        assert!(!to.join("profiles.json").exists());
+    }
+
+    #[test]
+    fn test_move_entries_from_missing_dir() {
+        let root = TempDir::new().unwrap();
+        let moved =
+            move_entries(&root.path().join("missing"), &root.path().join("to"), || {}).unwrap();
+        assert!(moved.is_empty());
+    }
+
+    #[test]
+    fn test_move_entries_skips_lock_files() {
+        let root = TempDir::new().unwrap();
+        let from = root.path().join("from");
+        let to = root.path().join("to");
+        fs::create_dir_all(from.join("logs")).unwrap();
+        fs::write(from.join("backups.json"), "{}").unwrap();
+        fs::write(from.join("backups.json.lock"), "").unwrap();
+        fs::write(from.join("logs").join("app.log.lock"), "").unwrap();
+
+        let mut redirected = false;
+        let moved = move_entries(&from, &to, || redirected = true).unwrap();
+        assert!(redirected);
+        assert_eq!(moved, vec!["backups.json".to_string(), "logs".to_string()]);
+        assert!(!to.join("backups.json.lock").exists());
+        assert!(!to.join("logs").join("app.log.lock").exists());
+        // 残留的锁文件一并清理，源目录被删除
+        assert!(!from.exists());
+    }
+
+    #[test]
+    fn test_move_entries_refuses_while_data_in_use() {
+        let root = TempDir::new().unwrap();
+        let from = root.path().join("from");
+        let to = root.path().join("to");
+        fs::create_dir_all(&from).unwrap();
+        fs::write(from.join("backups.json"), "{}").unwrap();
+        // 模拟其他进程正在修改备份文件
+        let held = fs::File::create(from.join("backups.json.lock")).unwrap();
+        held.lock().unwrap();
+
+        let result = move_entries(&from, &to, || {});
+        assert!(matches!(result, Err(BackupError::DataDirBusy(_))));
+        assert!(from.join("backups.json").exists());
+        assert!(!to.exists());
+
+        held.unlock().unwrap();
+        assert!(move_entries(&from, &to, || {}).is_ok());
+    }
+}
@@ -0,0 +1,415 @@
+//! 诊断包导出
+//!
+//! 现场写入失败时，用户导出一个 zip 文件发给开发者即可，无需逐项截图。诊断包包含：
+//!
+//! - `report.json`：程序版本、平台、权限检测结果、数据目录位置及可写性、各数据文件的统计
+//!   （记录数、解析状态、重复的 ID）、审计日志校验结果和当前配置
+//! - `logs/`：最近的日志文件，总大小不超过 [`MAX_LOG_BYTES`]
+//!
+//! 未选择包含标识符时，报告和日志中的 GUID 与 machine-id 均按 [`crate::logging::redact`] 脱敏，
+//! 即使日志是在调试模式下写入的。
+
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::audit::{self, AuditVerification};
+use crate::data_dir;
+use crate::i18n::{tr, Message};
+use crate::logging::{self, LOG_FILE_NAME};
+use crate::machine_id::{get_backup_file_path, read_machine_guid, BackupError, BackupStore};
+use crate::platform::permissions::{check_admin_permissions, PermissionCheckResult};
+use crate::profiles::ProfileStore;
+use crate::revert::RevertStore;
+use crate::scheduler::ScheduleStore;
+use crate::settings::{self, Settings};
+
+/// 诊断包中日志文件的总大小上限
+pub const MAX_LOG_BYTES: usize = 4 * 1024 * 1024;
+/// 未指定输出路径时，诊断包保存在数据目录下的该子目录中
+pub const DIAGNOSTICS_DIR_NAME: &str = "diagnostics";
+
+/// 运行平台
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlatformInfo {
+    pub os: String,
+    pub arch: String,
+    pub family: String,
+}
+
+/// 数据目录状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataDirStatus {
+    pub path: Option<PathBuf>,
+    /// 当前模式，自定义目录或无法解析配置时为 None
+    pub mode: Option<data_dir::DataDirMode>,
+    pub writable: bool,
+    /// 无法确定目录或写入探测失败的原因
+    pub error: Option<String>,
+}
+
+/// 数据文件的解析状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StoreStatus {
+    /// 文件不存在，视为空
+    Missing,
+    Ok,
+    Invalid {
+        error: String,
+    },
+}
+
+/// 单个数据文件的统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoreStats {
+    pub file: String,
+    pub size_bytes: u64,
+    pub status: StoreStatus,
+    pub entries: usize,
+    /// 出现多次的 ID（身份配置为名称）
+    pub duplicate_ids: Vec<String>,
+}
+
+/// 诊断报告，写入诊断包的 `report.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    pub generated_at: u64,
+    pub app_version: String,
+    pub platform: PlatformInfo,
+    pub permissions: PermissionCheckResult,
+    pub data_dir: DataDirStatus,
+    /// 当前机器码，未选择包含标识符时已脱敏
+    pub machine_id: Option<String>,
+    pub stores: Vec<StoreStats>,
+    pub audit: Option<AuditVerification>,
+    pub settings: Settings,
+    pub include_identifiers: bool,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct DiagnosticsExport {
+    /// 诊断包路径
+    pub path: PathBuf,
+    /// 诊断包内的文件
+    pub files: Vec<String>,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub size_bytes: u64,
+    pub include_identifiers: bool,
+}
+
+/// 收集诊断信息并写入 zip 文件
+/// `path` 为 None 时保存到数据目录下的 `diagnostics/`；`include_identifiers` 为 false 时脱敏
+pub fn export_diagnostics(
+    path: Option<&Path>,
+    include_identifiers: bool,
+) -> Result<DiagnosticsExport, BackupError> {
+    let dir = get_backup_file_path()?
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| BackupError::StorageError(tr(Message::DataDirUnknown)))?;
+    let generated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => dir
+            .join(DIAGNOSTICS_DIR_NAME)
+            .join(format!("machineid-diagnostics-{}.zip", generated_at)),
+    };
+
+    let report = collect_report(&dir, generated_at, include_identifiers);
+    let logs = logging::log_dir()
+        .map(|log_dir| collect_logs(&log_dir, settings::current().log_max_files))
+        .unwrap_or_default();
+    write_bundle(&path, &report, &logs, include_identifiers)
+}
+
+fn collect_report(dir: &Path, generated_at: u64, include_identifiers: bool) -> DiagnosticsReport {
+    let settings = settings::current();
+    DiagnosticsReport {
+        generated_at,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        platform: PlatformInfo {
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            family: std::env::consts::FAMILY.to_string(),
+        },
+        permissions: check_admin_permissions(),
+        data_dir: data_dir_status(dir, &settings),
+        machine_id: read_machine_guid().ok().map(|id| id.guid),
+        stores: vec![
+            inspect_store::<BackupStore>(dir, "backups.json", |s| {
+                s.backups.iter().map(|b| b.id.clone()).collect()
+            }),
+            inspect_store::<ScheduleStore>(dir, "schedules.json", |s| {
+                s.schedules.iter().map(|s| s.id.clone()).collect()
+            }),
+            inspect_store::<ProfileStore>(dir, "profiles.json", |s| {
+                s.profiles.iter().map(|p| p.name.clone()).collect()
+            }),
+            inspect_store::<RevertStore>(dir, "pending_reverts.json", |s| {
+                s.reverts.iter().map(|r| r.id.clone()).collect()
+            }),
+        ],
+        audit: audit::verify_audit_log().ok(),
+        settings,
+        include_identifiers,
+    }
+}
+
+/// 通过创建并删除探测文件判断数据目录是否可写
+fn data_dir_status(dir: &Path, settings: &Settings) -> DataDirStatus {
+    let probe = dir.join(format!(".diagnostics-probe-{}", std::process::id()));
+    let error = File::create(&probe)
+        .and_then(|_| fs::remove_file(&probe))
+        .err()
+        .map(|e| e.to_string());
+    DataDirStatus {
+        path: Some(dir.to_path_buf()),
+        mode: data_dir::resolve(settings).ok().and_then(|info| info.mode),
+        writable: error.is_none(),
+        error,
+    }
+}
+
+fn inspect_store<T: DeserializeOwned>(
+    dir: &Path,
+    file: &str,
+    ids: impl Fn(&T) -> Vec<String>,
+) -> StoreStats {
+    let path = dir.join(file);
+    let mut stats = StoreStats {
+        file: file.to_string(),
+        size_bytes: 0,
+        status: StoreStatus::Missing,
+        entries: 0,
+        duplicate_ids: Vec::new(),
+    };
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return stats,
+        Err(e) => {
+            stats.status = StoreStatus::Invalid {
+                error: e.to_string(),
+            };
+            return stats;
+        }
+    };
+    stats.size_bytes = content.len() as u64;
+    match serde_json::from_str::<T>(&content) {
+        Ok(store) => {
+            let ids = ids(&store);
+            let mut seen = BTreeSet::new();
+            let duplicates: BTreeSet<String> =
+                ids.iter().filter(|id| !seen.insert(*id)).cloned().collect();
+            stats.status = StoreStatus::Ok;
+            stats.entries = ids.len();
+            stats.duplicate_ids = duplicates.into_iter().collect();
+        }
+        Err(e) => {
+            stats.status = StoreStatus::Invalid {
+                error: e.to_string(),
+            }
+        }
+    }
+    stats
+}
+
+/// 从最新的日志文件开始读取，总大小超过上限时只保留较新的部分
+fn collect_logs(log_dir: &Path, max_files: usize) -> Vec<(String, String)> {
+    let names = std::iter::once(LOG_FILE_NAME.to_string())
+        .chain((1..=max_files).map(|index| format!("{}.{}", LOG_FILE_NAME, index)));
+    let mut budget = MAX_LOG_BYTES;
+    let mut logs = Vec::new();
+    for name in names {
+        if budget == 0 {
+            break;
+        }
+        let Ok(bytes) = fs::read(log_dir.join(&name)) else {
+            continue;
+        };
+        let content = if bytes.len() > budget {
+            // 截取末尾时从完整的一行开始
+            let tail = &bytes[bytes.len() - budget..];
+            let start = tail.iter().position(|b| *b == b'\n').map_or(0, |i| i + 1);
+            String::from_utf8_lossy(&tail[start..]).into_owned()
+        } else {
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+        budget = budget.saturating_sub(bytes.len());
+        logs.push((name, content));
+    }
+    logs
+}
+
+fn write_bundle(
+    path: &Path,
+    report: &DiagnosticsReport,
+    logs: &[(String, String)],
+    include_identifiers: bool,
+) -> Result<DiagnosticsExport, BackupError> {
+    let storage = |e: &dyn std::fmt::Display| BackupError::StorageError(e.to_string());
+    let scrub = |text: &str| {
+        if include_identifiers {
+            text.to_string()
+        } else {
+            logging::redact(text).into_owned()
+        }
+    };
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| storage(&e))?;
+    }
+    let file = File::create(path).map_err(|e| storage(&e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut entries = vec![(
+        "report.json".to_string(),
+        serde_json::to_string_pretty(report).map_err(|e| storage(&e))?,
+    )];
+    entries.extend(
+        logs.iter()
+            .map(|(name, content)| (format!("logs/{}", name), content.clone())),
+    );
+
+    let mut files = Vec::with_capacity(entries.len());
+    for (name, content) in entries {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| storage(&e))?;
+        zip.write_all(scrub(&content).as_bytes())
+            .map_err(|e| storage(&e))?;
+        files.push(name);
+    }
+    zip.finish().map_err(|e| storage(&e))?;
+
+    let size_bytes = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    Ok(DiagnosticsExport {
+        path: path.to_path_buf(),
+        files,
+        size_bytes,
+        include_identifiers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    const GUID: &str = "12345678-90ab-cdef-1234-567890abcdef";
+
+    fn read_entry(path: &Path, name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(File::open(path).unwrap()).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_inspect_store_reports_duplicates_and_errors() {
+        let dir = TempDir::new().unwrap();
+        let backup = |id: &str| {
+            format!(
+                r#"{{"id":"{}","guid":"{}","source":"test","timestamp":1,"description":null}}"#,
+                id, GUID
+            )
+        };
+        fs::write(
+            dir.path().join("backups.json"),
+            format!(
+                r#"{{"backups":[{},{},{}]}}"#,
+                backup("a"),
+                backup("b"),
+                backup("a")
+            ),
+        )
+        .unwrap();
+        fs::write(dir.path().join("profiles.json"), "{ not json").unwrap();
+
+        let backups = inspect_store::<BackupStore>(dir.path(), "backups.json", |s| {
+            s.backups.iter().map(|b| b.id.clone()).collect()
+        });
+        assert_eq!(backups.status, StoreStatus::Ok);
+        assert_eq!(backups.entries, 3);
+        assert_eq!(backups.duplicate_ids, vec!["a".to_string()]);
+
+        let profiles = inspect_store::<ProfileStore>(dir.path(), "profiles.json", |s| {
+            s.profiles.iter().map(|p| p.name.clone()).collect()
+        });
+        assert!(matches!(profiles.status, StoreStatus::Invalid { .. }));
+
+        let missing = inspect_store::<ScheduleStore>(dir.path(), "schedules.json", |s| {
+            s.schedules.iter().map(|s| s.id.clone()).collect()
+        });
+        assert_eq!(missing.status, StoreStatus::Missing);
+    }
+
+    #[test]
+    fn test_collect_logs_keeps_newest_within_budget() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(LOG_FILE_NAME), "newest\n").unwrap();
+        fs::write(
+            dir.path().join(format!("{}.1", LOG_FILE_NAME)),
+            "x".repeat(MAX_LOG_BYTES) + "\nolder\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join(format!("{}.2", LOG_FILE_NAME)), "oldest\n").unwrap();
+
+        let logs = collect_logs(dir.path(), 5);
+        let names: Vec<&str> = logs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec![LOG_FILE_NAME, "machineid-manage.log.1"]);
+        assert_eq!(logs[1].1, "older\n");
+    }
+
+    #[test]
+    fn test_bundle_redacts_identifiers_unless_opted_in() {
+        let dir = TempDir::new().unwrap();
+        let report = collect_report(dir.path(), 0, false);
+        let report = DiagnosticsReport {
+            machine_id: Some(GUID.to_string()),
+            ..report
+        };
+        let logs = vec![(LOG_FILE_NAME.to_string(), format!("写入机器码: {}\n", GUID))];
+
+        let redacted = dir.path().join("out").join("redacted.zip");
+        let export = write_bundle(&redacted, &report, &logs, false).unwrap();
+        assert_eq!(
+            export.files,
+            vec![
+                "report.json".to_string(),
+                "logs/machineid-manage.log".to_string()
+            ]
+        );
+        for name in &export.files {
+            let content = read_entry(&redacted, name);
+            assert!(!content.contains(GUID), "{} leaks the GUID", name);
+            assert!(content.contains("1234****-****-****-****-********cdef"));
+        }
+        let parsed: serde_json::Value =
+            serde_json::from_str(&read_entry(&redacted, "report.json")).unwrap();
+        assert_eq!(parsed["app_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(parsed["data_dir"]["writable"], true);
+
+        let raw = dir.path().join("raw.zip");
+        write_bundle(&raw, &report, &logs, true).unwrap();
+        assert!(read_entry(&raw, "logs/machineid-manage.log").contains(GUID));
+    }
+}
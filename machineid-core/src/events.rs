@@ -0,0 +1,286 @@
+//! 机器标识与备份存储的变更事件
+//!
+//! 本进程内的写入操作直接发布事件；其他进程（命令行、守护进程、另一个窗口）对备份存储的修改
+//! 由 [`run_change_monitor`] 检测：备份文件的修改时间或大小变化时重新读取并与上次的快照比较。
+//! 机器码的外部修改由 [`crate::watcher`] 检测后发布 `identity-changed`。
+//! 订阅方通过 [`subscribe`] 接收事件，例如图形界面将其转发给所有窗口。
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+use crate::machine_id::{get_backup_file_path, list_backups, MachineIdBackup};
+use crate::watcher::WatchAction;
+
+/// 检测外部变更的间隔（秒）
+const MONITOR_TICK_SECS: u64 = 2;
+/// 事件通道容量，订阅方处理过慢时会收到 Lagged 并应整体刷新
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+lazy_static! {
+    static ref EVENTS: broadcast::Sender<ChangeEvent> =
+        broadcast::channel(EVENT_CHANNEL_CAPACITY).0;
+    /// 最近一次已知的备份文件状态和机器码，用于识别外部变更
+    static ref KNOWN_STATE: Mutex<KnownState> = Mutex::new(KnownState::default());
+}
+
+/// 变更事件，`type` 字段与事件名称一致
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum ChangeEvent {
+    /// 机器码发生变化，`previous` 未知时为 None
+    IdentityChanged {
+        previous: Option<String>,
+        current: String,
+    },
+    BackupAdded {
+        backup: MachineIdBackup,
+    },
+    BackupDeleted {
+        id: String,
+    },
+    /// 备份存储被整体重新读取（外部修改、描述变更或事件丢失），订阅方应刷新列表
+    StoreReloaded {
+        count: usize,
+    },
+    /// 机器码被其他程序修改，需要提醒用户；`action` 为配置的处理方式，自动恢复失败时也会发出
+    IdentityAlert {
+        previous: String,
+        current: String,
+        action: WatchAction,
+        /// 自动恢复失败的原因
+        error: Option<String>,
+    },
+}
+
+impl ChangeEvent {
+    /// 所有事件名称
+    pub const NAMES: [&'static str; 5] = [
+        "identity-changed",
+        "backup-added",
+        "backup-deleted",
+        "store-reloaded",
+        "identity-alert",
+    ];
+
+    /// 事件名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChangeEvent::IdentityChanged { .. } => "identity-changed",
+            ChangeEvent::BackupAdded { .. } => "backup-added",
+            ChangeEvent::BackupDeleted { .. } => "backup-deleted",
+            ChangeEvent::StoreReloaded { .. } => "store-reloaded",
+            ChangeEvent::IdentityAlert { .. } => "identity-alert",
+        }
+    }
+}
+
+/// 备份文件的修改时间和大小
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fingerprint {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl Fingerprint {
+    fn of(path: &PathBuf) -> Option<Fingerprint> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Fingerprint {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct KnownState {
+    store_path: Option<PathBuf>,
+    fingerprint: Option<Fingerprint>,
+    backup_ids: Vec<String>,
+    identity: Option<String>,
+}
+
+/// 订阅变更事件
+pub fn subscribe() -> broadcast::Receiver<ChangeEvent> {
+    EVENTS.subscribe()
+}
+
+/// 发布事件，没有订阅方时直接丢弃
+pub fn publish(event: ChangeEvent) {
+    debug!("发布变更事件: {:?}", event);
+    let _ = EVENTS.send(event);
+}
+
+/// 本进程保存备份存储后调用，更新快照以免被识别为外部变更
+pub(crate) fn note_store_saved(path: &PathBuf, backups: &[MachineIdBackup]) {
+    let mut state = KNOWN_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.store_path = Some(path.clone());
+    state.fingerprint = Fingerprint::of(path);
+    state.backup_ids = backups.iter().map(|b| b.id.clone()).collect();
+}
+
+/// 本进程写入机器码后调用
+pub(crate) fn note_identity_written(previous: &str, current: &str) {
+    KNOWN_STATE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .identity = Some(current.to_string());
+    if !previous.eq_ignore_ascii_case(current) {
+        publish(ChangeEvent::IdentityChanged {
+            previous: Some(previous.to_string()),
+            current: current.to_string(),
+        });
+    }
+}
+
+/// 记录读取到的机器码，与上次记录的不同时返回上次的值；首次调用只记录
+pub(crate) fn observe_identity(current: &str) -> Option<String> {
+    let mut state = KNOWN_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let previous = state.identity.replace(current.to_string())?;
+    (!previous.eq_ignore_ascii_case(current)).then_some(previous)
+}
+
+/// 比较前后两次的备份列表，返回新增和删除事件
+fn diff_backups(previous_ids: &[String], current: &[MachineIdBackup]) -> Vec<ChangeEvent> {
+    let added = current
+        .iter()
+        .filter(|b| !previous_ids.contains(&b.id))
+        .map(|b| ChangeEvent::BackupAdded { backup: b.clone() });
+    let deleted = previous_ids
+        .iter()
+        .filter(|id| !current.iter().any(|b| &b.id == *id))
+        .map(|id| ChangeEvent::BackupDeleted { id: id.clone() });
+    added.chain(deleted).collect()
+}
+
+/// 检查备份文件是否被其他进程修改，返回需要发布的事件
+fn check_store() -> Vec<ChangeEvent> {
+    let path = match get_backup_file_path() {
+        Ok(path) => path,
+        Err(_) => return Vec::new(),
+    };
+    let fingerprint = Fingerprint::of(&path);
+
+    let mut state = KNOWN_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let path_changed = state.store_path.as_ref() != Some(&path);
+    if !path_changed && state.fingerprint == fingerprint {
+        return Vec::new();
+    }
+    // 首次检查只记录快照
+    let first_check = state.store_path.is_none();
+
+    let backups = match list_backups() {
+        Ok(backups) => backups,
+        Err(e) => {
+            warn!("重新读取备份存储失败: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut events = if path_changed {
+        Vec::new()
+    } else {
+        diff_backups(&state.backup_ids, &backups)
+    };
+    state.store_path = Some(path);
+    state.fingerprint = fingerprint;
+    state.backup_ids = backups.iter().map(|b| b.id.clone()).collect();
+
+    if first_check {
+        return Vec::new();
+    }
+    info!("检测到备份存储的外部变更");
+    events.push(ChangeEvent::StoreReloaded {
+        count: backups.len(),
+    });
+    events
+}
+
+/// 后台检测其他进程对备份存储的修改并发布事件
+pub async fn run_change_monitor() {
+    info!("变更监视已启动，每 {} 秒检查一次", MONITOR_TICK_SECS);
+    let mut interval = tokio::time::interval(Duration::from_secs(MONITOR_TICK_SECS));
+    loop {
+        interval.tick().await;
+        for event in check_store() {
+            publish(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup(id: &str) -> MachineIdBackup {
+        MachineIdBackup {
+            id: id.to_string(),
+            guid: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            source: "test".to_string(),
+            timestamp: 0,
+            description: None,
+            auto_description: None,
+        }
+    }
+
+    #[test]
+    fn test_event_names_match_tags() {
+        let events = [
+            ChangeEvent::IdentityChanged {
+                previous: None,
+                current: "a".to_string(),
+            },
+            ChangeEvent::BackupAdded {
+                backup: backup("backup_1"),
+            },
+            ChangeEvent::BackupDeleted {
+                id: "backup_1".to_string(),
+            },
+            ChangeEvent::StoreReloaded { count: 0 },
+            ChangeEvent::IdentityAlert {
+                previous: "a".to_string(),
+                current: "b".to_string(),
+                action: WatchAction::Revert,
+                error: None,
+            },
+        ];
+        for (event, name) in events.iter().zip(ChangeEvent::NAMES) {
+            assert_eq!(event.name(), name);
+            assert_eq!(serde_json::to_value(event).unwrap()["type"], name);
+        }
+    }
+
+    #[test]
+    fn test_diff_backups() {
+        let previous = vec!["backup_1".to_string(), "backup_2".to_string()];
+        let current = vec![backup("backup_2"), backup("backup_3")];
+        assert_eq!(
+            diff_backups(&previous, &current),
+            vec![
+                ChangeEvent::BackupAdded {
+                    backup: backup("backup_3"),
+                },
+                ChangeEvent::BackupDeleted {
+                    id: "backup_1".to_string(),
+                },
+            ]
+        );
+        assert!(diff_backups(&previous[1..], &current[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_publish_reaches_subscribers() {
+        let mut receiver = subscribe();
+        publish(ChangeEvent::StoreReloaded { count: 3 });
+        // 其他测试可能同时发布事件，找到本测试发布的即可
+        let found = std::iter::from_fn(|| receiver.try_recv().ok())
+            .any(|event| event == ChangeEvent::StoreReloaded { count: 3 });
+        assert!(found);
+    }
+}
@@ -0,0 +1,488 @@
+//! 备份导出
+//!
+//! 将选中的备份导出为以下格式之一：
+//!
+//! - JSON：带格式标识和版本号的 [`BackupExport`]，供本程序或其他工具读取
+//! - CSV：UTF-8（带 BOM）、CRLF 换行，可直接用表格软件打开
+//! - `.reg`：把 `MachineGuid` 设为所选备份的注册表文件，管理员双击即可应用，不依赖本程序；
+//!   只能选择一个 GUID 格式的备份
+//!
+//! 提供密码时，导出内容经 PBKDF2-SHA256 派生密钥、AES-256-GCM 加密后保存为 [`EncryptedExport`]，
+//! 可用 [`decrypt_export`] 还原。
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::audit::{self, AuditOperation};
+use crate::i18n::{tr, Message};
+use crate::machine_id::{list_backups, validate_guid_format, BackupError, MachineIdBackup};
+
+/// JSON 导出文件的格式标识
+pub const EXPORT_FORMAT_ID: &str = "machineid-manage.backups";
+/// JSON 导出文件的当前版本
+pub const EXPORT_VERSION: u32 = 1;
+/// 加密导出文件的格式标识
+pub const ENCRYPTED_FORMAT_ID: &str = "machineid-manage.encrypted";
+/// PBKDF2 迭代次数
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// 解密时接受的迭代次数范围，防止被篡改的文件让密钥派生耗时过长
+const MIN_PBKDF2_ITERATIONS: u32 = 1_000;
+const MAX_PBKDF2_ITERATIONS: u32 = PBKDF2_ITERATIONS * 10;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// 作为附加认证数据，防止密文被挪作其他用途
+const ENCRYPTION_AAD: &[u8] = b"machineid-manage.encrypted.v1";
+/// Windows 机器码所在的注册表键
+const REG_KEY: &str = r"HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\Cryptography";
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Reg,
+}
+
+impl ExportFormat {
+    /// 建议的文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Reg => "reg",
+        }
+    }
+}
+
+/// 导出请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ExportRequest {
+    /// 输出文件路径
+    pub path: PathBuf,
+    pub format: ExportFormat,
+    /// 要导出的备份 ID，按给定顺序导出；省略时导出全部备份
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub ids: Option<Vec<String>>,
+    /// 加密密码，省略时以明文保存
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub password: Option<String>,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ExportResult {
+    pub path: PathBuf,
+    pub format: ExportFormat,
+    /// 导出的备份数
+    pub count: usize,
+    pub encrypted: bool,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub size_bytes: u64,
+}
+
+/// JSON 导出文件的内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupExport {
+    /// 固定为 [`EXPORT_FORMAT_ID`]
+    pub format: String,
+    pub version: u32,
+    /// 导出时间（Unix 毫秒）
+    pub exported_at: u64,
+    pub backups: Vec<MachineIdBackup>,
+}
+
+/// 加密导出文件的内容，二进制字段以十六进制保存
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncryptedExport {
+    /// 固定为 [`ENCRYPTED_FORMAT_ID`]
+    pub format: String,
+    pub version: u32,
+    /// 密钥派生算法，目前为 `pbkdf2-sha256`
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: String,
+    /// 加密算法，目前为 `aes-256-gcm`
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// 按请求导出备份并写入文件
+pub fn export_backups(request: &ExportRequest) -> Result<ExportResult, BackupError> {
+    let result = export_to(request, &list_backups()?, PBKDF2_ITERATIONS);
+    audit::record(
+        AuditOperation::Export,
+        Some(&request.path.display().to_string()),
+        None,
+        None,
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn export_to(
+    request: &ExportRequest,
+    backups: &[MachineIdBackup],
+    iterations: u32,
+) -> Result<ExportResult, BackupError> {
+    let selected = select_backups(backups, request.ids.as_deref())?;
+    let content = match request.format {
+        ExportFormat::Json => render_json(&selected)?,
+        ExportFormat::Csv => render_csv(&selected).into_bytes(),
+        ExportFormat::Reg => render_reg(&selected)?,
+    };
+    let content = match request.password.as_deref() {
+        Some(password) => encrypt(&content, password, iterations)?,
+        None => content,
+    };
+
+    if let Some(parent) = request.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| BackupError::StorageError(e.to_string()))?;
+    }
+    fs::write(&request.path, &content).map_err(|e| BackupError::StorageError(e.to_string()))?;
+    Ok(ExportResult {
+        path: request.path.clone(),
+        format: request.format,
+        count: selected.len(),
+        encrypted: request.password.is_some(),
+        size_bytes: content.len() as u64,
+    })
+}
+
+fn select_backups(
+    backups: &[MachineIdBackup],
+    ids: Option<&[String]>,
+) -> Result<Vec<MachineIdBackup>, BackupError> {
+    let selected = match ids {
+        None => backups.to_vec(),
+        Some(ids) => ids
+            .iter()
+            .map(|id| {
+                backups
+                    .iter()
+                    .find(|b| &b.id == id)
+                    .cloned()
+                    .ok_or_else(|| BackupError::BackupNotFound(id.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    if selected.is_empty() {
+        return Err(BackupError::InvalidExport(tr(
+            Message::ExportNothingSelected,
+        )));
+    }
+    Ok(selected)
+}
+
+fn render_json(backups: &[MachineIdBackup]) -> Result<Vec<u8>, BackupError> {
+    let export = BackupExport {
+        format: EXPORT_FORMAT_ID.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        backups: backups.to_vec(),
+    };
+    serde_json::to_vec_pretty(&export).map_err(|e| BackupError::ParseError(e.to_string()))
+}
+
+fn render_csv(backups: &[MachineIdBackup]) -> String {
+    let mut csv = String::from("\u{feff}id,guid,source,timestamp,description\r\n");
+    for backup in backups {
+        let fields = [
+            csv_field(&backup.id),
+            csv_field(&backup.guid),
+            csv_field(&backup.source),
+            backup.timestamp.to_string(),
+            csv_field(backup.description.as_deref().unwrap_or("")),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// 按 RFC 4180 转义字段；以公式字符开头的值加单引号前缀，避免表格软件将其当作公式执行
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// 生成 regedit 使用的 UTF-16LE（带 BOM）注册表文件
+fn render_reg(backups: &[MachineIdBackup]) -> Result<Vec<u8>, BackupError> {
+    let [backup] = backups else {
+        return Err(BackupError::InvalidExport(tr(
+            Message::ExportRegNeedsSingleBackup {
+                count: backups.len(),
+            },
+        )));
+    };
+    if validate_guid_format(&backup.guid).is_err() {
+        return Err(BackupError::InvalidExport(tr(
+            Message::ExportRegNotMachineGuid { id: &backup.id },
+        )));
+    }
+    let text = format!(
+        "Windows Registry Editor Version 5.00\r\n\r\n[{}]\r\n\"MachineGuid\"=\"{}\"\r\n",
+        REG_KEY,
+        backup.guid.to_lowercase()
+    );
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+    Ok(bytes)
+}
+
+fn derive_key(password: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+fn encrypt(content: &[u8], password: &str, iterations: u32) -> Result<Vec<u8>, BackupError> {
+    if password.is_empty() {
+        return Err(BackupError::InvalidExport(tr(Message::ExportPasswordEmpty)));
+    }
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let key = derive_key(password, &salt, iterations);
+    let cipher = Aes256Gcm::new(&key.into());
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: content,
+                aad: ENCRYPTION_AAD,
+            },
+        )
+        .map_err(|e| BackupError::InvalidExport(e.to_string()))?;
+
+    let envelope = EncryptedExport {
+        format: ENCRYPTED_FORMAT_ID.to_string(),
+        version: EXPORT_VERSION,
+        kdf: "pbkdf2-sha256".to_string(),
+        iterations,
+        salt: hex::encode(salt),
+        cipher: "aes-256-gcm".to_string(),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    };
+    serde_json::to_vec_pretty(&envelope).map_err(|e| BackupError::ParseError(e.to_string()))
+}
+
+/// 解密加密导出文件，返回导出时的原始内容
+/// 密码错误或文件被修改时返回 [`BackupError::InvalidExport`]
+pub fn decrypt_export(content: &[u8], password: &str) -> Result<Vec<u8>, BackupError> {
+    let failed = || BackupError::InvalidExport(tr(Message::ExportDecryptFailed));
+    let envelope: EncryptedExport = serde_json::from_slice(content).map_err(|_| failed())?;
+    if envelope.format != ENCRYPTED_FORMAT_ID || envelope.version != EXPORT_VERSION {
+        return Err(failed());
+    }
+    let salt = hex::decode(&envelope.salt).map_err(|_| failed())?;
+    let nonce = hex::decode(&envelope.nonce).map_err(|_| failed())?;
+    let ciphertext = hex::decode(&envelope.ciphertext).map_err(|_| failed())?;
+    if salt.len() != SALT_LEN
+        || nonce.len() != NONCE_LEN
+        || !(MIN_PBKDF2_ITERATIONS..=MAX_PBKDF2_ITERATIONS).contains(&envelope.iterations)
+    {
+        return Err(failed());
+    }
+
+    let key = derive_key(password, &salt, envelope.iterations);
+    Aes256Gcm::new(&key.into())
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: ENCRYPTION_AAD,
+            },
+        )
+        .map_err(|_| failed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// 测试中使用较少的迭代次数，避免拖慢测试
+    const TEST_ITERATIONS: u32 = 1_000;
+
+    fn backup(id: &str, guid: &str, description: Option<&str>) -> MachineIdBackup {
+        MachineIdBackup {
+            id: id.to_string(),
+            guid: guid.to_string(),
+            source: "test".to_string(),
+            timestamp: 1_700_000_000_000,
+            description: description.map(str::to_string),
+            auto_description: None,
+        }
+    }
+
+    fn request(dir: &TempDir, format: ExportFormat) -> ExportRequest {
+        ExportRequest {
+            path: dir.path().join(format!("export.{}", format.extension())),
+            format,
+            ids: None,
+            password: None,
+        }
+    }
+
+    #[test]
+    fn test_json_export_is_versioned_and_keeps_selection_order() {
+        let dir = TempDir::new().unwrap();
+        let backups = vec![
+            backup("a", "11111111-1111-1111-1111-111111111111", None),
+            backup("b", "22222222-2222-2222-2222-222222222222", None),
+        ];
+        let request = ExportRequest {
+            ids: Some(vec!["b".to_string(), "a".to_string()]),
+            ..request(&dir, ExportFormat::Json)
+        };
+
+        let result = export_to(&request, &backups, TEST_ITERATIONS).unwrap();
+        assert_eq!(result.count, 2);
+        assert!(!result.encrypted);
+
+        let export: BackupExport =
+            serde_json::from_slice(&fs::read(&request.path).unwrap()).unwrap();
+        assert_eq!(export.format, EXPORT_FORMAT_ID);
+        assert_eq!(export.version, EXPORT_VERSION);
+        let ids: Vec<&str> = export.backups.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_selection_errors() {
+        let backups = vec![backup("a", "11111111-1111-1111-1111-111111111111", None)];
+        assert!(matches!(
+            select_backups(&backups, Some(&["missing".to_string()])),
+            Err(BackupError::BackupNotFound(_))
+        ));
+        assert!(matches!(
+            select_backups(&backups, Some(&[])),
+            Err(BackupError::InvalidExport(_))
+        ));
+        assert!(matches!(
+            select_backups(&[], None),
+            Err(BackupError::InvalidExport(_))
+        ));
+    }
+
+    #[test]
+    fn test_csv_escapes_fields() {
+        let csv = render_csv(&[backup(
+            "a",
+            "11111111-1111-1111-1111-111111111111",
+            Some("=HYPERLINK(\"x\"), 测试"),
+        )]);
+        assert!(csv.starts_with("\u{feff}id,guid,source,timestamp,description\r\n"));
+        assert!(csv.ends_with(
+            "a,11111111-1111-1111-1111-111111111111,test,1700000000000,\"'=HYPERLINK(\"\"x\"\"), 测试\"\r\n"
+        ));
+    }
+
+    #[test]
+    fn test_reg_export_sets_machine_guid() {
+        let guid = "ABCDEF01-2345-6789-ABCD-EF0123456789";
+        let bytes = render_reg(&[backup("a", guid, None)]).unwrap();
+        assert_eq!(&bytes[..2], &[0xFF, 0xFE]);
+        let units: Vec<u16> = bytes[2..]
+            .chunks(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        let text = String::from_utf16(&units).unwrap();
+        assert!(text.starts_with("Windows Registry Editor Version 5.00\r\n"));
+        assert!(text.contains(r"[HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\Cryptography]"));
+        assert!(text.contains("\"MachineGuid\"=\"abcdef01-2345-6789-abcd-ef0123456789\""));
+
+        let two = [backup("a", guid, None), backup("b", guid, None)];
+        assert!(matches!(
+            render_reg(&two),
+            Err(BackupError::InvalidExport(_))
+        ));
+        let linux = [backup("c", "0123456789abcdef0123456789abcdef", None)];
+        assert!(matches!(
+            render_reg(&linux),
+            Err(BackupError::InvalidExport(_))
+        ));
+    }
+
+    #[test]
+    fn test_encrypted_export_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let backups = vec![backup("a", "11111111-1111-1111-1111-111111111111", None)];
+        let request = ExportRequest {
+            password: Some("correct horse".to_string()),
+            ..request(&dir, ExportFormat::Csv)
+        };
+
+        let result = export_to(&request, &backups, TEST_ITERATIONS).unwrap();
+        assert!(result.encrypted);
+        let content = fs::read(&request.path).unwrap();
+        assert!(!String::from_utf8_lossy(&content).contains("11111111-1111"));
+
+        let plain = decrypt_export(&content, "correct horse").unwrap();
+        assert_eq!(String::from_utf8(plain).unwrap(), render_csv(&backups));
+        assert!(matches!(
+            decrypt_export(&content, "wrong"),
+            Err(BackupError::InvalidExport(_))
+        ));
+
+        // 迭代次数或盐长度被篡改时不做密钥派生
+        let envelope: EncryptedExport = serde_json::from_slice(&content).unwrap();
+        for tampered in [
+            EncryptedExport {
+                iterations: u32::MAX,
+                ..envelope.clone()
+            },
+            EncryptedExport {
+                iterations: 1,
+                ..envelope.clone()
+            },
+            EncryptedExport {
+                salt: hex::encode([0u8; 4]),
+                ..envelope.clone()
+            },
+        ] {
+            let content = serde_json::to_vec(&tampered).unwrap();
+            assert!(matches!(
+                decrypt_export(&content, "correct horse"),
+                Err(BackupError::InvalidExport(_))
+            ));
+        }
+
+        let empty = ExportRequest {
+            password: Some(String::new()),
+            ..request
+        };
+        assert!(export_to(&empty, &backups, TEST_ITERATIONS).is_err());
+    }
+}
@@ -0,0 +1,800 @@
+//! Linux 特权写入辅助程序
+//!
+//! 图形界面和命令行以普通用户运行。修改 `/etc/machine-id` 时通过 `pkexec` 启动独立的
+//! `machineid-helper`，由 polkit 按 `com.machineid.manage.write-identity` 动作认证（策略文件见
+//! `packaging/linux`）。辅助程序只负责写入；备份、事件等仍由调用方以普通用户身份处理，
+//! 不会以 root 运行整个界面，也不会写入用户的数据目录。
+//!
+//! 调用方向辅助程序的标准输入写入一行 JSON 请求 [`HelperRequest`]，
+//! 从标准输出读取一行 [`ApiResponse<HelperOutcome>`]。请求只有两种：
+//!
+//! - `set_identifier`：将标识符设为新值，写入前把原值保存为快照
+//! - `restore_snapshot`：写回辅助程序自己保存的快照，供管理员在用户数据目录丢失时手动恢复；
+//!   本程序的恢复和撤销使用数据目录中的备份，经 `set_identifier` 写入
+//!
+//! 请求在执行前全部校验，未知字段、超长输入、不支持的标识符、无效的值或快照 ID 均被拒绝。
+//! 快照和请求日志保存在仅 root 可访问的目录中，调用方无法伪造。每个请求（包括被拒绝的）
+//! 都记录在日志中，包含调用者 UID、请求和结果；日志不可写时不执行请求。
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::api::{ApiError, ApiResponse, ErrorCode};
+use crate::i18n::{tr, Message};
+use crate::json_store::{self, now_secs};
+use crate::machine_id::{read_machine_guid, BackupError};
+use crate::platform::linux;
+use crate::profiles::MACHINE_GUID_IDENTIFIER;
+
+/// 辅助程序的安装路径，与 polkit 策略中的 `org.freedesktop.policykit.exec.path` 一致
+pub const HELPER_INSTALL_PATH: &str = "/usr/libexec/machineid-manage/machineid-helper";
+/// 辅助程序文件名，未安装时在主程序所在目录查找（开发构建）
+pub const HELPER_FILE_NAME: &str = "machineid-helper";
+/// 辅助程序保存快照的目录
+const HELPER_STATE_DIR: &str = "/var/lib/machineid-manage";
+/// 辅助程序的请求日志目录
+const HELPER_LOG_DIR: &str = "/var/log/machineid-manage";
+/// pkexec 的常见位置
+const PKEXEC_PATHS: [&str; 2] = ["/usr/bin/pkexec", "/bin/pkexec"];
+/// pkexec 在用户取消或未通过认证时的退出码
+const PKEXEC_NOT_AUTHORIZED: i32 = 126;
+/// 单个请求的最大长度（字节）
+const MAX_REQUEST_BYTES: u64 = 4096;
+/// 日志中记录的被拒绝输入的最大长度（字符）
+const MAX_LOGGED_INPUT_CHARS: usize = 256;
+/// 保留的快照数量，超出时删除最早的
+const MAX_SNAPSHOTS: usize = 50;
+/// 辅助程序支持写入的标识符
+const HELPER_IDENTIFIERS: &[&str] = &[MACHINE_GUID_IDENTIFIER];
+
+/// 发送给辅助程序的请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+pub enum HelperRequest {
+    /// 将标识符设为 `value`；机器码接受 GUID 或 machine-id 格式
+    SetIdentifier { identifier: String, value: String },
+    /// 写回辅助程序保存的快照
+    RestoreSnapshot { snapshot_id: String },
+}
+
+impl HelperRequest {
+    /// 校验请求，通过后才会执行
+    fn validate(&self) -> Result<(), ApiError> {
+        match self {
+            HelperRequest::SetIdentifier { identifier, value } => {
+                if !HELPER_IDENTIFIERS.contains(&identifier.as_str()) {
+                    return Err(ApiError::new(
+                        ErrorCode::InvalidRequest,
+                        tr(Message::HelperUnsupportedIdentifier { identifier }),
+                    ));
+                }
+                linux::to_machine_id(value)
+                    .map(|_| ())
+                    .map_err(|e| ApiError::from_backup_error(&e, e.to_string()))
+            }
+            HelperRequest::RestoreSnapshot { snapshot_id } => {
+                if is_snapshot_id(snapshot_id) {
+                    Ok(())
+                } else {
+                    Err(ApiError::new(
+                        ErrorCode::InvalidRequest,
+                        tr(Message::HelperInvalidSnapshotId { id: snapshot_id }),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// 辅助程序执行成功后的结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HelperOutcome {
+    /// 写入前的值，读取失败时为 None
+    pub previous: Option<String>,
+    /// 写入后的值
+    pub current: String,
+    /// 写入前保存的快照，可用 `restore_snapshot` 请求写回；写入前的值未知时为 None
+    pub snapshot_id: Option<String>,
+}
+
+/// 辅助程序保存的快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HelperSnapshot {
+    pub id: String,
+    pub identifier: String,
+    pub value: String,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SnapshotStore {
+    snapshots: Vec<HelperSnapshot>,
+}
+
+impl SnapshotStore {
+    fn get(&self, id: &str) -> Option<&HelperSnapshot> {
+        self.snapshots.iter().find(|s| s.id == id)
+    }
+
+    /// 新增快照，超出数量上限时删除最早的
+    fn push(&mut self, snapshot: HelperSnapshot) {
+        self.snapshots.push(snapshot);
+        let excess = self.snapshots.len().saturating_sub(MAX_SNAPSHOTS);
+        self.snapshots.drain(..excess);
+    }
+}
+
+fn generate_snapshot_id() -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!(
+        "snapshot_{}_{:08x}",
+        timestamp,
+        rand::thread_rng().next_u32()
+    )
+}
+
+/// 快照 ID 格式：`snapshot_<毫秒时间戳>_<8 位小写十六进制>`
+fn is_snapshot_id(id: &str) -> bool {
+    let Some((timestamp, suffix)) = id
+        .strip_prefix("snapshot_")
+        .and_then(|rest| rest.split_once('_'))
+    else {
+        return false;
+    };
+    (1..=20).contains(&timestamp.len())
+        && timestamp.bytes().all(|b| b.is_ascii_digit())
+        && suffix.len() == 8
+        && suffix
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+fn find_pkexec() -> Option<&'static str> {
+    PKEXEC_PATHS
+        .into_iter()
+        .find(|path| Path::new(path).is_file())
+}
+
+/// 查找辅助程序：优先使用安装路径，其次是主程序所在目录
+pub fn helper_path() -> Option<PathBuf> {
+    let installed = PathBuf::from(HELPER_INSTALL_PATH);
+    if installed.is_file() {
+        return Some(installed);
+    }
+    let exe = std::env::current_exe().ok()?;
+    let beside = exe.parent()?.join(HELPER_FILE_NAME);
+    beside.is_file().then_some(beside)
+}
+
+/// pkexec 和辅助程序是否都可用
+pub fn is_available() -> bool {
+    find_pkexec().is_some() && helper_path().is_some()
+}
+
+/// 将辅助程序返回的错误转换为本地错误
+fn backup_error_from(error: ApiError, request: &HelperRequest) -> BackupError {
+    match (error.code, request) {
+        (ErrorCode::InvalidGuidFormat, HelperRequest::SetIdentifier { value, .. }) => {
+            BackupError::InvalidGuidFormat(value.clone())
+        }
+        (ErrorCode::BackupNotFound, HelperRequest::RestoreSnapshot { snapshot_id }) => {
+            BackupError::BackupNotFound(snapshot_id.clone())
+        }
+        (ErrorCode::InsufficientPermissions, _) => BackupError::InsufficientPermissions,
+        (code, _) => BackupError::HelperFailed(format!("{}: {}", code.as_str(), error.message)),
+    }
+}
+
+fn helper_failed(message: Message) -> BackupError {
+    BackupError::HelperFailed(tr(message))
+}
+
+/// 解析辅助程序的输出
+fn parse_response(output: &[u8], request: &HelperRequest) -> Result<HelperOutcome, BackupError> {
+    let response: ApiResponse<HelperOutcome> = serde_json::from_slice(output).map_err(|e| {
+        BackupError::HelperFailed(tr(Message::HelperInvalidResponse {
+            reason: &e.to_string(),
+        }))
+    })?;
+    match (response.data, response.error) {
+        (Some(outcome), None) if response.success => Ok(outcome),
+        (_, Some(error)) => Err(backup_error_from(error, request)),
+        _ => Err(BackupError::HelperFailed(tr(Message::HelperEmptyResponse))),
+    }
+}
+
+/// 通过 pkexec 以 root 身份运行辅助程序处理请求，期间 polkit 可能弹出认证对话框
+///
+/// 请求先在本地校验，无效的请求不会触发认证。
+pub fn run(request: &HelperRequest) -> Result<HelperOutcome, BackupError> {
+    request
+        .validate()
+        .map_err(|e| backup_error_from(e, request))?;
+    let pkexec =
+        find_pkexec().ok_or_else(|| BackupError::HelperFailed(tr(Message::HelperPkexecMissing)))?;
+    let helper = helper_path().ok_or_else(|| {
+        BackupError::HelperFailed(tr(Message::HelperNotInstalled {
+            path: HELPER_INSTALL_PATH,
+        }))
+    })?;
+    info!("通过 pkexec 调用特权辅助程序: {:?}", request);
+
+    let mut child = Command::new(pkexec)
+        .arg(&helper)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            helper_failed(Message::HelperSpawnFailed {
+                reason: &e.to_string(),
+            })
+        })?;
+    let line = serde_json::to_string(request).map_err(|e| {
+        helper_failed(Message::HelperSendFailed {
+            reason: &e.to_string(),
+        })
+    })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "{}", line).map_err(|e| {
+            helper_failed(Message::HelperSendFailed {
+                reason: &e.to_string(),
+            })
+        })?;
+    }
+    let output = child.wait_with_output().map_err(|e| {
+        helper_failed(Message::HelperWaitFailed {
+            reason: &e.to_string(),
+        })
+    })?;
+
+    if output.status.code() == Some(PKEXEC_NOT_AUTHORIZED) {
+        warn!("polkit 认证被取消或未通过");
+        return Err(BackupError::InsufficientPermissions);
+    }
+    parse_response(&output.stdout, request)
+        .inspect(|outcome| {
+            if let Some(snapshot_id) = &outcome.snapshot_id {
+                info!("特权辅助程序已保存写入前的快照: {}", snapshot_id);
+            }
+        })
+        .inspect_err(|_| {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!(
+                "特权辅助程序执行失败 ({}): {}",
+                output.status,
+                stderr.trim()
+            );
+        })
+}
+
+/// 通过辅助程序写入机器码
+pub fn set_identity(value: &str) -> Result<HelperOutcome, BackupError> {
+    run(&HelperRequest::SetIdentifier {
+        identifier: MACHINE_GUID_IDENTIFIER.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// 辅助程序读写的文件，正式运行时均位于仅 root 可访问的目录
+struct HelperFiles {
+    snapshots: PathBuf,
+    log: PathBuf,
+}
+
+impl HelperFiles {
+    fn system() -> Self {
+        HelperFiles {
+            snapshots: Path::new(HELPER_STATE_DIR).join("helper-snapshots.json"),
+            log: Path::new(HELPER_LOG_DIR).join("helper.log"),
+        }
+    }
+}
+
+/// 辅助程序操作的机器码，测试中替换为内存中的值
+struct Identity<'a> {
+    read: &'a dyn Fn() -> Result<String, BackupError>,
+    write: &'a dyn Fn(&str) -> Result<(), BackupError>,
+}
+
+/// 为文件创建仅 root 可访问的上级目录
+fn ensure_private_dir(path: &Path) -> Result<(), BackupError> {
+    match path.parent() {
+        Some(dir) if !dir.exists() => fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .map_err(|e| BackupError::StorageError(format!("{}: {}", dir.display(), e))),
+        _ => Ok(()),
+    }
+}
+
+/// 写入前保存原值的快照，原值未知时不保存
+fn take_snapshot(
+    files: &HelperFiles,
+    identifier: &str,
+    value: Option<&str>,
+) -> Result<Option<String>, BackupError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    ensure_private_dir(&files.snapshots)?;
+    let id = generate_snapshot_id();
+    json_store::update(&files.snapshots, |store: &mut SnapshotStore| {
+        store.push(HelperSnapshot {
+            id: id.clone(),
+            identifier: identifier.to_string(),
+            value: value.to_string(),
+            created_at: now_secs(),
+        });
+        Ok(())
+    })?;
+    Ok(Some(id))
+}
+
+/// 执行已通过校验的请求
+fn execute(
+    request: &HelperRequest,
+    files: &HelperFiles,
+    identity: &Identity,
+) -> Result<HelperOutcome, BackupError> {
+    let (identifier, value) = match request {
+        HelperRequest::SetIdentifier { identifier, value } => (identifier.clone(), value.clone()),
+        HelperRequest::RestoreSnapshot { snapshot_id } => {
+            let store: SnapshotStore = json_store::load(&files.snapshots)?;
+            let snapshot = store
+                .get(snapshot_id)
+                .ok_or_else(|| BackupError::BackupNotFound(snapshot_id.clone()))?;
+            linux::to_machine_id(&snapshot.value)?;
+            (snapshot.identifier.clone(), snapshot.value.clone())
+        }
+    };
+    let previous = (identity.read)().ok();
+    let snapshot_id = take_snapshot(files, &identifier, previous.as_deref())?;
+    (identity.write)(&value)?;
+    Ok(HelperOutcome {
+        previous,
+        current: (identity.read)()?,
+        snapshot_id,
+    })
+}
+
+fn error_response(error: &BackupError) -> ApiResponse<HelperOutcome> {
+    ApiResponse::err(ApiError::from_backup_error(error, error.to_string()))
+}
+
+/// 读取一行请求，解析并校验；超过长度限制的请求视为无效
+fn read_request(input: impl BufRead) -> Result<HelperRequest, ApiError> {
+    let line = read_line(input)?;
+    let request: HelperRequest = serde_json::from_str(line.trim()).map_err(|e| {
+        ApiError::new(
+            ErrorCode::InvalidRequest,
+            tr(Message::HelperInvalidRequest {
+                reason: &e.to_string(),
+            }),
+        )
+    })?;
+    request.validate()?;
+    Ok(request)
+}
+
+fn read_line(input: impl BufRead) -> Result<String, ApiError> {
+    let mut line = String::new();
+    input
+        .take(MAX_REQUEST_BYTES)
+        .read_line(&mut line)
+        .map_err(|e| {
+            ApiError::new(
+                ErrorCode::InvalidRequest,
+                tr(Message::HelperReadRequestFailed {
+                    reason: &e.to_string(),
+                }),
+            )
+        })?;
+    if !line.ends_with('\n') && line.len() as u64 >= MAX_REQUEST_BYTES {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            tr(Message::HelperRequestTooLarge {
+                max: MAX_REQUEST_BYTES,
+            }),
+        ));
+    }
+    Ok(line)
+}
+
+/// 在请求日志中追加一行，附带时间和发起请求的用户（pkexec 设置的 `PKEXEC_UID`）
+fn append_log(path: &Path, mut entry: Value) -> Result<(), BackupError> {
+    entry["timestamp"] = json!(now_secs());
+    entry["uid"] = json!(std::env::var("PKEXEC_UID").ok());
+    ensure_private_dir(path)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| BackupError::StorageError(format!("{}: {}", path.display(), e)))?;
+    writeln!(file, "{}", entry).map_err(|e| BackupError::StorageError(e.to_string()))
+}
+
+/// 处理一个请求：收到后先记录日志，执行后再记录结果
+fn handle(
+    input: impl BufRead,
+    files: &HelperFiles,
+    identity: &Identity,
+) -> ApiResponse<HelperOutcome> {
+    let (raw, request) = match read_line(input) {
+        Ok(line) => {
+            let request = read_request(line.as_bytes());
+            (line, request)
+        }
+        Err(error) => (String::new(), Err(error)),
+    };
+    let request = match request {
+        Ok(request) => request,
+        Err(error) => {
+            let input: String = raw.trim().chars().take(MAX_LOGGED_INPUT_CHARS).collect();
+            let entry = json!({ "stage": "rejected", "input": input, "error": error.code });
+            if let Err(e) = append_log(&files.log, entry) {
+                eprintln!("machineid-helper: 写入请求日志失败: {}", e);
+            }
+            return ApiResponse::err(error);
+        }
+    };
+
+    let received = json!({ "stage": "received", "request": request });
+    if let Err(e) = append_log(&files.log, received) {
+        return error_response(&e);
+    }
+    let response = match execute(&request, files, identity) {
+        Ok(outcome) => ApiResponse::ok(outcome),
+        Err(e) => error_response(&e),
+    };
+    let completed = json!({
+        "stage": "completed",
+        "request": request,
+        "success": response.success,
+        "error": response.error.as_ref().map(|e| e.code),
+        "snapshot_id": response.data.as_ref().and_then(|o| o.snapshot_id.as_deref()),
+    });
+    if let Err(e) = append_log(&files.log, completed) {
+        eprintln!("machineid-helper: 写入请求日志失败: {}", e);
+    }
+    response
+}
+
+fn serve_with(
+    input: impl BufRead,
+    mut output: impl Write,
+    files: &HelperFiles,
+    identity: &Identity,
+) -> io::Result<()> {
+    let response = handle(input, files, identity);
+    writeln!(output, "{}", serde_json::to_string(&response)?)?;
+    output.flush()
+}
+
+/// 辅助程序入口：从 `input` 读取一个请求，处理后将响应写入 `output`
+pub fn serve(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    if !linux::is_root() {
+        let response = error_response(&BackupError::InsufficientPermissions);
+        writeln!(output, "{}", serde_json::to_string(&response)?)?;
+        return output.flush();
+    }
+    let identity = Identity {
+        read: &|| read_machine_guid().map(|id| id.guid),
+        write: &linux::write_machine_id,
+    };
+    serve_with(input, output, &HelperFiles::system(), &identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::cell::RefCell;
+
+    const GUID: &str = "550e8400-e29b-41d4-a716-446655440000";
+    const MACHINE_ID: &str = "550e8400e29b41d4a716446655440000";
+    const ORIGINAL: &str = "3d1219c7c4c5404aaa1f6d2a48adfda4";
+
+    fn set_request(value: &str) -> HelperRequest {
+        HelperRequest::SetIdentifier {
+            identifier: MACHINE_GUID_IDENTIFIER.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn temp_files(dir: &tempfile::TempDir) -> HelperFiles {
+        HelperFiles {
+            snapshots: dir.path().join("state/helper-snapshots.json"),
+            log: dir.path().join("log/helper.log"),
+        }
+    }
+
+    /// 在内存中的机器码上处理一个请求，返回响应和写入过的值
+    fn serve_memory(
+        files: &HelperFiles,
+        current: &RefCell<String>,
+        input: &[u8],
+    ) -> (ApiResponse<HelperOutcome>, Vec<String>) {
+        let written = RefCell::new(Vec::new());
+        let identity = Identity {
+            read: &|| Ok(current.borrow().clone()),
+            write: &|value| {
+                let machine_id = linux::to_machine_id(value)?;
+                written.borrow_mut().push(machine_id.clone());
+                *current.borrow_mut() = machine_id;
+                Ok(())
+            },
+        };
+        let mut output = Vec::new();
+        serve_with(input, &mut output, files, &identity).unwrap();
+        assert_eq!(output.iter().filter(|&&b| b == b'\n').count(), 1);
+        (
+            serde_json::from_slice(&output).unwrap(),
+            written.into_inner(),
+        )
+    }
+
+    fn log_lines(files: &HelperFiles) -> Vec<Value> {
+        fs::read_to_string(&files.log)
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let request = set_request(GUID);
+        let line = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            line,
+            r#"{"op":"set_identifier","identifier":"MachineGuid","value":"550e8400-e29b-41d4-a716-446655440000"}"#
+        );
+        assert_eq!(read_request(line.as_bytes()).unwrap(), request);
+
+        let restore =
+            r#"{"op":"restore_snapshot","snapshot_id":"snapshot_1700000000000_0123abcd"}"#;
+        assert!(read_request(restore.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_read_request_rejects_invalid_input() {
+        for input in [
+            "",
+            "not json",
+            r#"{"op":"delete_everything"}"#,
+            r#"{"op":"set_identifier","value":"550e8400e29b41d4a716446655440000"}"#,
+            r#"{"op":"set_identifier","identifier":"HwProfileGuid","value":"550e8400e29b41d4a716446655440000"}"#,
+            r#"{"op":"set_identifier","identifier":"MachineGuid","value":"550e8400e29b41d4a716446655440000","path":"/etc/shadow"}"#,
+            r#"{"op":"restore_snapshot","snapshot_id":"../../etc/passwd"}"#,
+            r#"{"op":"restore_snapshot","snapshot_id":"snapshot_1_0123ABCD"}"#,
+            r#"{"op":"set_identifier","identifier":"MachineGuid","value":"550e8400e29b41d4a716446655440000"} trailing"#,
+        ] {
+            let error = read_request(input.as_bytes()).unwrap_err();
+            assert_eq!(error.code, ErrorCode::InvalidRequest, "{}", input);
+        }
+        let invalid_value = read_request(
+            r#"{"op":"set_identifier","identifier":"MachineGuid","value":"bad"}"#.as_bytes(),
+        )
+        .unwrap_err();
+        assert_eq!(invalid_value.code, ErrorCode::InvalidGuidFormat);
+
+        let oversized = format!(
+            r#"{{"op":"set_identifier","identifier":"MachineGuid","value":"{}"}}"#,
+            "a".repeat(MAX_REQUEST_BYTES as usize)
+        );
+        assert!(read_request(oversized.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_is_snapshot_id() {
+        assert!(is_snapshot_id(&generate_snapshot_id()));
+        assert!(is_snapshot_id("snapshot_1700000000000_0123abcd"));
+        for invalid in [
+            "",
+            "snapshot_",
+            "snapshot__0123abcd",
+            "snapshot_1_0123abc",
+            "snapshot_1_0123abcd_",
+            "snapshot_x_0123abcd",
+            "backup_1_0123abcd",
+        ] {
+            assert!(!is_snapshot_id(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_set_and_restore_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = temp_files(&dir);
+        let current = RefCell::new(ORIGINAL.to_string());
+
+        let line = serde_json::to_string(&set_request(GUID)).unwrap();
+        let (response, written) = serve_memory(&files, &current, line.as_bytes());
+        let outcome = response.data.unwrap();
+        assert_eq!(outcome.previous.as_deref(), Some(ORIGINAL));
+        assert_eq!(outcome.current, MACHINE_ID);
+        assert_eq!(written, vec![MACHINE_ID.to_string()]);
+
+        let snapshot_id = outcome.snapshot_id.unwrap();
+        let restore = serde_json::to_string(&HelperRequest::RestoreSnapshot {
+            snapshot_id: snapshot_id.clone(),
+        })
+        .unwrap();
+        let (response, written) = serve_memory(&files, &current, restore.as_bytes());
+        let outcome = response.data.unwrap();
+        assert_eq!(outcome.current, ORIGINAL);
+        assert_eq!(written, vec![ORIGINAL.to_string()]);
+        // 恢复前的值同样保存了快照
+        assert!(outcome.snapshot_id.is_some_and(|id| id != snapshot_id));
+
+        let missing = r#"{"op":"restore_snapshot","snapshot_id":"snapshot_1_0123abcd"}"#;
+        let (response, written) = serve_memory(&files, &current, missing.as_bytes());
+        assert_eq!(response.error.unwrap().code, ErrorCode::BackupNotFound);
+        assert!(written.is_empty());
+
+        let (response, _) = serve_memory(&files, &current, b"{\"op\":\"reboot\"}\n");
+        assert_eq!(response.error.unwrap().code, ErrorCode::InvalidRequest);
+
+        // 合法请求在收到时和完成后各记录一行，被拒绝的请求记录一行
+        let log = log_lines(&files);
+        let stages: Vec<&str> = log.iter().map(|e| e["stage"].as_str().unwrap()).collect();
+        assert_eq!(
+            stages,
+            [
+                "received",
+                "completed",
+                "received",
+                "completed",
+                "received",
+                "completed",
+                "rejected"
+            ]
+        );
+        assert_eq!(log[1]["success"], true);
+        assert_eq!(log[5]["error"], "backup_not_found");
+        assert_eq!(log[6]["input"], "{\"op\":\"reboot\"}");
+    }
+
+    #[test]
+    fn test_snapshot_store_keeps_latest() {
+        let mut store = SnapshotStore::default();
+        for i in 0..MAX_SNAPSHOTS + 5 {
+            store.push(HelperSnapshot {
+                id: format!("snapshot_{}_0123abcd", i),
+                identifier: MACHINE_GUID_IDENTIFIER.to_string(),
+                value: ORIGINAL.to_string(),
+                created_at: i as u64,
+            });
+        }
+        assert_eq!(store.snapshots.len(), MAX_SNAPSHOTS);
+        assert!(store.get("snapshot_4_0123abcd").is_none());
+        assert!(store.get("snapshot_5_0123abcd").is_some());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let request = set_request("bad");
+        let ok = serde_json::to_vec(&ApiResponse::ok(HelperOutcome {
+            previous: None,
+            current: MACHINE_ID.to_string(),
+            snapshot_id: None,
+        }))
+        .unwrap();
+        assert_eq!(parse_response(&ok, &request).unwrap().current, MACHINE_ID);
+
+        let invalid = serde_json::to_vec(&error_response(&BackupError::InvalidGuidFormat(
+            "bad".to_string(),
+        )))
+        .unwrap();
+        assert!(matches!(
+            parse_response(&invalid, &request),
+            Err(BackupError::InvalidGuidFormat(value)) if value == "bad"
+        ));
+
+        let denied =
+            serde_json::to_vec(&error_response(&BackupError::InsufficientPermissions)).unwrap();
+        assert!(matches!(
+            parse_response(&denied, &request),
+            Err(BackupError::InsufficientPermissions)
+        ));
+        assert!(matches!(
+            parse_response(b"", &request),
+            Err(BackupError::HelperFailed(_))
+        ));
+    }
+
+    /// 对合法请求做随机变异（翻转位、截断、插入记号或随机字节），或生成完全随机的输入
+    fn mutate(rng: &mut StdRng, seeds: &[String]) -> Vec<u8> {
+        const TOKENS: &[&str] = &[
+            "\"",
+            "{",
+            "}",
+            ",",
+            ":",
+            "\\u0000",
+            "\n",
+            "null",
+            "-",
+            "0",
+            "../",
+            "\"op\":\"restore_snapshot\"",
+            "\"extra\":1",
+            "机",
+        ];
+        if rng.gen_ratio(1, 10) {
+            let len = rng.gen_range(0..256);
+            return (0..len).map(|_| rng.gen()).collect();
+        }
+        let mut bytes = seeds[rng.gen_range(0..seeds.len())].clone().into_bytes();
+        for _ in 0..rng.gen_range(1..4) {
+            let at = rng.gen_range(0..=bytes.len());
+            match rng.gen_range(0..4) {
+                0 if !bytes.is_empty() => {
+                    let i = rng.gen_range(0..bytes.len());
+                    bytes[i] ^= 1 << rng.gen_range(0..8);
+                }
+                1 => bytes.truncate(at),
+                2 => {
+                    let token = TOKENS[rng.gen_range(0..TOKENS.len())];
+                    bytes.splice(at..at, token.bytes());
+                }
+                _ => bytes.insert(at, rng.gen()),
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_fuzz_protocol() {
+        const ITERATIONS: usize = 2000;
+        let dir = tempfile::tempdir().unwrap();
+        let files = temp_files(&dir);
+        let seeds = [
+            serde_json::to_string(&set_request(GUID)).unwrap(),
+            serde_json::to_string(&set_request(MACHINE_ID)).unwrap(),
+            r#"{"op":"restore_snapshot","snapshot_id":"snapshot_1700000000000_0123abcd"}"#
+                .to_string(),
+        ];
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        let current = RefCell::new(ORIGINAL.to_string());
+
+        let mut accepted = 0;
+        for _ in 0..ITERATIONS {
+            let input = mutate(&mut rng, &seeds);
+            // 解析不会 panic，接受的请求都能通过校验并原样往返
+            if let Ok(request) = read_request(input.as_slice()) {
+                assert!(request.validate().is_ok());
+                let line = serde_json::to_string(&request).unwrap();
+                assert_eq!(read_request(line.as_bytes()).unwrap(), request);
+                accepted += 1;
+            }
+            // 完整处理流程总是输出一行响应，只写入合法的机器码
+            let (response, written) = serve_memory(&files, &current, &input);
+            assert_eq!(response.success, response.data.is_some());
+            for value in &written {
+                assert_eq!(&linux::to_machine_id(value).unwrap(), value);
+            }
+        }
+        assert!(accepted > 0, "变异后的输入中应有部分仍是合法请求");
+        assert!(accepted < ITERATIONS);
+
+        // 每个请求至少记录一行
+        assert!(log_lines(&files).len() >= ITERATIONS);
+    }
+}
@@ -0,0 +1,1232 @@
+//! 后端消息的多语言支持
+//!
+//! 错误描述、自动生成的备份描述以及命令的提示信息统一在此处按语言渲染。
+//! 当前语言按以下顺序确定：运行时设置（[`set_locale`]）、`MACHINEID_LOCALE` 环境变量、
+//! 操作系统语言，均无法识别时使用简体中文。
+
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::machine_id::{AutoDescription, BackupError};
+
+/// 指定语言的环境变量
+pub const LOCALE_ENV: &str = "MACHINEID_LOCALE";
+
+lazy_static! {
+    /// 运行时设置的语言，优先于环境变量和系统语言
+    static ref LOCALE_OVERRIDE: RwLock<Option<Locale>> = RwLock::new(None);
+}
+
+/// 支持的语言
+/// 反序列化时兼容 `zh_CN.UTF-8`、`en-US` 等写法，见 [`Locale::from_tag`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum Locale {
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Locale {
+    /// 所有支持的语言
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::En];
+
+    /// 语言标签
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::En => "en",
+        }
+    }
+
+    /// 解析语言标签，兼容 `zh_CN.UTF-8`、`en-US` 等写法
+    /// 中文以外的语言一律使用英文；`C`/`POSIX` 等不含语言信息的值返回 None
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "" | "c" | "posix" => None,
+            "zh" => Some(Locale::ZhCn),
+            _ => Some(Locale::En),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let tag = String::deserialize(deserializer)?;
+        Locale::from_tag(&tag)
+            .ok_or_else(|| serde::de::Error::custom(format!("unsupported locale: {}", tag)))
+    }
+}
+
+/// 设置运行时语言，传入 None 时恢复自动检测
+pub fn set_locale(locale: Option<Locale>) {
+    *LOCALE_OVERRIDE.write().unwrap_or_else(|e| e.into_inner()) = locale;
+}
+
+/// 当前语言
+pub fn current_locale() -> Locale {
+    if let Some(locale) = *LOCALE_OVERRIDE.read().unwrap_or_else(|e| e.into_inner()) {
+        return locale;
+    }
+    detect_locale()
+}
+
+/// 根据环境变量和系统语言检测语言
+pub fn detect_locale() -> Locale {
+    std::env::var(LOCALE_ENV)
+        .ok()
+        .and_then(|tag| Locale::from_tag(&tag))
+        .or_else(|| sys_locale::get_locale().and_then(|tag| Locale::from_tag(&tag)))
+        .unwrap_or(Locale::ZhCn)
+}
+
+/// 按当前语言渲染消息
+pub fn tr(message: Message<'_>) -> String {
+    message.render(current_locale())
+}
+
+/// 可本地化的提示信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message<'a> {
+    // 命令结果
+    WriteSucceeded {
+        guid: &'a str,
+    },
+    GenerateSucceeded {
+        guid: &'a str,
+    },
+    RestoreSucceeded {
+        guid: &'a str,
+    },
+    ProfileApplied {
+        name: &'a str,
+    },
+
+    // 计划任务
+    ScheduleRotated {
+        guid: &'a str,
+    },
+    ScheduleRestored {
+        guid: &'a str,
+    },
+    ScheduleIntervalZero,
+    ScheduleIntervalTooLong {
+        max: u64,
+    },
+    CronFieldCount {
+        actual: usize,
+    },
+    CronFieldInvalid {
+        field: &'a str,
+    },
+
+    // 身份配置
+    ProfileNameEmpty,
+    ProfileNameWhitespace,
+    ProfileNameTooLong {
+        max: usize,
+    },
+    ProfileNoIdentifiers,
+    ProfileUnsupportedIdentifier {
+        name: &'a str,
+    },
+    ProfileMissingValue {
+        name: &'a str,
+    },
+
+    // 预览令牌
+    PreviewTokenMalformed,
+    PreviewTokenSignatureMalformed,
+    PreviewTokenSignatureMismatch,
+    PreviewTokenExpiryMalformed,
+    PreviewTokenExpired,
+    PreviewTokenUsed,
+
+    // 数据目录
+    DataDirCreateFailed {
+        reason: &'a str,
+    },
+    DataDirUnknown,
+    DataDirExePathUnavailable {
+        reason: &'a str,
+    },
+    DataDirExeDirUnavailable,
+    DataDirAppDataUnavailable,
+    DataDirHomeUnavailable,
+    DataDirUnsupportedOs,
+    GuidBlocklistReadFailed {
+        reason: &'a str,
+    },
+
+    // 本地接口（HTTP / JSON-RPC）
+    RequestLineInvalid {
+        line: &'a str,
+    },
+    RequestHeaderInvalid {
+        line: &'a str,
+    },
+    RequestHeadIncomplete,
+    RequestTimeout,
+    ContentLengthInvalid,
+    RequestBodyTooLarge,
+    RequestBodyInvalid {
+        reason: &'a str,
+    },
+    EndpointNotFound,
+    Unauthorized,
+    InternalError,
+    RpcParamNotString {
+        name: &'a str,
+    },
+    RpcParamMissing {
+        name: &'a str,
+    },
+    RpcParamsNotObject,
+    RpcUnknownMethod {
+        name: &'a str,
+    },
+    RpcVersionInvalid,
+    RpcSocketInUse {
+        path: &'a str,
+    },
+
+    // 配置
+    SettingsDataDirNotAbsolute {
+        path: &'a str,
+    },
+    SettingsDescriptionLengthOutOfRange {
+        max: usize,
+    },
+    SettingsHttpPortZero,
+    SettingsWatchIntervalOutOfRange {
+        max: u64,
+    },
+    SettingsLogSizeOutOfRange {
+        max: u64,
+    },
+    SettingsLogFilesOutOfRange {
+        max: usize,
+    },
+    SettingsUserFileUnavailable,
+
+    // 导出
+    ExportNothingSelected,
+    ExportRegNeedsSingleBackup {
+        count: usize,
+    },
+    ExportRegNotMachineGuid {
+        id: &'a str,
+    },
+    ExportPasswordEmpty,
+    ExportDecryptFailed,
+
+    // 审计日志
+    AuditLogUnavailable,
+    AuditLogNotRecorded,
+
+    // 特权辅助程序与提权
+    HelperUnsupportedIdentifier {
+        identifier: &'a str,
+    },
+    HelperInvalidSnapshotId {
+        id: &'a str,
+    },
+    HelperInvalidRequest {
+        reason: &'a str,
+    },
+    HelperReadRequestFailed {
+        reason: &'a str,
+    },
+    HelperRequestTooLarge {
+        max: u64,
+    },
+    HelperInvalidResponse {
+        reason: &'a str,
+    },
+    HelperEmptyResponse,
+    HelperPkexecMissing,
+    HelperNotInstalled {
+        path: &'a str,
+    },
+    HelperSpawnFailed {
+        reason: &'a str,
+    },
+    HelperSendFailed {
+        reason: &'a str,
+    },
+    HelperWaitFailed {
+        reason: &'a str,
+    },
+    ElevationPolkitOnDemand,
+    ElevationHelperUnavailable {
+        path: &'a str,
+    },
+    ElevationExePathUnavailable {
+        reason: &'a str,
+    },
+    ElevationWorkingDirUnavailable {
+        reason: &'a str,
+    },
+    ElevationCancelled,
+    ElevationLaunchFailed {
+        code: u32,
+    },
+
+    // 托盘
+    TrayCurrentGuid {
+        guid: &'a str,
+    },
+    TrayGuidUnavailable,
+    TrayPermissionGranted,
+    TrayPermissionDenied,
+    TrayBackupNow,
+    TrayGenerateAndApply,
+    TrayRestoreLast,
+    TrayShowWindow,
+    TrayQuit,
+    TrayConfirmTitle,
+    TrayConfirmGenerate {
+        current: &'a str,
+    },
+    TrayConfirmRestore {
+        current: &'a str,
+        guid: &'a str,
+    },
+    TrayConfirmOk,
+    TrayConfirmCancel,
+
+    // 命令行
+    CliUsage,
+    CliOptionNeedsValue {
+        option: &'a str,
+    },
+    CliInvalidPort {
+        value: &'a str,
+    },
+    CliInvalidOptionValue {
+        option: &'a str,
+        value: &'a str,
+    },
+    CliUnknownOption {
+        option: &'a str,
+    },
+    CliMissingSubcommand,
+    CliMissingArgument {
+        subcommand: &'a str,
+        name: &'a str,
+    },
+    CliUnknownSubcommand {
+        name: &'a str,
+    },
+    CliExtraArgument {
+        value: &'a str,
+    },
+    CliBackupIdArg,
+    CliDataDirModeArg,
+    CliBackedUp {
+        id: &'a str,
+        guid: &'a str,
+    },
+    CliBackupSkipped,
+    CliRestored {
+        from: &'a str,
+        to: &'a str,
+    },
+    CliWritten {
+        from: &'a str,
+        to: &'a str,
+    },
+    CliGenerated {
+        from: &'a str,
+        to: &'a str,
+    },
+    CliDeleted {
+        id: &'a str,
+    },
+    CliVerifyMismatch {
+        current: &'a str,
+    },
+    CliVerifyOk {
+        guid: &'a str,
+    },
+    CliAuditVerified {
+        entries: u64,
+    },
+    CliAuditTampered {
+        line: u64,
+        verified: u64,
+    },
+    CliDataDir {
+        path: &'a str,
+        mode: &'a str,
+    },
+    CliDiagnosticsExported {
+        path: &'a str,
+        redacted: bool,
+    },
+    CliDataMigrated {
+        from: &'a str,
+        to: &'a str,
+        count: usize,
+    },
+    CliRpcListening {
+        path: &'a str,
+    },
+    CliHttpListening {
+        port: u16,
+    },
+    CliError {
+        message: &'a str,
+    },
+}
+
+impl Message<'_> {
+    pub fn render(&self, locale: Locale) -> String {
+        match locale {
+            Locale::ZhCn => self.zh_cn(),
+            Locale::En => self.en(),
+        }
+    }
+
+    fn zh_cn(&self) -> String {
+        match *self {
+            Message::WriteSucceeded { guid } => format!("成功将 MachineGuid 替换为: {}", guid),
+            Message::GenerateSucceeded { guid } => {
+                format!("成功生成并替换 MachineGuid: {}", guid)
+            }
+            Message::RestoreSucceeded { guid } => format!("恢复成功: {}", guid),
+            Message::ProfileApplied { name } => format!("已切换到身份配置: {}", name),
+            Message::ScheduleRotated { guid } => format!("已轮换为: {}", guid),
+            Message::ScheduleRestored { guid } => format!("已恢复为: {}", guid),
+            Message::ScheduleIntervalZero => "执行间隔必须大于 0 分钟".to_string(),
+            Message::ScheduleIntervalTooLong { max } => {
+                format!("执行间隔不能超过 {} 分钟", max)
+            }
+            Message::CronFieldCount { actual } => {
+                format!("cron 表达式需要 5 个字段，实际为 {} 个", actual)
+            }
+            Message::CronFieldInvalid { field } => format!("无效的 cron 字段: {}", field),
+            Message::ProfileNameEmpty => "配置名称不能为空".to_string(),
+            Message::ProfileNameWhitespace => "配置名称首尾不能包含空白字符".to_string(),
+            Message::ProfileNameTooLong { max } => format!("配置名称不能超过 {} 个字符", max),
+            Message::ProfileNoIdentifiers => "配置至少需要包含一个标识符".to_string(),
+            Message::ProfileUnsupportedIdentifier { name } => {
+                format!("不支持的标识符: {}", name)
+            }
+            Message::ProfileMissingValue { name } => format!("配置缺少 {} 值", name),
+            Message::PreviewTokenMalformed => "格式错误".to_string(),
+            Message::PreviewTokenSignatureMalformed => "签名格式错误".to_string(),
+            Message::PreviewTokenSignatureMismatch => "签名不匹配".to_string(),
+            Message::PreviewTokenExpiryMalformed => "过期时间格式错误".to_string(),
+            Message::PreviewTokenExpired => "令牌已过期".to_string(),
+            Message::PreviewTokenUsed => "令牌已被使用".to_string(),
+            Message::DataDirCreateFailed { reason } => format!("创建备份目录失败: {}", reason),
+            Message::DataDirUnknown => "无法确定数据目录".to_string(),
+            Message::DataDirExePathUnavailable { reason } => {
+                format!("无法获取程序路径: {}", reason)
+            }
+            Message::DataDirExeDirUnavailable => "无法获取程序目录".to_string(),
+            Message::DataDirAppDataUnavailable => "无法获取 APPDATA 目录".to_string(),
+            Message::DataDirHomeUnavailable => "无法获取用户主目录".to_string(),
+            Message::DataDirUnsupportedOs => "不支持的操作系统".to_string(),
+            Message::GuidBlocklistReadFailed { reason } => {
+                format!("读取 GUID 黑名单失败: {}", reason)
+            }
+            Message::RequestLineInvalid { line } => format!("无效的请求行: {}", line),
+            Message::RequestHeaderInvalid { line } => format!("无效的请求头: {}", line),
+            Message::RequestHeadIncomplete => "请求头不完整或过长".to_string(),
+            Message::RequestTimeout => "读取请求超时".to_string(),
+            Message::ContentLengthInvalid => "无效的 Content-Length".to_string(),
+            Message::RequestBodyTooLarge => "请求体过大".to_string(),
+            Message::RequestBodyInvalid { reason } => format!("请求体格式无效: {}", reason),
+            Message::EndpointNotFound => "接口不存在".to_string(),
+            Message::Unauthorized => "未授权，请携带有效的访问令牌".to_string(),
+            Message::InternalError => "内部错误".to_string(),
+            Message::RpcParamNotString { name } => format!("参数 {} 必须是字符串", name),
+            Message::RpcParamMissing { name } => format!("缺少参数 {}", name),
+            Message::RpcParamsNotObject => "params 必须是对象".to_string(),
+            Message::RpcUnknownMethod { name } => format!("未知方法: {}", name),
+            Message::RpcVersionInvalid => "jsonrpc 必须为 \"2.0\"".to_string(),
+            Message::RpcSocketInUse { path } => format!("套接字已被占用: {}", path),
+            Message::SettingsDataDirNotAbsolute { path } => {
+                format!("数据目录必须为绝对路径: {}", path)
+            }
+            Message::SettingsDescriptionLengthOutOfRange { max } => {
+                format!("描述长度上限应在 1 到 {} 之间", max)
+            }
+            Message::SettingsHttpPortZero => "HTTP 端口不能为 0".to_string(),
+            Message::SettingsWatchIntervalOutOfRange { max } => {
+                format!("机器码检查间隔应在 1 到 {} 秒之间", max)
+            }
+            Message::SettingsLogSizeOutOfRange { max } => {
+                format!("日志文件大小上限应在 1 到 {} MB 之间", max)
+            }
+            Message::SettingsLogFilesOutOfRange { max } => {
+                format!("日志文件保留数量应在 1 到 {} 之间", max)
+            }
+            Message::SettingsUserFileUnavailable => "无法确定用户配置文件位置".to_string(),
+            Message::ExportNothingSelected => "没有可导出的备份".to_string(),
+            Message::ExportRegNeedsSingleBackup { count } => {
+                format!(".reg 文件只能包含一个备份，当前选择了 {} 个", count)
+            }
+            Message::ExportRegNotMachineGuid { id } => {
+                format!("备份 {} 不是 Windows MachineGuid，无法导出为 .reg 文件", id)
+            }
+            Message::ExportPasswordEmpty => "加密密码不能为空".to_string(),
+            Message::ExportDecryptFailed => "密码错误或文件已损坏，无法解密".to_string(),
+            Message::AuditLogUnavailable => {
+                "审计日志不可写，已取消修改机器码，请检查数据目录的权限和磁盘空间".to_string()
+            }
+            Message::AuditLogNotRecorded => {
+                "机器码已修改，但未能记入审计日志，请检查数据目录的权限和磁盘空间".to_string()
+            }
+            Message::HelperUnsupportedIdentifier { identifier } => {
+                format!("不支持的标识符: {}", identifier)
+            }
+            Message::HelperInvalidSnapshotId { id } => format!("无效的快照 ID: {}", id),
+            Message::HelperInvalidRequest { reason } => format!("请求无效: {}", reason),
+            Message::HelperReadRequestFailed { reason } => format!("读取请求失败: {}", reason),
+            Message::HelperRequestTooLarge { max } => format!("请求超过 {} 字节", max),
+            Message::HelperInvalidResponse { reason } => {
+                format!("无法解析辅助程序的响应: {}", reason)
+            }
+            Message::HelperEmptyResponse => "辅助程序返回了空响应".to_string(),
+            Message::HelperPkexecMissing => "未找到 pkexec，请安装 polkit".to_string(),
+            Message::HelperNotInstalled { path } => format!("未找到 {}", path),
+            Message::HelperSpawnFailed { reason } => format!("启动 pkexec 失败: {}", reason),
+            Message::HelperSendFailed { reason } => format!("发送请求失败: {}", reason),
+            Message::HelperWaitFailed { reason } => format!("等待辅助程序失败: {}", reason),
+            Message::ElevationPolkitOnDemand => {
+                "写入机器码时将通过 polkit 请求授权，无需重启程序".to_string()
+            }
+            Message::ElevationHelperUnavailable { path } => {
+                format!("未找到 pkexec 或 {}", path)
+            }
+            Message::ElevationExePathUnavailable { reason } => {
+                format!("无法获取当前程序路径: {}", reason)
+            }
+            Message::ElevationWorkingDirUnavailable { reason } => {
+                format!("无法获取当前工作目录: {}", reason)
+            }
+            Message::ElevationCancelled => "用户取消了权限提升请求".to_string(),
+            Message::ElevationLaunchFailed { code } => format!(
+                "无法以管理员身份启动: {} (错误码: {})",
+                shell_execute_error(code).0,
+                code
+            ),
+            Message::TrayCurrentGuid { guid } => format!("当前机器码: {}（点击复制）", guid),
+            Message::TrayGuidUnavailable => "无法读取机器码".to_string(),
+            Message::TrayPermissionGranted => "权限: 管理员".to_string(),
+            Message::TrayPermissionDenied => "权限: 受限（无法修改机器码）".to_string(),
+            Message::TrayBackupNow => "立即备份".to_string(),
+            Message::TrayGenerateAndApply => "生成并应用随机机器码".to_string(),
+            Message::TrayRestoreLast => "恢复上一个备份".to_string(),
+            Message::TrayShowWindow => "显示主窗口".to_string(),
+            Message::TrayQuit => "退出".to_string(),
+            Message::TrayConfirmTitle => "确认修改机器码".to_string(),
+            Message::TrayConfirmGenerate { current } => format!(
+                "将把机器码 {} 替换为新生成的随机值，写入前会自动备份当前值。是否继续？",
+                current
+            ),
+            Message::TrayConfirmRestore { current, guid } => {
+                format!("将把机器码从 {} 恢复为备份中的 {}。是否继续？", current, guid)
+            }
+            Message::TrayConfirmOk => "继续".to_string(),
+            Message::TrayConfirmCancel => "取消".to_string(),
+            Message::CliUsage => CLI_USAGE_ZH_CN.to_string(),
+            Message::CliOptionNeedsValue { option } => format!("{} 需要一个参数", option),
+            Message::CliInvalidPort { value } => format!("无效的端口: {}", value),
+            Message::CliInvalidOptionValue { option, value } => {
+                format!("{} 的取值无效: {}", option, value)
+            }
+            Message::CliUnknownOption { option } => format!("未知选项: {}", option),
+            Message::CliMissingSubcommand => "缺少子命令".to_string(),
+            Message::CliMissingArgument { subcommand, name } => {
+                format!("{} 需要参数 <{}>", subcommand, name)
+            }
+            Message::CliUnknownSubcommand { name } => format!("未知子命令: {}", name),
+            Message::CliExtraArgument { value } => format!("多余的参数: {}", value),
+            Message::CliBackupIdArg => "备份ID".to_string(),
+            Message::CliDataDirModeArg => "模式".to_string(),
+            Message::CliBackedUp { id, guid } => format!("已备份: {}\t{}", id, guid),
+            Message::CliBackupSkipped => "当前机器码已有备份，跳过".to_string(),
+            Message::CliRestored { from, to } => format!("已恢复: {} -> {}", from, to),
+            Message::CliWritten { from, to } => format!("已写入: {} -> {}", from, to),
+            Message::CliGenerated { from, to } => format!("已生成并写入: {} -> {}", from, to),
+            Message::CliDeleted { id } => format!("已删除备份: {}", id),
+            Message::CliVerifyMismatch { current } => format!("不一致: 当前 {}", current),
+            Message::CliVerifyOk { guid } => format!("校验通过: {}", guid),
+            Message::CliAuditVerified { entries } => {
+                format!("审计日志完整，共 {} 条记录", entries)
+            }
+            Message::CliAuditTampered { line, verified } => format!(
+                "审计日志在第 {} 行处被篡改或损坏，此前 {} 条记录校验通过",
+                line, verified
+            ),
+            Message::CliDataDir { path, mode } => format!("{}\t{}", path, mode),
+            Message::CliDiagnosticsExported { path, redacted } => format!(
+                "诊断包已导出{}: {}",
+                if redacted { "（机器码已脱敏）" } else { "" },
+                path
+            ),
+            Message::CliDataMigrated { from, to, count } => {
+                format!("已迁移 {} 项数据: {} -> {}", count, from, to)
+            }
+            Message::CliRpcListening { path } => format!("JSON-RPC 服务监听于 {}", path),
+            Message::CliHttpListening { port } => format!(
+                "HTTP 接口监听于 http://127.0.0.1:{}，访问令牌保存在应用数据目录的 api_token 文件中",
+                port
+            ),
+            Message::CliError { message } => format!("错误: {}", message),
+        }
+    }
+
+    fn en(&self) -> String {
+        match *self {
+            Message::WriteSucceeded { guid } => format!("MachineGuid replaced with: {}", guid),
+            Message::GenerateSucceeded { guid } => {
+                format!("Generated and applied MachineGuid: {}", guid)
+            }
+            Message::RestoreSucceeded { guid } => format!("Restored: {}", guid),
+            Message::ProfileApplied { name } => format!("Switched to identity profile: {}", name),
+            Message::ScheduleRotated { guid } => format!("Rotated to: {}", guid),
+            Message::ScheduleRestored { guid } => format!("Restored to: {}", guid),
+            Message::ScheduleIntervalZero => "Interval must be greater than 0 minutes".to_string(),
+            Message::ScheduleIntervalTooLong { max } => {
+                format!("Interval must not exceed {} minutes", max)
+            }
+            Message::CronFieldCount { actual } => {
+                format!("Cron expression needs 5 fields, got {}", actual)
+            }
+            Message::CronFieldInvalid { field } => format!("Invalid cron field: {}", field),
+            Message::ProfileNameEmpty => "Profile name must not be empty".to_string(),
+            Message::ProfileNameWhitespace => {
+                "Profile name must not start or end with whitespace".to_string()
+            }
+            Message::ProfileNameTooLong { max } => {
+                format!("Profile name must not exceed {} characters", max)
+            }
+            Message::ProfileNoIdentifiers => {
+                "Profile must contain at least one identifier".to_string()
+            }
+            Message::ProfileUnsupportedIdentifier { name } => {
+                format!("Unsupported identifier: {}", name)
+            }
+            Message::ProfileMissingValue { name } => format!("Profile has no {} value", name),
+            Message::PreviewTokenMalformed => "malformed token".to_string(),
+            Message::PreviewTokenSignatureMalformed => "malformed signature".to_string(),
+            Message::PreviewTokenSignatureMismatch => "signature mismatch".to_string(),
+            Message::PreviewTokenExpiryMalformed => "malformed expiry time".to_string(),
+            Message::PreviewTokenExpired => "the token has expired".to_string(),
+            Message::PreviewTokenUsed => "the token has already been used".to_string(),
+            Message::DataDirCreateFailed { reason } => {
+                format!("Failed to create the data directory: {}", reason)
+            }
+            Message::DataDirUnknown => "Could not determine the data directory".to_string(),
+            Message::DataDirExePathUnavailable { reason } => {
+                format!("Could not determine the program path: {}", reason)
+            }
+            Message::DataDirExeDirUnavailable => {
+                "Could not determine the program directory".to_string()
+            }
+            Message::DataDirAppDataUnavailable => {
+                "Could not determine the APPDATA directory".to_string()
+            }
+            Message::DataDirHomeUnavailable => {
+                "Could not determine the home directory".to_string()
+            }
+            Message::DataDirUnsupportedOs => "Unsupported operating system".to_string(),
+            Message::GuidBlocklistReadFailed { reason } => {
+                format!("Failed to read the GUID blocklist: {}", reason)
+            }
+            Message::RequestLineInvalid { line } => format!("Invalid request line: {}", line),
+            Message::RequestHeaderInvalid { line } => format!("Invalid request header: {}", line),
+            Message::RequestHeadIncomplete => {
+                "Request headers are incomplete or too large".to_string()
+            }
+            Message::RequestTimeout => "Timed out reading the request".to_string(),
+            Message::ContentLengthInvalid => "Invalid Content-Length".to_string(),
+            Message::RequestBodyTooLarge => "Request body too large".to_string(),
+            Message::RequestBodyInvalid { reason } => format!("Invalid request body: {}", reason),
+            Message::EndpointNotFound => "Endpoint not found".to_string(),
+            Message::Unauthorized => "Unauthorized, a valid access token is required".to_string(),
+            Message::InternalError => "Internal error".to_string(),
+            Message::RpcParamNotString { name } => format!("Parameter {} must be a string", name),
+            Message::RpcParamMissing { name } => format!("Missing parameter {}", name),
+            Message::RpcParamsNotObject => "params must be an object".to_string(),
+            Message::RpcUnknownMethod { name } => format!("Unknown method: {}", name),
+            Message::RpcVersionInvalid => "jsonrpc must be \"2.0\"".to_string(),
+            Message::RpcSocketInUse { path } => format!("The socket is already in use: {}", path),
+            Message::SettingsDataDirNotAbsolute { path } => {
+                format!("The data directory must be an absolute path: {}", path)
+            }
+            Message::SettingsDescriptionLengthOutOfRange { max } => {
+                format!("The description length limit must be between 1 and {}", max)
+            }
+            Message::SettingsHttpPortZero => "The HTTP port must not be 0".to_string(),
+            Message::SettingsWatchIntervalOutOfRange { max } => format!(
+                "The identity check interval must be between 1 and {} seconds",
+                max
+            ),
+            Message::SettingsLogSizeOutOfRange { max } => {
+                format!("The log file size limit must be between 1 and {} MB", max)
+            }
+            Message::SettingsLogFilesOutOfRange { max } => {
+                format!("The number of kept log files must be between 1 and {}", max)
+            }
+            Message::SettingsUserFileUnavailable => {
+                "Could not determine the user configuration file location".to_string()
+            }
+            Message::ExportNothingSelected => "There are no backups to export".to_string(),
+            Message::ExportRegNeedsSingleBackup { count } => format!(
+                "A .reg file can contain only one backup, {} are selected",
+                count
+            ),
+            Message::ExportRegNotMachineGuid { id } => format!(
+                "Backup {} is not a Windows MachineGuid and cannot be exported as a .reg file",
+                id
+            ),
+            Message::ExportPasswordEmpty => "The encryption password must not be empty".to_string(),
+            Message::ExportDecryptFailed => {
+                "Wrong password or corrupted file, could not decrypt".to_string()
+            }
+            Message::AuditLogUnavailable => {
+                "The audit log is not writable, the machine ID was not changed; please check the permissions and free space of the data directory".to_string()
+            }
+            Message::AuditLogNotRecorded => {
+                "The machine ID was changed but could not be recorded in the audit log; please check the permissions and free space of the data directory".to_string()
+            }
+            Message::HelperUnsupportedIdentifier { identifier } => {
+                format!("Unsupported identifier: {}", identifier)
+            }
+            Message::HelperInvalidSnapshotId { id } => format!("Invalid snapshot ID: {}", id),
+            Message::HelperInvalidRequest { reason } => format!("Invalid request: {}", reason),
+            Message::HelperReadRequestFailed { reason } => {
+                format!("Failed to read the request: {}", reason)
+            }
+            Message::HelperRequestTooLarge { max } => {
+                format!("The request exceeds {} bytes", max)
+            }
+            Message::HelperInvalidResponse { reason } => {
+                format!("Could not parse the helper response: {}", reason)
+            }
+            Message::HelperEmptyResponse => "The helper returned an empty response".to_string(),
+            Message::HelperPkexecMissing => "pkexec not found, please install polkit".to_string(),
+            Message::HelperNotInstalled { path } => format!("{} not found", path),
+            Message::HelperSpawnFailed { reason } => format!("Failed to start pkexec: {}", reason),
+            Message::HelperSendFailed { reason } => {
+                format!("Failed to send the request: {}", reason)
+            }
+            Message::HelperWaitFailed { reason } => {
+                format!("Failed to wait for the helper: {}", reason)
+            }
+            Message::ElevationPolkitOnDemand => {
+                "Writing the machine ID will ask for polkit authorization, no restart needed"
+                    .to_string()
+            }
+            Message::ElevationHelperUnavailable { path } => {
+                format!("pkexec or {} not found", path)
+            }
+            Message::ElevationExePathUnavailable { reason } => {
+                format!("Could not determine the program path: {}", reason)
+            }
+            Message::ElevationWorkingDirUnavailable { reason } => {
+                format!("Could not determine the working directory: {}", reason)
+            }
+            Message::ElevationCancelled => "The elevation request was cancelled".to_string(),
+            Message::ElevationLaunchFailed { code } => format!(
+                "Could not start as administrator: {} (error code {})",
+                shell_execute_error(code).1,
+                code
+            ),
+            Message::TrayCurrentGuid { guid } => {
+                format!("Current machine ID: {} (click to copy)", guid)
+            }
+            Message::TrayGuidUnavailable => "Machine ID unavailable".to_string(),
+            Message::TrayPermissionGranted => "Permission: administrator".to_string(),
+            Message::TrayPermissionDenied => {
+                "Permission: limited (cannot change the machine ID)".to_string()
+            }
+            Message::TrayBackupNow => "Back up now".to_string(),
+            Message::TrayGenerateAndApply => "Generate and apply a random machine ID".to_string(),
+            Message::TrayRestoreLast => "Restore the last backup".to_string(),
+            Message::TrayShowWindow => "Show window".to_string(),
+            Message::TrayQuit => "Quit".to_string(),
+            Message::TrayConfirmTitle => "Confirm machine ID change".to_string(),
+            Message::TrayConfirmGenerate { current } => format!(
+                "The machine ID {} will be replaced with a new random value. The current value is backed up first. Continue?",
+                current
+            ),
+            Message::TrayConfirmRestore { current, guid } => format!(
+                "The machine ID will be restored from {} to the backed-up {}. Continue?",
+                current, guid
+            ),
+            Message::TrayConfirmOk => "Continue".to_string(),
+            Message::TrayConfirmCancel => "Cancel".to_string(),
+            Message::CliUsage => CLI_USAGE_EN.to_string(),
+            Message::CliOptionNeedsValue { option } => format!("{} requires a value", option),
+            Message::CliInvalidPort { value } => format!("Invalid port: {}", value),
+            Message::CliInvalidOptionValue { option, value } => {
+                format!("Invalid value for {}: {}", option, value)
+            }
+            Message::CliUnknownOption { option } => format!("Unknown option: {}", option),
+            Message::CliMissingSubcommand => "Missing subcommand".to_string(),
+            Message::CliMissingArgument { subcommand, name } => {
+                format!("{} requires argument <{}>", subcommand, name)
+            }
+            Message::CliUnknownSubcommand { name } => format!("Unknown subcommand: {}", name),
+            Message::CliExtraArgument { value } => format!("Unexpected argument: {}", value),
+            Message::CliBackupIdArg => "backup-id".to_string(),
+            Message::CliDataDirModeArg => "mode".to_string(),
+            Message::CliBackedUp { id, guid } => format!("Backed up: {}\t{}", id, guid),
+            Message::CliBackupSkipped => {
+                "Current machine ID is already backed up, skipped".to_string()
+            }
+            Message::CliRestored { from, to } => format!("Restored: {} -> {}", from, to),
+            Message::CliWritten { from, to } => format!("Written: {} -> {}", from, to),
+            Message::CliGenerated { from, to } => {
+                format!("Generated and written: {} -> {}", from, to)
+            }
+            Message::CliDeleted { id } => format!("Deleted backup: {}", id),
+            Message::CliVerifyMismatch { current } => format!("Mismatch: current {}", current),
+            Message::CliVerifyOk { guid } => format!("Verified: {}", guid),
+            Message::CliAuditVerified { entries } => {
+                format!("Audit log intact, {} entries", entries)
+            }
+            Message::CliAuditTampered { line, verified } => format!(
+                "Audit log tampered with or corrupted at line {}, {} earlier entries verified",
+                line, verified
+            ),
+            Message::CliDataDir { path, mode } => format!("{}\t{}", path, mode),
+            Message::CliDiagnosticsExported { path, redacted } => format!(
+                "Diagnostics bundle exported{}: {}",
+                if redacted {
+                    " (machine IDs redacted)"
+                } else {
+                    ""
+                },
+                path
+            ),
+            Message::CliDataMigrated { from, to, count } => {
+                format!("Migrated {} items: {} -> {}", count, from, to)
+            }
+            Message::CliRpcListening { path } => format!("JSON-RPC service listening on {}", path),
+            Message::CliHttpListening { port } => format!(
+                "HTTP API listening on http://127.0.0.1:{}, the access token is stored in the api_token file in the data directory",
+                port
+            ),
+            Message::CliError { message } => format!("Error: {}", message),
+        }
+    }
+}
+
+/// ShellExecuteW 错误码的说明（中文，英文）
+fn shell_execute_error(code: u32) -> (&'static str, &'static str) {
+    match code {
+        0 | 8 => ("内存不足", "out of memory"),
+        2 => ("文件未找到", "file not found"),
+        3 => ("路径未找到", "path not found"),
+        5 => (
+            "访问被拒绝（用户可能取消了 UAC 提示）",
+            "access denied (the UAC prompt may have been cancelled)",
+        ),
+        11 => ("EXE 文件无效", "invalid EXE file"),
+        26 => ("共享错误", "sharing violation"),
+        27 => ("文件关联不完整", "incomplete file association"),
+        28 => ("无法加载应用程序", "could not load the application"),
+        31 => ("没有应用程序关联", "no associated application"),
+        106 => ("用户取消了 UAC 提示", "the UAC prompt was cancelled"),
+        1223 => ("用户取消了操作", "the operation was cancelled"),
+        _ => ("未知错误", "unknown error"),
+    }
+}
+
+const CLI_USAGE_ZH_CN: &str = "用法: machineid-manage <子命令> [参数] [--json]
+
+子命令:
+  show                         显示当前机器码
+  backup [--description <描述>] 备份当前机器码
+  list                         列出所有备份
+  restore <备份ID>             从备份恢复机器码
+  set <GUID> [--description <描述>]
+                               写入指定机器码
+  generate [--description <描述>]
+                               生成并写入随机机器码
+  delete <备份ID>              删除备份
+  verify [GUID]                校验当前机器码格式，提供 GUID 时检查是否一致
+  audit-verify                 校验审计日志的哈希链是否完整
+  diagnostics [路径] [--include-identifiers]
+                               导出诊断包（zip），默认对机器码脱敏
+  data-dir                     显示当前数据目录及模式
+  migrate-data <portable|installed>
+                               将数据迁移到便携模式或安装模式的数据目录
+  daemon [--socket <路径>]     以守护进程模式提供本地 JSON-RPC 服务，并监视机器码的外部修改
+  http [--port <端口>]         在 127.0.0.1 上提供需令牌认证的 HTTP 接口
+
+选项:
+  --json                       以 JSON 格式输出
+  --config <文件>              使用指定的用户配置文件
+  --data-dir <目录>            使用指定的数据目录
+  --data-dir-mode <模式>       数据目录模式: portable、installed
+  --log-level <级别>           日志级别: trace、debug、info、warn、error
+  --locale <语言>              输出语言: zh-CN、en
+  -h, --help                   显示帮助
+
+以上选项也可写入配置文件或通过 MACHINEID_ 前缀的环境变量设置，
+例如 MACHINEID_LOCALE=en、MACHINEID_DATA_DIR=/var/lib/machineid-manage";
+
+const CLI_USAGE_EN: &str = "Usage: machineid-manage <subcommand> [args] [--json]
+
+Subcommands:
+  show                         Show the current machine ID
+  backup [--description <text>]
+                               Back up the current machine ID
+  list                         List all backups
+  restore <backup-id>          Restore the machine ID from a backup
+  set <GUID> [--description <text>]
+                               Write the given machine ID
+  generate [--description <text>]
+                               Generate and write a random machine ID
+  delete <backup-id>           Delete a backup
+  verify [GUID]                Check the current machine ID format, or compare it with GUID
+  audit-verify                 Check that the audit log hash chain is intact
+  diagnostics [path] [--include-identifiers]
+                               Export a diagnostics bundle (zip); machine IDs are
+                               redacted unless --include-identifiers is given
+  data-dir                     Show the current data directory and mode
+  migrate-data <portable|installed>
+                               Move data to the portable or installed data directory
+  daemon [--socket <path>]     Serve local JSON-RPC as a daemon and watch for external
+                               machine ID changes
+  http [--port <port>]         Serve the token-authenticated HTTP API on 127.0.0.1
+
+Options:
+  --json                       Print JSON output
+  --config <file>              Use the given user configuration file
+  --data-dir <dir>             Use the given data directory
+  --data-dir-mode <mode>       Data directory mode: portable, installed
+  --log-level <level>          Log level: trace, debug, info, warn, error
+  --locale <language>          Output language: zh-CN, en
+  -h, --help                   Show this help
+
+These options can also be set in the configuration file or through MACHINEID_
+environment variables, e.g. MACHINEID_LOCALE=en, MACHINEID_DATA_DIR=/var/lib/machineid-manage";
+
+/// 面向用户的错误描述，不包含路径等内部细节
+pub fn error_summary(error: &BackupError, locale: Locale) -> String {
+    match locale {
+        Locale::ZhCn => match error {
+            BackupError::InsufficientPermissions => {
+                "权限不足，需要管理员权限才能执行此操作".to_string()
+            }
+            BackupError::InvalidGuidFormat(_) => "GUID 格式无效，请检查输入".to_string(),
+            BackupError::NotFound => "未找到 MachineGuid，系统可能尚未初始化".to_string(),
+            BackupError::BackupNotFound(_) => "指定的备份不存在".to_string(),
+            BackupError::RegistryWriteError(_) => {
+                "注册表写入失败，请检查权限或系统状态".to_string()
+            }
+            BackupError::RegistryError(_) => "注册表读取失败，请检查系统状态".to_string(),
+            BackupError::StorageError(_) => "存储操作失败，请检查磁盘空间".to_string(),
+            BackupError::ParseError(_) => "数据解析失败".to_string(),
+            BackupError::UnsupportedPlatform => "当前操作系统不支持此功能".to_string(),
+            BackupError::GuidGenerationExhausted(_) => {
+                "无法生成未使用过的 GUID，请检查备份记录和黑名单".to_string()
+            }
+            BackupError::InvalidPreviewToken(_) => "预览已失效，请重新生成预览".to_string(),
+            BackupError::InvalidSchedule(msg) => format!("计划任务配置无效: {}", msg),
+            BackupError::ScheduleNotFound(_) => "指定的计划任务不存在".to_string(),
+            BackupError::ProfileNotFound(_) => "指定的身份配置不存在".to_string(),
+            BackupError::ProfileAlreadyExists(_) => "同名的身份配置已存在".to_string(),
+            BackupError::InvalidProfile(msg) => format!("身份配置无效: {}", msg),
+            BackupError::PendingRevertNotFound(_) => "指定的自动恢复记录不存在".to_string(),
+            BackupError::InvalidRevertDuration(_) => {
+                "临时写入的持续时间无效，应在 1 秒到 7 天之间".to_string()
+            }
+            BackupError::InvalidSettings(msg) => format!("配置无效: {}", msg),
+            BackupError::DataDirConflict(_) => {
+                "目标数据目录中已有同名数据，请先清理后再迁移".to_string()
+            }
+            BackupError::DataDirBusy(_) => {
+                "数据正被其他进程使用，请关闭常驻服务或其他窗口后再迁移".to_string()
+            }
+            BackupError::HelperFailed(_) => {
+                "特权辅助程序执行失败，请确认已安装 machineid-helper 和 polkit".to_string()
+            }
+            BackupError::InvalidExport(msg) => format!("无法导出备份: {}", msg),
+            BackupError::AuditLogFailed(msg) => msg.clone(),
+            BackupError::GuidAlreadyUsed(_) => "该机器码已使用过，请重新生成预览".to_string(),
+        },
+        Locale::En => match error {
+            BackupError::InsufficientPermissions => {
+                "Insufficient permissions, administrator rights are required".to_string()
+            }
+            BackupError::InvalidGuidFormat(_) => {
+                "Invalid GUID format, please check the input".to_string()
+            }
+            BackupError::NotFound => {
+                "MachineGuid not found, the system may not be initialized".to_string()
+            }
+            BackupError::BackupNotFound(_) => "The specified backup does not exist".to_string(),
+            BackupError::RegistryWriteError(_) => {
+                "Failed to write the registry, please check permissions or system state".to_string()
+            }
+            BackupError::RegistryError(_) => {
+                "Failed to read the registry, please check system state".to_string()
+            }
+            BackupError::StorageError(_) => {
+                "Storage operation failed, please check disk space".to_string()
+            }
+            BackupError::ParseError(_) => "Failed to parse data".to_string(),
+            BackupError::UnsupportedPlatform => {
+                "This feature is not supported on the current operating system".to_string()
+            }
+            BackupError::GuidGenerationExhausted(_) => {
+                "Could not generate an unused GUID, please check backups and the blocklist"
+                    .to_string()
+            }
+            BackupError::InvalidPreviewToken(_) => {
+                "The preview has expired, please generate a new one".to_string()
+            }
+            BackupError::InvalidSchedule(msg) => format!("Invalid schedule: {}", msg),
+            BackupError::ScheduleNotFound(_) => "The specified schedule does not exist".to_string(),
+            BackupError::ProfileNotFound(_) => {
+                "The specified identity profile does not exist".to_string()
+            }
+            BackupError::ProfileAlreadyExists(_) => {
+                "An identity profile with the same name already exists".to_string()
+            }
+            BackupError::InvalidProfile(msg) => format!("Invalid identity profile: {}", msg),
+            BackupError::PendingRevertNotFound(_) => {
+                "The specified automatic revert does not exist".to_string()
+            }
+            BackupError::InvalidRevertDuration(_) => {
+                "Invalid temporary write duration, it must be between 1 second and 7 days"
+                    .to_string()
+            }
+            BackupError::InvalidSettings(msg) => format!("Invalid settings: {}", msg),
+            BackupError::DataDirConflict(_) => {
+                "The target data directory already contains data with the same names, please clean it up before migrating".to_string()
+            }
+            BackupError::DataDirBusy(_) => {
+                "The data is in use by another process, please stop the daemon or other windows before migrating".to_string()
+            }
+            BackupError::HelperFailed(_) => {
+                "The privileged helper failed, please make sure machineid-helper and polkit are installed"
+                    .to_string()
+            }
+            BackupError::InvalidExport(msg) => format!("Could not export backups: {}", msg),
+            BackupError::AuditLogFailed(msg) => msg.clone(),
+            BackupError::GuidAlreadyUsed(_) => {
+                "This machine ID was used before, please generate a new preview".to_string()
+            }
+        },
+    }
+}
+
+/// 完整的错误描述，包含内部细节，用于日志和命令行输出
+pub fn error_detail(error: &BackupError, locale: Locale) -> String {
+    match locale {
+        Locale::ZhCn => match error {
+            BackupError::RegistryError(e) => format!("注册表读取失败: {}", e),
+            BackupError::RegistryWriteError(e) => format!("注册表写入失败: {}", e),
+            BackupError::NotFound => "MachineGuid 值不存在".to_string(),
+            BackupError::ParseError(e) => format!("MachineGuid 值解析失败: {}", e),
+            BackupError::StorageError(e) => format!("备份存储失败: {}", e),
+            BackupError::BackupNotFound(id) => format!("备份不存在: {}", id),
+            BackupError::InvalidGuidFormat(guid) => format!("无效的 GUID 格式: {}", guid),
+            BackupError::InsufficientPermissions => {
+                "权限不足，需要管理员权限才能修改注册表".to_string()
+            }
+            BackupError::UnsupportedPlatform => {
+                "当前系统不支持该功能（仅支持 Windows）".to_string()
+            }
+            BackupError::GuidGenerationExhausted(n) => {
+                format!("无法生成未使用过的 GUID，已拒绝 {} 个候选值", n)
+            }
+            BackupError::InvalidPreviewToken(e) => format!("预览令牌无效: {}", e),
+            BackupError::InvalidSchedule(e) => format!("无效的计划任务配置: {}", e),
+            BackupError::ScheduleNotFound(id) => format!("计划任务不存在: {}", id),
+            BackupError::ProfileNotFound(name) => format!("身份配置不存在: {}", name),
+            BackupError::ProfileAlreadyExists(name) => format!("身份配置已存在: {}", name),
+            BackupError::InvalidProfile(e) => format!("无效的身份配置: {}", e),
+            BackupError::PendingRevertNotFound(id) => format!("自动恢复记录不存在: {}", id),
+            BackupError::InvalidRevertDuration(secs) => {
+                format!("无效的临时写入持续时间: {} 秒", secs)
+            }
+            BackupError::InvalidSettings(e) => format!("无效的配置: {}", e),
+            BackupError::DataDirConflict(e) => format!("目标数据目录中已存在: {}", e),
+            BackupError::DataDirBusy(e) => format!("数据文件正被其他进程使用: {}", e),
+            BackupError::HelperFailed(e) => format!("特权辅助程序执行失败: {}", e),
+            BackupError::InvalidExport(e) => format!("无效的导出: {}", e),
+            BackupError::AuditLogFailed(e) => format!("审计日志写入失败: {}", e),
+            BackupError::GuidAlreadyUsed(guid) => format!("机器码已使用过: {}", guid),
+        },
+        Locale::En => match error {
+            BackupError::RegistryError(e) => format!("Failed to read the registry: {}", e),
+            BackupError::RegistryWriteError(e) => format!("Failed to write the registry: {}", e),
+            BackupError::NotFound => "MachineGuid value does not exist".to_string(),
+            BackupError::ParseError(e) => format!("Failed to parse MachineGuid value: {}", e),
+            BackupError::StorageError(e) => format!("Backup storage failed: {}", e),
+            BackupError::BackupNotFound(id) => format!("Backup not found: {}", id),
+            BackupError::InvalidGuidFormat(guid) => format!("Invalid GUID format: {}", guid),
+            BackupError::InsufficientPermissions => {
+                "Insufficient permissions, administrator rights are required to modify the registry"
+                    .to_string()
+            }
+            BackupError::UnsupportedPlatform => {
+                "This feature is not supported on the current system (Windows only)".to_string()
+            }
+            BackupError::GuidGenerationExhausted(n) => format!(
+                "Could not generate an unused GUID, {} candidates were rejected",
+                n
+            ),
+            BackupError::InvalidPreviewToken(e) => format!("Invalid preview token: {}", e),
+            BackupError::InvalidSchedule(e) => format!("Invalid schedule configuration: {}", e),
+            BackupError::ScheduleNotFound(id) => format!("Schedule not found: {}", id),
+            BackupError::ProfileNotFound(name) => format!("Identity profile not found: {}", name),
+            BackupError::ProfileAlreadyExists(name) => {
+                format!("Identity profile already exists: {}", name)
+            }
+            BackupError::InvalidProfile(e) => format!("Invalid identity profile: {}", e),
+            BackupError::PendingRevertNotFound(id) => {
+                format!("Automatic revert not found: {}", id)
+            }
+            BackupError::InvalidRevertDuration(secs) => {
+                format!("Invalid temporary write duration: {} seconds", secs)
+            }
+            BackupError::InvalidSettings(e) => format!("Invalid settings: {}", e),
+            BackupError::DataDirConflict(e) => {
+                format!("Already present in the target data directory: {}", e)
+            }
+            BackupError::DataDirBusy(e) => {
+                format!("Data file is in use by another process: {}", e)
+            }
+            BackupError::HelperFailed(e) => format!("Privileged helper failed: {}", e),
+            BackupError::InvalidExport(e) => format!("Invalid export: {}", e),
+            BackupError::AuditLogFailed(e) => format!("Audit log write failed: {}", e),
+            BackupError::GuidAlreadyUsed(guid) => format!("Machine ID was already used: {}", guid),
+        },
+    }
+}
+
+/// 渲染自动生成的备份描述
+pub fn auto_description(description: &AutoDescription, locale: Locale) -> String {
+    match locale {
+        Locale::ZhCn => match description {
+            AutoDescription::BeforeRestore { backup_id, guid } => {
+                format!("恢复前自动备份: 从备份 {} 恢复到 {}", backup_id, guid)
+            }
+            AutoDescription::AfterReplace { guid } => format!("替换后自动备份: {}", guid),
+            AutoDescription::BeforeProfileSwitch { profile } => {
+                format!("切换到配置 {} 前自动备份", profile)
+            }
+            AutoDescription::RevertExpired { revert_id } => {
+                format!("临时写入到期自动恢复: {}", revert_id)
+            }
+            AutoDescription::ScheduledRotation => "计划任务自动轮换".to_string(),
+            AutoDescription::ExternalChange { previous, guid } => {
+                format!("检测到外部修改后自动备份: {} 变为 {}", previous, guid)
+            }
+        },
+        Locale::En => match description {
+            AutoDescription::BeforeRestore { backup_id, guid } => format!(
+                "Automatic backup before restore: from backup {} to {}",
+                backup_id, guid
+            ),
+            AutoDescription::AfterReplace { guid } => {
+                format!("Automatic backup after replace: {}", guid)
+            }
+            AutoDescription::BeforeProfileSwitch { profile } => {
+                format!("Automatic backup before switching to profile {}", profile)
+            }
+            AutoDescription::RevertExpired { revert_id } => {
+                format!(
+                    "Automatic revert after temporary write expired: {}",
+                    revert_id
+                )
+            }
+            AutoDescription::ScheduledRotation => "Scheduled automatic rotation".to_string(),
+            AutoDescription::ExternalChange { previous, guid } => format!(
+                "Automatic backup after external change: {} became {}",
+                previous, guid
+            ),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("zh_CN.UTF-8"), Some(Locale::ZhCn));
+        assert_eq!(Locale::from_tag("zh-Hant-TW"), Some(Locale::ZhCn));
+        assert_eq!(Locale::from_tag("en-US"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("de_DE@euro"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("C.UTF-8"), None);
+        assert_eq!(Locale::from_tag("POSIX"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn test_locale_serialization() {
+        for locale in Locale::ALL {
+            assert_eq!(
+                serde_json::to_value(locale).unwrap(),
+                serde_json::json!(locale.as_str())
+            );
+            assert_eq!(Locale::from_tag(locale.as_str()), Some(locale));
+        }
+    }
+
+    #[test]
+    fn test_error_summary_hides_details() {
+        let error = BackupError::StorageError("/home/user/.data/backups.json".to_string());
+        for locale in Locale::ALL {
+            assert!(!error_summary(&error, locale).contains("/home/user"));
+            assert!(error_detail(&error, locale).contains("/home/user"));
+        }
+        assert_eq!(
+            error_summary(&BackupError::BackupNotFound("1".to_string()), Locale::En),
+            "The specified backup does not exist"
+        );
+    }
+
+    #[test]
+    fn test_auto_description_renders_per_locale() {
+        let description = AutoDescription::AfterReplace {
+            guid: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+        };
+        assert_eq!(
+            auto_description(&description, Locale::ZhCn),
+            "替换后自动备份: 550e8400-e29b-41d4-a716-446655440000"
+        );
+        assert_eq!(
+            auto_description(&description, Locale::En),
+            "Automatic backup after replace: 550e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+
+    #[test]
+    fn test_message_renders_per_locale() {
+        let message = Message::RestoreSucceeded { guid: "abc" };
+        assert_eq!(message.render(Locale::ZhCn), "恢复成功: abc");
+        assert_eq!(message.render(Locale::En), "Restored: abc");
+    }
+}
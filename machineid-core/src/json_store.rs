@@ -0,0 +1,251 @@
+//! JSON 数据文件的读写
+//!
+//! 备份、计划任务、身份配置、待恢复记录、辅助程序快照和重启状态各自保存为一个 JSON 文件，
+//! 统一通过本模块读写：
+//!
+//! - 写入先落到同目录的临时文件，同步后再重命名覆盖，中途崩溃或并发读取不会看到半截内容；
+//! - [`update`] 在读取-修改-写入期间持有 `<文件名>.lock` 上的排他文件锁，
+//!   图形界面、命令行、守护进程等多个进程同时修改同一文件时不会互相覆盖；
+//! - 同一线程可以重复对同一文件加锁，外层持有锁时内层直接返回，便于把多次修改合并在一个锁内。
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::machine_id::BackupError;
+
+/// 区分同一进程内并发写入使用的临时文件
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// 当前线程已持有的锁文件
+    static HELD_LOCKS: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+}
+
+/// 当前时间（Unix 秒）
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn storage_error(path: &Path, e: impl std::fmt::Display) -> BackupError {
+    BackupError::StorageError(format!("{}: {}", path.display(), e))
+}
+
+/// 读取 JSON 文件，文件不存在时返回默认值
+pub fn load<T: DeserializeOwned + Default>(path: &Path) -> Result<T, BackupError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => return Err(storage_error(path, e)),
+    };
+    serde_json::from_str(&content).map_err(|e| storage_error(path, e))
+}
+
+/// 以格式化的 JSON 原子地保存
+pub fn save<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), BackupError> {
+    let content = serde_json::to_vec_pretty(value).map_err(|e| storage_error(path, e))?;
+    write_atomic(path, &content).map_err(|e| storage_error(path, e))
+}
+
+/// 先写入同目录下的临时文件并同步，再重命名覆盖目标文件
+pub fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = dir.join(format!(
+        ".{}.{}.{}.tmp",
+        file_name,
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = (|| {
+        let mut file = File::create(&temp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// 数据文件对应的锁文件
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+/// 数据文件的排他锁，被丢弃时释放
+pub struct StoreLock {
+    lock: PathBuf,
+    /// 同一线程内重复加锁时为 None，由最外层的锁负责释放
+    file: Option<File>,
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        if self.file.is_some() {
+            HELD_LOCKS.with(|held| held.borrow_mut().remove(&self.lock));
+        }
+    }
+}
+
+/// 取得数据文件的排他锁，返回的句柄被丢弃时释放
+///
+/// 锁加在单独的 `<文件名>.lock` 上（Unix 为 flock，Windows 为 LockFileEx），
+/// 不影响其他进程直接读取数据文件本身。当前线程已持有该锁时直接返回。
+pub fn lock_exclusive(path: &Path) -> Result<StoreLock, BackupError> {
+    let lock = lock_path(path);
+    if HELD_LOCKS.with(|held| held.borrow().contains(&lock)) {
+        return Ok(StoreLock { lock, file: None });
+    }
+    let file = open_lock_file(&lock)?;
+    file.lock().map_err(|e| storage_error(&lock, e))?;
+    HELD_LOCKS.with(|held| held.borrow_mut().insert(lock.clone()));
+    Ok(StoreLock {
+        lock,
+        file: Some(file),
+    })
+}
+
+/// 尝试取得数据文件的排他锁，锁正被其他进程或本进程的其他线程持有时返回 None
+pub fn try_lock_exclusive(path: &Path) -> Result<Option<StoreLock>, BackupError> {
+    let lock = lock_path(path);
+    if HELD_LOCKS.with(|held| held.borrow().contains(&lock)) {
+        return Ok(Some(StoreLock { lock, file: None }));
+    }
+    let file = open_lock_file(&lock)?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => return Ok(None),
+        Err(TryLockError::Error(e)) => return Err(storage_error(&lock, e)),
+    }
+    HELD_LOCKS.with(|held| held.borrow_mut().insert(lock.clone()));
+    Ok(Some(StoreLock {
+        lock,
+        file: Some(file),
+    }))
+}
+
+fn open_lock_file(lock: &Path) -> Result<File, BackupError> {
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock)
+        .map_err(|e| storage_error(lock, e))
+}
+
+/// 在排他锁内读取、修改并保存；`f` 返回错误时不写回
+pub fn update<S, T, F>(path: &Path, f: F) -> Result<T, BackupError>
+where
+    S: Serialize + DeserializeOwned + Default,
+    F: FnOnce(&mut S) -> Result<T, BackupError>,
+{
+    let _lock = lock_exclusive(path)?;
+    let mut store = load(path)?;
+    let result = f(&mut store)?;
+    save(path, &store)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    type Counters = BTreeMap<String, u64>;
+
+    #[test]
+    fn test_load_missing_file_returns_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let loaded: Counters = load(&dir.path().join("missing.json")).unwrap();
+        assert!(loaded.is_empty());
+    }
+
+    #[test]
+    fn test_update_saves_only_on_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("counters.json");
+
+        update(&path, |counters: &mut Counters| {
+            counters.insert("runs".to_string(), 1);
+            Ok(())
+        })
+        .unwrap();
+        let failed = update(&path, |counters: &mut Counters| {
+            counters.insert("runs".to_string(), 2);
+            Err::<(), _>(BackupError::NotFound)
+        });
+        assert!(failed.is_err());
+
+        let loaded: Counters = load(&path).unwrap();
+        assert_eq!(loaded.get("runs"), Some(&1));
+        // 只留下数据文件和锁文件，没有残留的临时文件
+        let mut names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["counters.json", "counters.json.lock"]);
+    }
+
+    #[test]
+    fn test_nested_lock_in_same_thread() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("counters.json");
+
+        let outer = lock_exclusive(&path).unwrap();
+        // 外层持有锁时内层的修改不会阻塞
+        update(&path, |counters: &mut Counters| {
+            counters.insert("runs".to_string(), 1);
+            Ok(())
+        })
+        .unwrap();
+        drop(outer);
+
+        // 外层释放后其他线程可以取得锁
+        std::thread::scope(|scope| {
+            scope.spawn(|| lock_exclusive(&path).unwrap());
+        });
+        let loaded: Counters = load(&path).unwrap();
+        assert_eq!(loaded.get("runs"), Some(&1));
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_serialized() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("counters.json");
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        update(&path, |counters: &mut Counters| {
+                            *counters.entry("runs".to_string()).or_default() += 1;
+                            Ok(())
+                        })
+                        .unwrap();
+                    }
+                });
+            }
+        });
+
+        let loaded: Counters = load(&path).unwrap();
+        assert_eq!(loaded.get("runs"), Some(&80));
+    }
+}
@@ -0,0 +1,66 @@
+//! MachineID-Manage 核心库
+//!
+//! 提供与界面无关的机器标识管理能力，可被图形界面、命令行或其他 Rust 工具直接依赖：
+//!
+//! - [`machine_id`]：机器码读取/写入（Windows 注册表、macOS IOPlatformUUID、Linux machine-id）、
+//!   备份存储以及唯一 GUID 生成
+//! - [`platform::permissions`]：管理员权限检测与权限提升
+//! - [`platform::capabilities`]：按标识符细分的读写能力检测及无法写入的原因
+//! - [`restart_state`]：提权重启前后的状态交接，新进程可继续用户未完成的操作
+//! - `helper`（仅 Linux）：经 pkexec 调用的特权写入辅助程序及其请求协议
+//! - [`preview_token`]：与预览 GUID 绑定的一次性令牌
+//! - [`profiles`]：命名身份配置
+//! - [`scheduler`]：计划任务（定时轮换或恢复）
+//! - [`revert`]：临时写入的自动恢复
+//! - [`rpc`]：守护进程模式下的本地 JSON-RPC 接口
+//! - [`api_token`]：本地 HTTP 接口的访问令牌
+//! - [`api`]：统一的响应外壳与错误码
+//! - [`audit`]：机器标识操作的哈希链审计日志
+//! - [`logging`]：按大小和日期轮转的日志文件，以及日志中机器码的脱敏
+//! - [`export`]：备份导出为 JSON、CSV 或 `.reg` 文件，可选加密
+//! - [`diagnostics`]：导出包含环境、权限、数据文件统计和脱敏日志的诊断包
+//! - [`json_store`]：JSON 数据文件的原子写入与跨进程加锁更新
+//! - [`i18n`]：错误描述、自动备份描述和提示信息的多语言渲染
+//! - [`events`]：机器码与备份存储的变更事件（含其他进程造成的变更）
+//! - [`watcher`]：监视其他程序对机器码的修改，按配置自动备份、提醒或恢复
+//! - [`data_dir`]：数据目录的便携模式、安装模式与迁移
+//! - [`settings`]：分层加载的应用配置（默认值、配置文件、环境变量、命令行参数）
+//!
+//! 所有可失败的操作统一返回 [`BackupError`]。
+//!
+//! ```no_run
+//! use machineid_core::machine_id::{backup_current_machine_guid, read_machine_guid};
+//!
+//! let current = read_machine_guid()?;
+//! println!("{} ({})", current.guid, current.source);
+//!
+//! if let Some(backup) = backup_current_machine_guid(Some("部署前备份".to_string()))? {
+//!     println!("已备份: {}", backup.id);
+//! }
+//! # Ok::<(), machineid_core::BackupError>(())
+//! ```
+
+pub mod api;
+pub mod api_token;
+pub mod audit;
+pub mod data_dir;
+pub mod diagnostics;
+pub mod events;
+pub mod export;
+#[cfg(target_os = "linux")]
+pub mod helper;
+pub mod i18n;
+pub mod json_store;
+pub mod logging;
+pub mod machine_id;
+pub mod platform;
+pub mod preview_token;
+pub mod profiles;
+pub mod restart_state;
+pub mod revert;
+pub mod rpc;
+pub mod scheduler;
+pub mod settings;
+pub mod watcher;
+
+pub use machine_id::{BackupError, MachineId, MachineIdBackup};
@@ -0,0 +1,392 @@
+//! 日志文件与机器码脱敏
+//!
+//! 日志写入数据目录下的 `logs/machineid-manage.log`，超过配置的大小或跨日时轮转为
+//! `machineid-manage.log.1`、`.2`……（数字越小越新），超出保留数量的旧文件被删除。
+//! 图形界面、命令行和常驻服务可能同时写入同一个日志文件，每次写入和轮转都在
+//! `machineid-manage.log.lock` 上的文件锁内进行，并先确认当前文件没有被其他进程轮转。
+//!
+//! 未开启调试模式时，写入控制台和文件的日志经 [`RedactingWriter`] 脱敏：GUID 和 32 位十六进制机器码
+//! 只保留首尾各 4 位，例如 `1234****-****-****-****-********cdef`。
+//!
+//! 本模块只提供 [`std::io::Write`] 实现，由调用方接入所用的日志框架。
+
+use std::borrow::Cow;
+use std::fs::{self, File, Metadata, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+use crate::machine_id::{get_data_file_path, BackupError};
+use crate::settings::Settings;
+
+/// 日志目录名，与备份文件位于同一目录
+pub const LOG_DIR_NAME: &str = "logs";
+/// 当前日志文件名
+pub const LOG_FILE_NAME: &str = "machineid-manage.log";
+/// 脱敏时保留的首尾字符数
+const VISIBLE_CHARS: usize = 4;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+lazy_static! {
+    /// 日志中可能出现的机器标识：带连字符的 GUID（Windows MachineGuid、macOS IOPlatformUUID）
+    /// 和 32 位十六进制串（Linux machine-id）；更长的十六进制串（如 SHA-256 摘要）不受影响
+    static ref IDENTIFIER_PATTERN: Regex = Regex::new(
+        r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b|\b[0-9a-fA-F]{32}\b"
+    ).expect("Invalid identifier regex pattern");
+    /// 本进程正在写入的日志文件，数据目录迁移后需要切换到新的目录
+    static ref ACTIVE_LOG: Mutex<Option<LogFile>> = Mutex::new(None);
+}
+
+/// 遮盖文本中的机器标识，只保留首尾各 4 位十六进制字符
+pub fn redact(text: &str) -> Cow<'_, str> {
+    IDENTIFIER_PATTERN.replace_all(text, |caps: &Captures| mask(&caps[0]))
+}
+
+fn mask(identifier: &str) -> String {
+    let digits = identifier.chars().filter(|c| *c != '-').count();
+    let mut index = 0;
+    identifier
+        .chars()
+        .map(|c| {
+            if c == '-' {
+                return c;
+            }
+            index += 1;
+            if index <= VISIBLE_CHARS || index > digits - VISIBLE_CHARS {
+                c
+            } else {
+                '*'
+            }
+        })
+        .collect()
+}
+
+/// 写入前对内容脱敏的包装
+/// 日志框架每条记录调用一次写入，机器码不会被拆分到两次写入中
+pub struct RedactingWriter<W> {
+    inner: W,
+    enabled: bool,
+}
+
+impl<W: Write> RedactingWriter<W> {
+    /// `enabled` 为 false 时原样写入，用于调试模式
+    pub fn new(inner: W, enabled: bool) -> Self {
+        RedactingWriter { inner, enabled }
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.enabled {
+            return self.inner.write(buf);
+        }
+        let text = String::from_utf8_lossy(buf);
+        self.inner.write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 按大小和日期轮转的日志文件
+pub struct RotatingFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: Option<File>,
+    /// 进程间互斥写入和轮转的锁文件
+    lock: File,
+    size: u64,
+    day: u64,
+}
+
+impl RotatingFile {
+    /// 在 `dir` 下打开（必要时创建）当前日志文件
+    /// `max_files` 为保留的已轮转文件数（至少为 1），不含当前文件
+    pub fn open(dir: &Path, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let mut rotating = RotatingFile {
+            dir: dir.to_path_buf(),
+            max_bytes,
+            max_files,
+            file: None,
+            lock: open_lock(dir)?,
+            size: 0,
+            day: today(),
+        };
+        rotating.open_current()?;
+        // 沿用上次运行或其他进程留下的文件时，以其修改日期判断是否需要轮转
+        rotating.day = rotating.current_day().unwrap_or(rotating.day);
+        Ok(rotating)
+    }
+
+    fn current_path(&self) -> PathBuf {
+        self.dir.join(LOG_FILE_NAME)
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{}.{}", LOG_FILE_NAME, index))
+    }
+
+    /// 当前文件最后修改的日期
+    fn current_day(&self) -> Option<u64> {
+        fs::metadata(self.current_path())
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(day_of)
+    }
+
+    /// 改为在 `dir` 下写入，数据目录迁移后使用
+    fn redirect(&mut self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        self.lock = open_lock(dir)?;
+        self.dir = dir.to_path_buf();
+        self.open_current()
+    }
+
+    fn open_current(&mut self) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.current_path())?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// 当前文件改名为 `.1`，已有的轮转文件依次后移，超出保留数量的删除
+    fn rotate(&mut self, day: u64) -> io::Result<()> {
+        self.file = None;
+        match fs::remove_file(self.rotated_path(self.max_files)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        if self.current_path().exists() {
+            fs::rename(self.current_path(), self.rotated_path(1))?;
+        }
+        self.day = day;
+        self.open_current()
+    }
+
+    /// 在锁内写入，其他进程不会同时写入或轮转
+    fn write_on(&mut self, buf: &[u8], day: u64) -> io::Result<usize> {
+        self.lock.lock()?;
+        let result = self.write_locked(buf, day);
+        let _ = self.lock.unlock();
+        result
+    }
+
+    /// 当前文件已被其他进程轮转或删除时重新打开，并以磁盘上的长度为准
+    fn sync_with_disk(&mut self) -> io::Result<()> {
+        let opened = self.file.as_ref().and_then(|file| file.metadata().ok());
+        match (opened, fs::metadata(self.current_path())) {
+            (Some(opened), Ok(on_disk)) if same_file(&opened, &on_disk) => {
+                self.size = on_disk.len();
+                Ok(())
+            }
+            _ => {
+                self.open_current()?;
+                self.day = self.current_day().unwrap_or(self.day);
+                Ok(())
+            }
+        }
+    }
+
+    fn write_locked(&mut self, buf: &[u8], day: u64) -> io::Result<usize> {
+        self.sync_with_disk()?;
+        let oversized = self.size > 0 && self.size + buf.len() as u64 > self.max_bytes;
+        if day != self.day || oversized {
+            self.rotate(day)?;
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(buf)?;
+        }
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_on(buf, today())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn open_lock(dir: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(format!("{}.lock", LOG_FILE_NAME)))
+}
+
+/// 两个元数据是否属于同一个文件
+#[cfg(unix)]
+fn same_file(a: &Metadata, b: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+/// 稳定版 Rust 在 Windows 上无法取得文件编号，以长度和创建时间判断
+#[cfg(not(unix))]
+fn same_file(a: &Metadata, b: &Metadata) -> bool {
+    a.len() == b.len() && a.created().ok() == b.created().ok()
+}
+
+/// 可在多个线程间共享的日志文件句柄
+#[derive(Clone)]
+pub struct LogFile(Arc<Mutex<RotatingFile>>);
+
+impl Write for LogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).flush()
+    }
+}
+
+/// 日志目录
+pub fn log_dir() -> Result<PathBuf, BackupError> {
+    get_data_file_path(LOG_DIR_NAME)
+}
+
+/// 按配置的大小上限和保留数量打开日志文件
+pub fn open_log_file(settings: &Settings) -> Result<LogFile, BackupError> {
+    let dir = log_dir()?;
+    let file = RotatingFile::open(
+        &dir,
+        settings.log_max_size_mb * 1024 * 1024,
+        settings.log_max_files,
+    )
+    .map_err(|e| BackupError::StorageError(format!("{}: {}", dir.display(), e)))?;
+    let log = LogFile(Arc::new(Mutex::new(file)));
+    *ACTIVE_LOG.lock().unwrap_or_else(|e| e.into_inner()) = Some(log.clone());
+    Ok(log)
+}
+
+/// 将正在写入的日志文件切换到 `dir`，未打开日志文件时不做任何事
+pub fn redirect_log_dir(dir: &Path) -> io::Result<()> {
+    let active = ACTIVE_LOG.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    match active {
+        Some(LogFile(file)) => file.lock().unwrap_or_else(|e| e.into_inner()).redirect(dir),
+        None => Ok(()),
+    }
+}
+
+/// 自 Unix 纪元起的天数（UTC）
+fn today() -> u64 {
+    day_of(SystemTime::now()).unwrap_or(0)
+}
+
+fn day_of(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() / SECS_PER_DAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_redact_identifiers() {
+        assert_eq!(
+            redact("写入机器码: 12345678-90ab-cdef-1234-567890abcdef"),
+            "写入机器码: 1234****-****-****-****-********cdef"
+        );
+        assert_eq!(
+            redact("machine-id {0123456789abcdef0123456789abcdef}"),
+            "machine-id {0123************************cdef}"
+        );
+        // SHA-256 摘要和普通文本保持不变
+        let digest = "a".repeat(64);
+        assert_eq!(redact(&digest), digest);
+        assert!(matches!(redact("备份数量: 12"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_redacting_writer_respects_debug_mode() {
+        let line = b"guid=12345678-90AB-CDEF-1234-567890ABCDEF\n";
+
+        let mut redacted = RedactingWriter::new(Vec::new(), true);
+        redacted.write_all(line).unwrap();
+        assert_eq!(
+            redacted.inner,
+            b"guid=1234****-****-****-****-********CDEF\n".to_vec()
+        );
+
+        let mut plain = RedactingWriter::new(Vec::new(), false);
+        plain.write_all(line).unwrap();
+        assert_eq!(plain.inner, line.to_vec());
+    }
+
+    #[test]
+    fn test_rotates_by_size_and_keeps_limit() {
+        let dir = TempDir::new().unwrap();
+        let mut file = RotatingFile::open(dir.path(), 10, 2).unwrap();
+        let day = file.day;
+        for line in ["first-0\n", "second\n", "third-\n", "fourth\n"] {
+            file.write_on(line.as_bytes(), day).unwrap();
+        }
+
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read(LOG_FILE_NAME), "fourth\n");
+        assert_eq!(read("machineid-manage.log.1"), "third-\n");
+        assert_eq!(read("machineid-manage.log.2"), "second\n");
+        assert!(!dir.path().join("machineid-manage.log.3").exists());
+    }
+
+    #[test]
+    fn test_rotates_on_new_day() {
+        let dir = TempDir::new().unwrap();
+        let mut file = RotatingFile::open(dir.path(), 1024, 3).unwrap();
+        let day = file.day;
+        file.write_on(b"yesterday\n", day).unwrap();
+        file.write_on(b"today\n", day + 1).unwrap();
+
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read(LOG_FILE_NAME), "today\n");
+        assert_eq!(read("machineid-manage.log.1"), "yesterday\n");
+    }
+
+    #[test]
+    fn test_processes_share_rotation() {
+        let dir = TempDir::new().unwrap();
+        // 两个句柄模拟同时写入的两个进程
+        let mut first = RotatingFile::open(dir.path(), 10, 3).unwrap();
+        let mut second = RotatingFile::open(dir.path(), 10, 3).unwrap();
+        let day = first.day;
+
+        first.write_on(b"first-1\n", day).unwrap();
+        second.write_on(b"second\n", day).unwrap();
+        // 第一个句柄发现文件已被轮转，写入新的当前文件并按其长度轮转
+        first.write_on(b"first-2\n", day).unwrap();
+
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read(LOG_FILE_NAME), "first-2\n");
+        assert_eq!(read("machineid-manage.log.1"), "second\n");
+        assert_eq!(read("machineid-manage.log.2"), "first-1\n");
+    }
+}
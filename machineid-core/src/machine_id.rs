@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -7,6 +8,12 @@ use rand::rngs::OsRng;
 use rand::RngCore;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+
+use crate::audit::{self, AuditOperation};
+use crate::data_dir;
+use crate::events::{self, ChangeEvent};
+use crate::i18n::{self, tr, Locale, Message};
+use crate::json_store;
 #[cfg(windows)]
 use winreg::enums::*;
 #[cfg(windows)]
@@ -21,19 +28,22 @@ lazy_static! {
 
 /// 获取备份文件路径
 /// 优先使用应用程序数据目录，确保有写入权限
-fn get_backup_file_path() -> Result<PathBuf, BackupError> {
+pub(crate) fn get_backup_file_path() -> Result<PathBuf, BackupError> {
     // 检查测试环境变量
     if let Ok(path_str) = std::env::var("BACKUP_TEST_PATH") {
         return Ok(PathBuf::from(path_str));
     }
 
-    // 使用系统应用程序数据目录
-    let app_data_dir = get_app_data_dir()?;
+    // 按配置确定数据目录（便携模式、安装模式或自定义目录）
+    let app_data_dir = data_dir::current_data_dir()?;
 
     // 确保目录存在
     if !app_data_dir.exists() {
-        fs::create_dir_all(&app_data_dir)
-            .map_err(|e| BackupError::StorageError(format!("创建备份目录失败: {}", e)))?;
+        fs::create_dir_all(&app_data_dir).map_err(|e| {
+            BackupError::StorageError(tr(Message::DataDirCreateFailed {
+                reason: &e.to_string(),
+            }))
+        })?;
     }
 
     let mut path = app_data_dir;
@@ -41,86 +51,56 @@ fn get_backup_file_path() -> Result<PathBuf, BackupError> {
     Ok(path)
 }
 
-/// 获取应用程序数据目录
-/// 使用程序所在目录下的 .data 文件夹存储数据，确保数据与程序关联
-#[cfg(windows)]
-fn get_app_data_dir() -> Result<PathBuf, BackupError> {
-    // 获取程序所在目录
-    let exe_path = std::env::current_exe()
-        .map_err(|e| BackupError::StorageError(format!("无法获取程序路径: {}", e)))?;
-    
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| BackupError::StorageError("无法获取程序目录".to_string()))?;
-    
-    // 使用程序目录下的 .data 文件夹
-    let mut path = exe_dir.to_path_buf();
-    path.push(".data");
-    Ok(path)
-}
-
-#[cfg(target_os = "macos")]
-fn get_app_data_dir() -> Result<PathBuf, BackupError> {
-    // 获取程序所在目录（.app/Contents/MacOS/）
-    let exe_path = std::env::current_exe()
-        .map_err(|e| BackupError::StorageError(format!("无法获取程序路径: {}", e)))?;
-    
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| BackupError::StorageError("无法获取程序目录".to_string()))?;
-    
-    // 使用程序目录下的 .data 文件夹
-    let mut path = exe_dir.to_path_buf();
-    path.push(".data");
-    Ok(path)
+/// 获取与备份文件位于同一目录的其他数据文件路径
+pub(crate) fn get_data_file_path(file_name: &str) -> Result<PathBuf, BackupError> {
+    Ok(get_backup_file_path()?.with_file_name(file_name))
 }
 
-#[cfg(target_os = "linux")]
-fn get_app_data_dir() -> Result<PathBuf, BackupError> {
-    // 获取程序所在目录
-    let exe_path = std::env::current_exe()
-        .map_err(|e| BackupError::StorageError(format!("无法获取程序路径: {}", e)))?;
-    
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| BackupError::StorageError("无法获取程序目录".to_string()))?;
-    
-    // 使用程序目录下的 .data 文件夹
-    let mut path = exe_dir.to_path_buf();
-    path.push(".data");
-    Ok(path)
-}
-
-#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-fn get_app_data_dir() -> Result<PathBuf, BackupError> {
-    Err(BackupError::StorageError("不支持的操作系统".to_string()))
-}
-
-#[derive(Debug, thiserror::Error)]
-#[error(transparent)]
+/// 错误描述随当前语言变化，见 [`crate::i18n`]
+#[derive(Debug)]
 pub enum BackupError {
-    #[error("注册表读取失败: {0}")]
     RegistryError(String),
-    #[error("注册表写入失败: {0}")]
     RegistryWriteError(String),
-    #[error("MachineGuid 值不存在")]
     NotFound,
-    #[error("MachineGuid 值解析失败: {0}")]
     #[allow(dead_code)]
     ParseError(String),
-    #[error("备份存储失败: {0}")]
     StorageError(String),
-    #[error("备份不存在: {0}")]
     BackupNotFound(String),
-    #[error("无效的 GUID 格式: {0}")]
     InvalidGuidFormat(String),
-    #[error("权限不足，需要管理员权限才能修改注册表")]
     InsufficientPermissions,
-    #[error("当前系统不支持该功能（仅支持 Windows）")]
     #[allow(dead_code)]
     UnsupportedPlatform,
+    GuidGenerationExhausted(usize),
+    InvalidPreviewToken(String),
+    InvalidSchedule(String),
+    ScheduleNotFound(String),
+    ProfileNotFound(String),
+    ProfileAlreadyExists(String),
+    InvalidProfile(String),
+    PendingRevertNotFound(String),
+    InvalidRevertDuration(u64),
+    InvalidSettings(String),
+    DataDirConflict(String),
+    /// 迁移数据目录时，数据文件正被其他进程使用
+    DataDirBusy(String),
+    /// 特权辅助程序不可用或执行失败
+    HelperFailed(String),
+    /// 导出请求无效，或加密导出文件无法解密
+    InvalidExport(String),
+    /// 审计日志不可写，或修改机器码后未能记入审计日志
+    AuditLogFailed(String),
+    /// 要写入的机器码已在备份或黑名单中出现过
+    GuidAlreadyUsed(String),
+}
+
+impl std::fmt::Display for BackupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&i18n::error_detail(self, i18n::current_locale()))
+    }
 }
 
+impl std::error::Error for BackupError {}
+
 impl Serialize for BackupError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -130,13 +110,83 @@ impl Serialize for BackupError {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
 pub struct MachineIdBackup {
     pub id: String,
     pub guid: String,
     pub source: String,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
     pub timestamp: u64,
+    /// 展示用描述；自动生成的备份读取时按当前语言重新渲染
     pub description: Option<String>,
+    /// 自动生成的描述，以结构化形式保存，用户修改描述后清空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_description: Option<AutoDescription>,
+}
+
+/// 自动备份的描述，保存结构化数据以便按任意语言渲染
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum AutoDescription {
+    /// 从备份恢复前
+    BeforeRestore { backup_id: String, guid: String },
+    /// 替换机器码后
+    AfterReplace { guid: String },
+    /// 切换身份配置前
+    BeforeProfileSwitch { profile: String },
+    /// 临时写入到期恢复
+    RevertExpired { revert_id: String },
+    /// 计划任务轮换
+    ScheduledRotation,
+    /// 检测到其他程序修改机器码
+    ExternalChange { previous: String, guid: String },
+}
+
+impl AutoDescription {
+    pub fn render(&self, locale: Locale) -> String {
+        i18n::auto_description(self, locale)
+    }
+
+    /// 识别旧版本保存的中文自动描述
+    pub fn from_legacy(text: &str) -> Option<Self> {
+        if let Some(rest) = text.strip_prefix("恢复前自动备份: 从备份 ") {
+            let (backup_id, guid) = rest.split_once(" 恢复到 ")?;
+            return Some(AutoDescription::BeforeRestore {
+                backup_id: backup_id.to_string(),
+                guid: guid.to_string(),
+            });
+        }
+        if let Some(guid) = text.strip_prefix("替换后自动备份: ") {
+            return Some(AutoDescription::AfterReplace {
+                guid: guid.to_string(),
+            });
+        }
+        if let Some(profile) = text
+            .strip_prefix("切换到配置 ")
+            .and_then(|rest| rest.strip_suffix(" 前自动备份"))
+        {
+            return Some(AutoDescription::BeforeProfileSwitch {
+                profile: profile.to_string(),
+            });
+        }
+        if let Some(revert_id) = text.strip_prefix("临时写入到期自动恢复: ") {
+            return Some(AutoDescription::RevertExpired {
+                revert_id: revert_id.to_string(),
+            });
+        }
+        (text == "计划任务自动轮换").then_some(AutoDescription::ScheduledRotation)
+    }
+}
+
+/// 创建备份时的描述：用户填写的文本或自动生成的描述
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupDescription {
+    Text(String),
+    Auto(AutoDescription),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -144,6 +194,12 @@ pub struct BackupStore {
     pub backups: Vec<MachineIdBackup>,
 }
 
+impl Default for BackupStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl BackupStore {
     pub fn new() -> Self {
         BackupStore {
@@ -172,11 +228,35 @@ impl BackupStore {
         self.backups.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.backups.is_empty()
+    }
+
+    /// 是否已有相同机器码的备份，GUID 与 machine-id 格式视为同一值
     pub fn has_guid(&self, guid: &str) -> bool {
-        self.backups.iter().any(|b| b.guid == guid)
+        let guid = normalize_identity(guid);
+        self.backups
+            .iter()
+            .any(|b| normalize_identity(&b.guid) == guid)
+    }
+
+    /// 按指定语言渲染自动描述，旧版本保存的中文自动描述会先转换为结构化形式
+    pub fn localize(&mut self, locale: Locale) {
+        for backup in &mut self.backups {
+            if backup.auto_description.is_none() {
+                backup.auto_description = backup
+                    .description
+                    .as_deref()
+                    .and_then(AutoDescription::from_legacy);
+            }
+            if let Some(auto) = &backup.auto_description {
+                backup.description = Some(auto.render(locale));
+            }
+        }
     }
 }
 
+#[cfg(windows)]
 fn get_registry_path() -> &'static str {
     "SOFTWARE\\Microsoft\\Cryptography"
 }
@@ -205,24 +285,34 @@ pub fn test_registry_write_access() -> Result<(), BackupError> {
 }
 
 fn load_backup_store() -> Result<BackupStore, BackupError> {
-    let path = get_backup_file_path()?;
-
-    if !path.exists() {
-        return Ok(BackupStore::new());
-    }
-
-    let content =
-        fs::read_to_string(&path).map_err(|e| BackupError::StorageError(e.to_string()))?;
-
-    serde_json::from_str(&content).map_err(|e| BackupError::StorageError(e.to_string()))
+    let mut store: BackupStore = json_store::load(&get_backup_file_path()?)?;
+    store.localize(i18n::current_locale());
+    Ok(store)
 }
 
 fn save_backup_store(store: &BackupStore) -> Result<(), BackupError> {
     let path = get_backup_file_path()?;
-    let content = serde_json::to_string_pretty(store)
-        .map_err(|e| BackupError::StorageError(e.to_string()))?;
+    json_store::save(&path, store)?;
+    events::note_store_saved(&path, &store.backups);
+    Ok(())
+}
 
-    fs::write(&path, content).map_err(|e| BackupError::StorageError(e.to_string()))
+/// 取得备份存储的排他锁
+/// 写入和恢复机器码时在锁内完成写入前备份、写入和写入后备份，其他进程的备份不会穿插其中
+fn lock_backup_store() -> Result<json_store::StoreLock, BackupError> {
+    json_store::lock_exclusive(&get_backup_file_path()?)
+}
+
+/// 在备份存储的排他锁内读取、修改并保存；`f` 返回错误时不写回
+fn with_backup_store<T, F>(f: F) -> Result<T, BackupError>
+where
+    F: FnOnce(&mut BackupStore) -> Result<T, BackupError>,
+{
+    let _lock = lock_backup_store()?;
+    let mut store = load_backup_store()?;
+    let result = f(&mut store)?;
+    save_backup_store(&store)?;
+    Ok(result)
 }
 
 fn generate_backup_id() -> String {
@@ -235,10 +325,32 @@ fn generate_backup_id() -> String {
 
 pub fn backup_current_machine_guid(
     description: Option<String>,
+) -> Result<Option<MachineIdBackup>, BackupError> {
+    backup_current_machine_guid_with(description.map(BackupDescription::Text))
+}
+
+/// 备份当前机器码，描述可以是用户文本或自动描述
+pub fn backup_current_machine_guid_with(
+    description: Option<BackupDescription>,
+) -> Result<Option<MachineIdBackup>, BackupError> {
+    let result = create_backup(description);
+    let backup = result.as_ref().ok().and_then(Option::as_ref);
+    audit::record(
+        AuditOperation::Backup,
+        backup.map(|b| b.id.as_str()),
+        None,
+        backup.map(|b| b.guid.as_str()),
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn create_backup(
+    description: Option<BackupDescription>,
 ) -> Result<Option<MachineIdBackup>, BackupError> {
     let machine_id = read_machine_guid()?;
 
-    // 只加载一次存储
+    let _lock = lock_backup_store()?;
     let mut store = load_backup_store()?;
 
     // 检查是否已存在相同 GUID 的备份
@@ -246,7 +358,7 @@ pub fn backup_current_machine_guid(
         return Ok(None);
     }
 
-    let backup = MachineIdBackup {
+    let mut backup = MachineIdBackup {
         id: generate_backup_id(),
         guid: machine_id.guid.clone(),
         source: machine_id.source.clone(),
@@ -254,11 +366,23 @@ pub fn backup_current_machine_guid(
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs(),
-        description,
+        description: None,
+        auto_description: None,
     };
+    match description {
+        Some(BackupDescription::Text(text)) => backup.description = Some(text),
+        Some(BackupDescription::Auto(auto)) => {
+            backup.description = Some(auto.render(i18n::current_locale()));
+            backup.auto_description = Some(auto);
+        }
+        None => {}
+    }
 
     store.add_backup(backup.clone());
     save_backup_store(&store)?;
+    events::publish(ChangeEvent::BackupAdded {
+        backup: backup.clone(),
+    });
 
     Ok(Some(backup))
 }
@@ -269,15 +393,31 @@ pub fn list_backups() -> Result<Vec<MachineIdBackup>, BackupError> {
 }
 
 pub fn delete_backup(id: &str) -> Result<(), BackupError> {
-    let mut store = load_backup_store()?;
-    store.remove_backup(id)?;
-    save_backup_store(&store)?;
+    let result = with_backup_store(|store| store.remove_backup(id));
+    audit::record(
+        AuditOperation::Delete,
+        Some(id),
+        result.as_ref().ok().map(|b| b.guid.as_str()),
+        None,
+        result.as_ref().err(),
+    );
+    result?;
+    events::publish(ChangeEvent::BackupDeleted { id: id.to_string() });
     Ok(())
 }
 
 pub fn clear_all_backups() -> Result<(), BackupError> {
-    let store = BackupStore::new();
-    save_backup_store(&store)?;
+    let result = with_backup_store(|store| Ok(std::mem::take(store)));
+    audit::record(
+        AuditOperation::Clear,
+        None,
+        None,
+        None,
+        result.as_ref().err(),
+    );
+    for backup in result?.backups {
+        events::publish(ChangeEvent::BackupDeleted { id: backup.id });
+    }
     Ok(())
 }
 
@@ -291,22 +431,21 @@ pub fn update_backup_description(
     id: &str,
     description: Option<String>,
 ) -> Result<MachineIdBackup, BackupError> {
-    let mut store = load_backup_store()?;
-
-    // 查找备份
-    let backup_index = store
-        .backups
-        .iter()
-        .position(|b| b.id == id)
-        .ok_or_else(|| BackupError::BackupNotFound(id.to_string()))?;
-
-    // 更新描述
-    store.backups[backup_index].description = description;
-
-    // 保存
-    save_backup_store(&store)?;
+    let (updated, count) = with_backup_store(|store| {
+        let backup = store
+            .backups
+            .iter_mut()
+            .find(|b| b.id == id)
+            .ok_or_else(|| BackupError::BackupNotFound(id.to_string()))?;
+
+        // 更新描述，用户修改后不再按语言重新渲染
+        backup.description = description;
+        backup.auto_description = None;
+        Ok((backup.clone(), store.len()))
+    })?;
+    events::publish(ChangeEvent::StoreReloaded { count });
 
-    Ok(store.backups[backup_index].clone())
+    Ok(updated)
 }
 
 pub fn get_backup_by_id(id: &str) -> Result<MachineIdBackup, BackupError> {
@@ -338,23 +477,63 @@ fn set_machine_guid_value(new_guid: &str) -> Result<(), BackupError> {
     Ok(())
 }
 
-#[cfg(not(windows))]
+/// root 直接写入机器码文件，普通用户通过 pkexec 调用特权辅助程序
+#[cfg(target_os = "linux")]
+fn set_machine_guid_value(new_guid: &str) -> Result<(), BackupError> {
+    if crate::platform::linux::is_root() {
+        crate::platform::linux::write_machine_id(new_guid)
+    } else {
+        crate::helper::set_identity(new_guid).map(|_| ())
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
 fn set_machine_guid_value(_new_guid: &str) -> Result<(), BackupError> {
     Err(BackupError::UnsupportedPlatform)
 }
 
+/// 校验要写入的机器码，Linux 另外接受不含连字符的 machine-id 格式
+pub fn validate_identity_value(value: &str) -> Result<(), BackupError> {
+    #[cfg(target_os = "linux")]
+    return crate::platform::linux::to_machine_id(value).map(|_| ());
+    #[cfg(not(target_os = "linux"))]
+    validate_guid_format(value)
+}
+
+/// 从备份恢复机器码
+/// 审计日志不可写时拒绝恢复，恢复后未能记入审计日志时返回 [`BackupError::AuditLogFailed`]
 pub fn restore_backup_by_id(id: &str) -> Result<RestoreInfo, BackupError> {
+    audit::ensure_writable()?;
+    let result = restore_backup(id);
+    match &result {
+        Ok(info) => audit::record_identity_change(
+            AuditOperation::Restore,
+            Some(id),
+            Some(&info.previous_guid),
+            Some(&info.restored_guid),
+            None,
+        )?,
+        Err(e) => audit::record(AuditOperation::Restore, Some(id), None, None, Some(e)),
+    }
+    result
+}
+
+fn restore_backup(id: &str) -> Result<RestoreInfo, BackupError> {
+    let _lock = lock_backup_store()?;
     let target = get_backup_by_id(id)?;
-    validate_guid_format(&target.guid)?;
+    validate_identity_value(&target.guid)?;
 
     let previous = read_machine_guid()?;
-    let pre_backup = backup_current_machine_guid(Some(format!(
-        "恢复前自动备份: 从备份 {} 恢复到 {}",
-        target.id, target.guid
+    let pre_backup = backup_current_machine_guid_with(Some(BackupDescription::Auto(
+        AutoDescription::BeforeRestore {
+            backup_id: target.id.clone(),
+            guid: target.guid.clone(),
+        },
     )))?;
 
     set_machine_guid_value(&target.guid)?;
     let restored = read_machine_guid()?;
+    events::note_identity_written(&previous.guid, &restored.guid);
 
     Ok(RestoreInfo {
         previous_guid: previous.guid,
@@ -423,13 +602,15 @@ pub fn read_machine_guid() -> Result<MachineId, BackupError> {
 
 #[cfg(target_os = "linux")]
 pub fn read_machine_guid() -> Result<MachineId, BackupError> {
-    let machine_id = fs::read_to_string("/etc/machine-id")
-        .or_else(|_| fs::read_to_string("/var/lib/dbus/machine-id"))
+    use crate::platform::linux::MACHINE_ID_FILES;
+
+    let machine_id = fs::read_to_string(MACHINE_ID_FILES[0])
+        .or_else(|_| fs::read_to_string(MACHINE_ID_FILES[1]))
         .map_err(|e| BackupError::RegistryError(format!("Failed to read machine-id: {}", e)))?;
 
     Ok(MachineId {
         guid: machine_id.trim().to_string(),
-        source: "/etc/machine-id".to_string(),
+        source: MACHINE_ID_FILES[0].to_string(),
     })
 }
 
@@ -438,52 +619,102 @@ pub fn read_machine_guid() -> Result<MachineId, BackupError> {
     Err(BackupError::UnsupportedPlatform)
 }
 
+/// 读取机器码并记入审计日志，用于用户发起的读取
+///
+/// 监视、能力检测等内部检查直接调用 [`read_machine_guid`]，避免审计日志被轮询淹没。
+pub fn read_machine_guid_audited() -> Result<MachineId, BackupError> {
+    let result = read_machine_guid();
+    audit::record(
+        AuditOperation::Read,
+        None,
+        None,
+        result.as_ref().ok().map(|m| m.guid.as_str()),
+        result.as_ref().err(),
+    );
+    result
+}
+
+/// 机器码的比较形式：小写并去掉连字符
+/// Linux 写入后读回的是不含连字符的 machine-id，与生成的 GUID 需按同一形式比较
+pub fn normalize_identity(value: &str) -> String {
+    value
+        .trim()
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
 /// 验证 GUID 格式
 /// 使用预编译的正则表达式提高性能
-fn validate_guid_format(guid: &str) -> Result<(), BackupError> {
+pub fn validate_guid_format(guid: &str) -> Result<(), BackupError> {
     if !GUID_PATTERN.is_match(guid) {
         return Err(BackupError::InvalidGuidFormat(guid.to_string()));
     }
     Ok(())
 }
 
-#[cfg(windows)]
 pub fn write_machine_guid(
     new_guid: &str,
     description: Option<String>,
 ) -> Result<WriteResult, BackupError> {
-    validate_guid_format(new_guid)?;
+    write_machine_guid_with(new_guid, description.map(BackupDescription::Text))
+}
 
-    let pre_backup = backup_current_machine_guid(description)?;
+/// 写入机器码，`description` 用于写入前的备份
+/// 审计日志不可写时拒绝写入，写入后未能记入审计日志时返回 [`BackupError::AuditLogFailed`]
+pub fn write_machine_guid_with(
+    new_guid: &str,
+    description: Option<BackupDescription>,
+) -> Result<WriteResult, BackupError> {
+    audit::ensure_writable()?;
+    let result = write_identity(new_guid, description);
+    match &result {
+        Ok(written) => audit::record_identity_change(
+            AuditOperation::Write,
+            None,
+            Some(&written.previous_guid),
+            Some(&written.new_guid),
+            None,
+        )?,
+        Err(e) => audit::record(AuditOperation::Write, None, None, Some(new_guid), Some(e)),
+    }
+    result
+}
 
-    let hkcu = RegKey::predef(HKEY_LOCAL_MACHINE);
-    let crypt_path = get_registry_path();
-    let crypt_key = hkcu
-        .open_subkey_with_flags(crypt_path, KEY_WRITE | KEY_READ)
-        .map_err(|e| BackupError::RegistryWriteError(e.to_string()))?;
+#[cfg(any(windows, target_os = "linux"))]
+fn write_identity(
+    new_guid: &str,
+    description: Option<BackupDescription>,
+) -> Result<WriteResult, BackupError> {
+    validate_identity_value(new_guid)?;
 
-    crypt_key
-        .set_value("MachineGuid", &new_guid)
-        .map_err(|e| BackupError::RegistryWriteError(e.to_string()))?;
+    let _lock = lock_backup_store()?;
+    let previous = read_machine_guid()?;
+    let pre_backup = backup_current_machine_guid_with(description)?;
 
-    let post_backup = backup_current_machine_guid(Some(format!("替换后自动备份: {}", new_guid)))?;
+    set_machine_guid_value(new_guid)?;
+
+    let post_backup = backup_current_machine_guid_with(Some(BackupDescription::Auto(
+        AutoDescription::AfterReplace {
+            guid: new_guid.to_string(),
+        },
+    )))?;
 
     let machine_id = read_machine_guid()?;
+    events::note_identity_written(&previous.guid, &machine_id.guid);
     Ok(WriteResult {
-        previous_guid: pre_backup
-            .as_ref()
-            .map(|b| b.guid.clone())
-            .unwrap_or_else(|| machine_id.guid.clone()),
+        previous_guid: previous.guid,
         new_guid: machine_id.guid.clone(),
         pre_backup,
         post_backup,
     })
 }
 
-#[cfg(not(windows))]
-pub fn write_machine_guid(
+#[cfg(not(any(windows, target_os = "linux")))]
+fn write_identity(
     _new_guid: &str,
-    _description: Option<String>,
+    _description: Option<BackupDescription>,
 ) -> Result<WriteResult, BackupError> {
     Err(BackupError::UnsupportedPlatform)
 }
@@ -515,11 +746,128 @@ pub fn generate_random_guid() -> Result<String, BackupError> {
     ))
 }
 
+/// 单次生成唯一 GUID 时的最大尝试次数
+const MAX_GENERATION_ATTEMPTS: usize = 32;
+
+/// 获取 GUID 黑名单文件路径
+/// 与备份文件位于同一目录，每行一个 GUID，`#` 开头的行为注释
+fn get_guid_blocklist_path() -> Result<PathBuf, BackupError> {
+    get_data_file_path("guid_blocklist.txt")
+}
+
+/// 解析 GUID 黑名单内容
+fn parse_guid_blocklist(content: &str) -> impl Iterator<Item = String> + '_ {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(normalize_identity)
+}
+
+/// 已使用过的 GUID 集合
+/// 包含当前机器码、所有历史备份以及黑名单中的值，比较时忽略大小写和连字符
+#[derive(Debug, Clone, Default)]
+pub struct GuidHistory {
+    guids: HashSet<String>,
+}
+
+impl GuidHistory {
+    /// 从当前机器码、备份存储和黑名单文件加载历史记录
+    pub fn load() -> Result<Self, BackupError> {
+        let mut history = GuidHistory::default();
+
+        if let Ok(current) = read_machine_guid() {
+            history.insert(&current.guid);
+        }
+
+        for backup in load_backup_store()?.backups {
+            history.insert(&backup.guid);
+        }
+
+        let blocklist_path = get_guid_blocklist_path()?;
+        if blocklist_path.exists() {
+            let content = fs::read_to_string(&blocklist_path).map_err(|e| {
+                BackupError::StorageError(tr(Message::GuidBlocklistReadFailed {
+                    reason: &e.to_string(),
+                }))
+            })?;
+            history.guids.extend(parse_guid_blocklist(&content));
+        }
+
+        Ok(history)
+    }
+
+    pub fn insert(&mut self, guid: &str) {
+        self.guids.insert(normalize_identity(guid));
+    }
+
+    pub fn contains(&self, guid: &str) -> bool {
+        self.guids.contains(&normalize_identity(guid))
+    }
+
+    /// 生成一个不在历史记录中的随机 GUID
+    pub fn generate_unique(&self) -> Result<UniqueGuid, BackupError> {
+        self.generate_unique_with(generate_random_guid)
+    }
+
+    /// 使用指定的候选值生成器生成唯一 GUID，命中历史记录的候选值会被拒绝并重新生成
+    fn generate_unique_with<F>(&self, mut next_candidate: F) -> Result<UniqueGuid, BackupError>
+    where
+        F: FnMut() -> Result<String, BackupError>,
+    {
+        let mut rejected_candidates = 0;
+        while rejected_candidates < MAX_GENERATION_ATTEMPTS {
+            let candidate = next_candidate()?;
+            if !self.contains(&candidate) {
+                return Ok(UniqueGuid {
+                    guid: candidate,
+                    rejected_candidates,
+                });
+            }
+            rejected_candidates += 1;
+        }
+        Err(BackupError::GuidGenerationExhausted(rejected_candidates))
+    }
+}
+
+/// 唯一 GUID 生成结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniqueGuid {
+    pub guid: String,
+    /// 因与历史记录冲突而被拒绝的候选值数量
+    pub rejected_candidates: usize,
+}
+
+/// 生成一个与当前机器码、历史备份及黑名单均不重复的随机 GUID
+pub fn generate_unique_guid() -> Result<UniqueGuid, BackupError> {
+    GuidHistory::load()?.generate_unique()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerateResult {
+    #[serde(flatten)]
+    pub write: WriteResult,
+    pub rejected_candidates: usize,
+}
+
 pub fn generate_random_machine_guid(
     description: Option<String>,
-) -> Result<WriteResult, BackupError> {
-    let new_guid = generate_random_guid()?;
-    write_machine_guid(&new_guid, description)
+) -> Result<GenerateResult, BackupError> {
+    generate_random_machine_guid_with(description.map(BackupDescription::Text))
+}
+
+/// 生成并写入随机机器码，`description` 用于写入前的备份
+pub fn generate_random_machine_guid_with(
+    description: Option<BackupDescription>,
+) -> Result<GenerateResult, BackupError> {
+    let UniqueGuid {
+        guid,
+        rejected_candidates,
+    } = generate_unique_guid()?;
+    Ok(GenerateResult {
+        write: write_machine_guid_with(&guid, description)?,
+        rejected_candidates,
+    })
 }
 
 /// 以管理员权限重启应用程序
@@ -632,6 +980,7 @@ mod tests {
                 source: "test".to_string(),
                 timestamp: 1234567890,
                 description: None,
+                auto_description: None,
             };
 
             store.add_backup(backup.clone());
@@ -714,6 +1063,45 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_operations_are_audited() {
+        use crate::audit::{query_audit_log, verify_audit_log, AuditQuery};
+
+        with_temp_backup_dir(|_temp_dir| {
+            let current = read_machine_guid_audited().ok();
+            let backup = backup_current_machine_guid(None).ok().flatten();
+            if let Some(backup) = &backup {
+                delete_backup(&backup.id).unwrap();
+            }
+            assert!(delete_backup("nonexistent_id").is_err());
+            clear_all_backups().unwrap();
+
+            let entries = query_audit_log(&AuditQuery::default()).unwrap();
+            let operations: Vec<_> = entries.iter().map(|e| e.operation).collect();
+            let mut expected = vec![AuditOperation::Clear, AuditOperation::Delete];
+            if backup.is_some() {
+                expected.extend([AuditOperation::Delete, AuditOperation::Backup]);
+            } else {
+                expected.push(AuditOperation::Backup);
+            }
+            expected.push(AuditOperation::Read);
+            assert_eq!(operations, expected);
+
+            // 失败的操作同样记录，并带有错误码
+            assert!(!entries[1].success);
+            assert_eq!(entries[1].error_code.as_deref(), Some("backup_not_found"));
+            // 只保存摘要，不保存明文
+            if let Some(current) = current {
+                let read = entries.last().unwrap();
+                assert_eq!(read.new_hash, Some(audit::hash_value(&current.guid)));
+                assert!(!serde_json::to_string(&entries)
+                    .unwrap()
+                    .contains(&current.guid));
+            }
+            assert!(verify_audit_log().unwrap().valid);
+        });
+    }
+
     #[test]
     fn test_clear_all_backups() {
         if !cfg!(target_os = "windows") {
@@ -771,6 +1159,11 @@ mod tests {
             let machine_id = result.unwrap();
             assert!(!machine_id.guid.is_empty());
             assert_eq!(machine_id.guid.len(), 36);
+        } else if cfg!(any(target_os = "macos", target_os = "linux")) {
+            // 结果取决于运行环境（例如容器中可能没有 /etc/machine-id）
+            if let Ok(machine_id) = result {
+                assert!(!machine_id.guid.is_empty());
+            }
         } else {
             assert!(result.is_err());
         }
@@ -808,6 +1201,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_identity_value_accepts_platform_format() {
+        assert!(validate_identity_value("550E8400-E29B-41D4-A716-446655440000").is_ok());
+        assert!(validate_identity_value("550E8400-E29B-41D4-A716").is_err());
+        // 不含连字符的 machine-id 仅在 Linux 上可以写入
+        assert_eq!(
+            validate_identity_value("550e8400e29b41d4a716446655440000").is_ok(),
+            cfg!(target_os = "linux")
+        );
+    }
+
     #[test]
     fn test_write_machine_guid_with_auto_backup() {
         if !cfg!(target_os = "windows") {
@@ -941,6 +1345,223 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_guid_history_rejects_known_candidates() {
+        let mut history = GuidHistory::default();
+        history.insert("550E8400-E29B-41D4-A716-446655440000");
+        history.insert("12345678-1234-1234-1234-123456789012");
+
+        let mut candidates = vec![
+            "550e8400-e29b-41d4-a716-446655440000",
+            "12345678-1234-1234-1234-123456789012",
+            "ffffffff-ffff-4fff-bfff-ffffffffffff",
+        ]
+        .into_iter();
+
+        let result = history
+            .generate_unique_with(|| Ok(candidates.next().unwrap().to_string()))
+            .expect("应该生成唯一的GUID");
+        assert_eq!(result.guid, "ffffffff-ffff-4fff-bfff-ffffffffffff");
+        assert_eq!(result.rejected_candidates, 2);
+    }
+
+    #[test]
+    fn test_guid_history_exhausted() {
+        let mut history = GuidHistory::default();
+        history.insert("550E8400-E29B-41D4-A716-446655440000");
+
+        let result =
+            history.generate_unique_with(|| Ok("550E8400-E29B-41D4-A716-446655440000".to_string()));
+        match result {
+            Err(BackupError::GuidGenerationExhausted(count)) => {
+                assert_eq!(count, MAX_GENERATION_ATTEMPTS)
+            }
+            other => panic!("应该返回 GuidGenerationExhausted: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_guid_history_loads_backups_and_blocklist() {
+        with_temp_backup_dir(|temp_dir| {
+            let mut store = BackupStore::new();
+            store.add_backup(MachineIdBackup {
+                id: "backup_1".to_string(),
+                guid: "550E8400-E29B-41D4-A716-446655440000".to_string(),
+                source: "test".to_string(),
+                timestamp: 1234567890,
+                description: None,
+                auto_description: None,
+            });
+            save_backup_store(&store).unwrap();
+
+            fs::write(
+                temp_dir.path.with_file_name("guid_blocklist.txt"),
+                "# 已在授权服务器上使用\n\nAAAAAAAA-BBBB-4CCC-8DDD-EEEEEEEEEEEE\n",
+            )
+            .unwrap();
+
+            let history = GuidHistory::load().unwrap();
+            assert!(history.contains("550e8400-e29b-41d4-a716-446655440000"));
+            assert!(history.contains("aaaaaaaa-bbbb-4ccc-8ddd-eeeeeeeeeeee"));
+            assert!(!history.contains("# 已在授权服务器上使用"));
+
+            let unique = history.generate_unique().unwrap();
+            assert!(!history.contains(&unique.guid));
+        });
+    }
+
+    #[test]
+    fn test_concurrent_backup_store_updates_are_not_lost() {
+        with_temp_backup_dir(|temp_dir| {
+            std::thread::scope(|scope| {
+                for thread in 0..4 {
+                    scope.spawn(move || {
+                        for i in 0..5 {
+                            with_backup_store(|store| {
+                                store.add_backup(MachineIdBackup {
+                                    id: format!("backup_{}_{}", thread, i),
+                                    guid: format!("guid-{}-{}", thread, i),
+                                    source: "test".to_string(),
+                                    timestamp: 1234567890,
+                                    description: None,
+                                    auto_description: None,
+                                });
+                                Ok(())
+                            })
+                            .unwrap();
+                        }
+                    });
+                }
+            });
+
+            assert_eq!(get_backup_count().unwrap(), 20);
+            // 写入经临时文件重命名完成，目录中不留下临时文件
+            let leftovers = fs::read_dir(temp_dir.path.parent().unwrap())
+                .unwrap()
+                .filter(|entry| {
+                    let name = entry.as_ref().unwrap().file_name();
+                    name.to_string_lossy().ends_with(".tmp")
+                })
+                .count();
+            assert_eq!(leftovers, 0);
+        });
+    }
+
+    #[test]
+    fn test_identity_comparison_ignores_machine_id_format() {
+        let mut store = BackupStore::new();
+        store.add_backup(MachineIdBackup {
+            id: "backup_1".to_string(),
+            guid: "550e8400e29b41d4a716446655440000".to_string(),
+            source: "/etc/machine-id".to_string(),
+            timestamp: 1234567890,
+            description: None,
+            auto_description: None,
+        });
+        assert!(store.has_guid("550E8400-E29B-41D4-A716-446655440000"));
+        assert!(!store.has_guid("550E8400-E29B-41D4-A716-446655440001"));
+
+        let mut history = GuidHistory::default();
+        history.insert("550e8400e29b41d4a716446655440000");
+        history.guids.extend(parse_guid_blocklist(
+            "AAAAAAAA-BBBB-4CCC-8DDD-EEEEEEEEEEEE\n",
+        ));
+        assert!(history.contains("550E8400-E29B-41D4-A716-446655440000"));
+        assert!(history.contains("aaaaaaaabbbb4ccc8dddeeeeeeeeeeee"));
+        assert!(!history.contains("550e8400e29b41d4a716446655440001"));
+    }
+
+    #[test]
+    fn test_backup_store_localizes_auto_descriptions() {
+        let backup = |id: &str, description: Option<&str>| MachineIdBackup {
+            id: id.to_string(),
+            guid: "550E8400-E29B-41D4-A716-446655440000".to_string(),
+            source: "test".to_string(),
+            timestamp: 1234567890,
+            description: description.map(str::to_string),
+            auto_description: None,
+        };
+        let mut store = BackupStore::new();
+        store.add_backup(backup("backup_1", Some("替换后自动备份: abc")));
+        store.add_backup(backup("backup_2", Some("切换到配置 办公 前自动备份")));
+        store.add_backup(backup("backup_3", Some("部署前手动备份")));
+        store.add_backup(backup("backup_4", None));
+
+        store.localize(Locale::En);
+        let by_id = |id: &str| store.get_backup(id).unwrap().clone();
+        assert_eq!(
+            by_id("backup_1").auto_description,
+            Some(AutoDescription::AfterReplace {
+                guid: "abc".to_string()
+            })
+        );
+        assert_eq!(
+            by_id("backup_1").description.as_deref(),
+            Some("Automatic backup after replace: abc")
+        );
+        assert_eq!(
+            by_id("backup_2").description.as_deref(),
+            Some("Automatic backup before switching to profile 办公")
+        );
+        assert_eq!(by_id("backup_3").auto_description, None);
+        assert_eq!(
+            by_id("backup_3").description.as_deref(),
+            Some("部署前手动备份")
+        );
+        assert_eq!(by_id("backup_4").description, None);
+
+        // 结构化描述可以再次按其他语言渲染
+        store.localize(Locale::ZhCn);
+        assert_eq!(
+            store.get_backup("backup_1").unwrap().description.as_deref(),
+            Some("替换后自动备份: abc")
+        );
+    }
+
+    #[test]
+    fn test_auto_description_legacy_formats() {
+        assert_eq!(
+            AutoDescription::from_legacy("恢复前自动备份: 从备份 backup_1 恢复到 abc"),
+            Some(AutoDescription::BeforeRestore {
+                backup_id: "backup_1".to_string(),
+                guid: "abc".to_string()
+            })
+        );
+        assert_eq!(
+            AutoDescription::from_legacy("临时写入到期自动恢复: revert_1"),
+            Some(AutoDescription::RevertExpired {
+                revert_id: "revert_1".to_string()
+            })
+        );
+        assert_eq!(
+            AutoDescription::from_legacy("计划任务自动轮换"),
+            Some(AutoDescription::ScheduledRotation)
+        );
+        assert_eq!(AutoDescription::from_legacy("恢复前自动备份"), None);
+        assert_eq!(AutoDescription::from_legacy("自动备份测试"), None);
+    }
+
+    #[test]
+    fn test_update_description_clears_auto_description() {
+        with_temp_backup_dir(|_temp_dir| {
+            let mut store = BackupStore::new();
+            store.add_backup(MachineIdBackup {
+                id: "backup_1".to_string(),
+                guid: "550E8400-E29B-41D4-A716-446655440000".to_string(),
+                source: "test".to_string(),
+                timestamp: 1234567890,
+                description: None,
+                auto_description: Some(AutoDescription::ScheduledRotation),
+            });
+            save_backup_store(&store).unwrap();
+
+            let updated =
+                update_backup_description("backup_1", Some("计划任务自动轮换".to_string()))
+                    .unwrap();
+            assert_eq!(updated.auto_description, None);
+        });
+    }
+
     #[test]
     fn test_backup_store_has_guid() {
         with_temp_backup_dir(|_temp_dir| {
@@ -953,6 +1574,7 @@ mod tests {
                 source: "test".to_string(),
                 timestamp: 1234567890,
                 description: None,
+                auto_description: None,
             };
             store.add_backup(backup1.clone());
             assert!(store.has_guid("550E8400-E29B-41D4-A716-446655440000"));
@@ -964,6 +1586,7 @@ mod tests {
                 source: "test".to_string(),
                 timestamp: 1234567891,
                 description: None,
+                auto_description: None,
             };
             store.add_backup(backup2.clone());
             assert!(store.has_guid("550E8400-E29B-41D4-A716-446655440000"));
@@ -983,6 +1606,7 @@ mod tests {
                 source: "test".to_string(),
                 timestamp: 1234567890,
                 description: None,
+                auto_description: None,
             };
             store.add_backup(backup1.clone());
 
@@ -1148,6 +1772,7 @@ mod tests {
                     source: "test".to_string(),
                     timestamp: 1234567890,
                     description: None,
+                    auto_description: None,
                 };
                 store.add_backup(backup);
             }
@@ -0,0 +1,564 @@
+//! 按标识符细分的能力检测
+//!
+//! [`check_admin_permissions`](super::permissions::check_admin_permissions) 只回答“是否有管理员权限”，
+//! 这里进一步检查每个标识符能否读取、能否写入，以及写入会因为什么失败（只读挂载、immutable 属性、
+//! 缺少 `CAP_DAC_OVERRIDE`、SELinux/AppArmor 限制等），界面据此在用户操作之前说明原因。
+//! 只对当前平台有意义的检查项在其他平台上为 `None`。
+
+use serde::Serialize;
+use tracing::info;
+
+use crate::machine_id::read_machine_guid;
+use crate::profiles::MACHINE_GUID_IDENTIFIER;
+
+/// 读取或写入无法进行的原因
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum CapabilityIssue {
+    /// 无法读取当前值
+    Unreadable { reason: String },
+    /// 当前平台不支持写入
+    WriteUnsupported,
+    /// 既没有管理员权限，也没有可用的提权途径
+    MissingPrivilege,
+    /// 文件所在的文件系统以只读方式挂载
+    ReadOnlyFilesystem { path: String },
+    /// 文件设置了 immutable 属性（`chattr +i`），root 也无法替换
+    ImmutableAttribute { path: String },
+    /// 当前进程受 SELinux 或 AppArmor 限制，写入可能被拒绝
+    MacDenialLikely { module: String, context: String },
+}
+
+/// 单个标识符的能力
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ProviderCapabilities {
+    /// 标识符键名，与命名身份配置中的键一致
+    pub identifier: String,
+    /// 标识符的存储位置（文件路径或注册表路径）
+    pub location: String,
+    pub can_read: bool,
+    pub can_write: bool,
+    /// 写入方式：`direct`、`polkit_helper` 或 `registry`，无法写入时为 None
+    pub write_method: Option<String>,
+    /// 能否同步写入 `/var/lib/dbus/machine-id`，该文件不存在时为 None（仅 Linux）
+    pub can_write_dbus_id: Option<bool>,
+    /// 所在文件系统是否只读挂载（仅 Linux）
+    pub read_only_filesystem: Option<bool>,
+    /// 是否设置了 immutable 属性（仅 Linux）
+    pub immutable: Option<bool>,
+    /// 当前进程是否具有 `CAP_DAC_OVERRIDE`（仅 Linux）
+    pub cap_dac_override: Option<bool>,
+    /// SELinux/AppArmor 是否可能拒绝写入（仅 Linux）
+    pub mac_denial_likely: Option<bool>,
+    /// 读取或写入无法进行的原因，全部能力可用时为空
+    pub issues: Vec<CapabilityIssue>,
+}
+
+/// 能力检测报告
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct CapabilityReport {
+    pub platform: String,
+    pub providers: Vec<ProviderCapabilities>,
+}
+
+impl ProviderCapabilities {
+    /// 只填写公共字段，平台相关字段留空
+    fn new(location: &str) -> Self {
+        let (can_read, issues) = match read_machine_guid() {
+            Ok(_) => (true, Vec::new()),
+            Err(e) => (
+                false,
+                vec![CapabilityIssue::Unreadable {
+                    reason: e.to_string(),
+                }],
+            ),
+        };
+        ProviderCapabilities {
+            identifier: MACHINE_GUID_IDENTIFIER.to_string(),
+            location: location.to_string(),
+            can_read,
+            can_write: false,
+            write_method: None,
+            can_write_dbus_id: None,
+            read_only_filesystem: None,
+            immutable: None,
+            cap_dac_override: None,
+            mac_denial_likely: None,
+            issues,
+        }
+    }
+}
+
+/// 检测当前平台各标识符的读写能力
+pub fn check_capabilities() -> CapabilityReport {
+    let providers = vec![machine_guid_capabilities()];
+    for provider in &providers {
+        info!(
+            "能力检测: {} can_read={}, can_write={}, issues={:?}",
+            provider.identifier, provider.can_read, provider.can_write, provider.issues
+        );
+    }
+    CapabilityReport {
+        platform: std::env::consts::OS.to_string(),
+        providers,
+    }
+}
+
+#[cfg(windows)]
+fn machine_guid_capabilities() -> ProviderCapabilities {
+    let mut caps =
+        ProviderCapabilities::new("HKLM\\SOFTWARE\\Microsoft\\Cryptography\\MachineGuid");
+    if super::permissions::check_registry_write_permission().has_permission {
+        caps.can_write = true;
+        caps.write_method = Some("registry".to_string());
+    } else {
+        caps.issues.push(CapabilityIssue::MissingPrivilege);
+    }
+    caps
+}
+
+#[cfg(target_os = "macos")]
+fn machine_guid_capabilities() -> ProviderCapabilities {
+    let mut caps = ProviderCapabilities::new("IOPlatformUUID");
+    caps.issues.push(CapabilityIssue::WriteUnsupported);
+    caps
+}
+
+#[cfg(target_os = "linux")]
+fn machine_guid_capabilities() -> ProviderCapabilities {
+    let mut caps = ProviderCapabilities::new(linux::MACHINE_ID_FILES[0]);
+    linux::Probe::collect().apply(&mut caps);
+    caps
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn machine_guid_capabilities() -> ProviderCapabilities {
+    let mut caps = ProviderCapabilities::new("");
+    caps.issues.push(CapabilityIssue::WriteUnsupported);
+    caps
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::ffi::CString;
+    use std::fs;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::path::Path;
+
+    use super::{CapabilityIssue, ProviderCapabilities};
+
+    pub use crate::platform::linux::MACHINE_ID_FILES;
+
+    /// `CAP_DAC_OVERRIDE` 在能力位图中的位置
+    const CAP_DAC_OVERRIDE: u32 = 1;
+    /// `linux/fs.h` 中的 immutable 标志，libc 未导出
+    const FS_IMMUTABLE_FL: libc::c_long = 0x0000_0010;
+
+    /// 单个文件的状态
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct FileProbe {
+        pub path: String,
+        pub read_only_filesystem: bool,
+        pub immutable: bool,
+    }
+
+    /// 影响写入的全部观测结果，与判断逻辑分开以便测试
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct Probe {
+        pub machine_id: FileProbe,
+        /// 独立的 dbus 机器码文件；为符号链接或不存在时为 None
+        pub dbus_file: Option<FileProbe>,
+        pub dbus_exists: bool,
+        /// 有效用户是否为 root，写入时据此选择直接写入或经辅助程序写入
+        pub is_root: bool,
+        pub cap_dac_override: bool,
+        pub helper_available: bool,
+        /// 可能拒绝写入的强制访问控制模块及当前进程的安全上下文
+        pub mac_confinement: Option<(String, String)>,
+    }
+
+    impl FileProbe {
+        fn collect(path: &str) -> Self {
+            FileProbe {
+                path: path.to_string(),
+                read_only_filesystem: is_read_only_filesystem(Path::new(path)),
+                immutable: is_immutable(Path::new(path)),
+            }
+        }
+
+        fn issues(&self) -> Vec<CapabilityIssue> {
+            let mut issues = Vec::new();
+            if self.read_only_filesystem {
+                issues.push(CapabilityIssue::ReadOnlyFilesystem {
+                    path: self.path.clone(),
+                });
+            }
+            if self.immutable {
+                issues.push(CapabilityIssue::ImmutableAttribute {
+                    path: self.path.clone(),
+                });
+            }
+            issues
+        }
+    }
+
+    impl Probe {
+        pub fn collect() -> Self {
+            let dbus = MACHINE_ID_FILES[1];
+            let dbus_metadata = fs::symlink_metadata(dbus).ok();
+            Probe {
+                machine_id: FileProbe::collect(MACHINE_ID_FILES[0]),
+                dbus_file: dbus_metadata
+                    .as_ref()
+                    .filter(|metadata| metadata.file_type().is_file())
+                    .map(|_| FileProbe::collect(dbus)),
+                dbus_exists: dbus_metadata.is_some(),
+                is_root: crate::platform::linux::is_root(),
+                cap_dac_override: fs::read_to_string("/proc/self/status")
+                    .ok()
+                    .and_then(|status| effective_capabilities(&status))
+                    .is_some_and(|caps| caps & (1 << CAP_DAC_OVERRIDE) != 0),
+                helper_available: crate::helper::is_available(),
+                mac_confinement: selinux_confinement().or_else(apparmor_confinement),
+            }
+        }
+
+        /// 根据观测结果填写能力
+        ///
+        /// 写入方式与实际写入时的选择一致：root 直接写入（还需要 `CAP_DAC_OVERRIDE`），
+        /// 其他用户经辅助程序写入。特权辅助程序以 root 身份运行，但仍然受文件系统和
+        /// immutable 属性的限制；强制访问控制只针对当前进程，经辅助程序写入时不计入。
+        pub fn apply(&self, caps: &mut ProviderCapabilities) {
+            caps.read_only_filesystem = Some(self.machine_id.read_only_filesystem);
+            caps.immutable = Some(self.machine_id.immutable);
+            caps.cap_dac_override = Some(self.cap_dac_override);
+            caps.mac_denial_likely = Some(self.mac_confinement.is_some());
+
+            let method = if self.is_root {
+                (self.cap_dac_override && self.mac_confinement.is_none()).then_some("direct")
+            } else if self.helper_available {
+                Some("polkit_helper")
+            } else {
+                None
+            };
+            if method.is_none() {
+                match &self.mac_confinement {
+                    Some((module, context)) if self.is_root && self.cap_dac_override => {
+                        caps.issues.push(CapabilityIssue::MacDenialLikely {
+                            module: module.clone(),
+                            context: context.clone(),
+                        })
+                    }
+                    _ => caps.issues.push(CapabilityIssue::MissingPrivilege),
+                }
+            }
+
+            let file_issues = self.machine_id.issues();
+            caps.can_write = method.is_some() && file_issues.is_empty();
+            caps.write_method = method.filter(|_| caps.can_write).map(str::to_string);
+            caps.issues.extend(file_issues);
+
+            caps.can_write_dbus_id = match &self.dbus_file {
+                Some(dbus) => {
+                    let dbus_issues = dbus.issues();
+                    let writable = caps.can_write && dbus_issues.is_empty();
+                    caps.issues.extend(dbus_issues);
+                    Some(writable)
+                }
+                // 指向 /etc/machine-id 的符号链接随之更新
+                None if self.dbus_exists => Some(caps.can_write),
+                None => None,
+            };
+        }
+    }
+
+    /// 解析 `/proc/self/status` 中的有效能力集（CapEff）
+    pub fn effective_capabilities(status: &str) -> Option<u64> {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("CapEff:"))
+            .and_then(|value| u64::from_str_radix(value.trim(), 16).ok())
+    }
+
+    /// SELinux 处于强制模式且当前进程不在 unconfined 域中时，写入可能被拒绝
+    pub fn selinux_denial_likely(enforce: &str, context: &str) -> bool {
+        let domain = context.split(':').nth(2).unwrap_or("");
+        enforce.trim() == "1" && !domain.is_empty() && !domain.contains("unconfined")
+    }
+
+    /// AppArmor 标签形如 `unconfined` 或 `profile (enforce)`，只有强制模式的配置会拒绝写入
+    pub fn apparmor_denial_likely(label: &str) -> bool {
+        label.trim().ends_with("(enforce)")
+    }
+
+    fn selinux_confinement() -> Option<(String, String)> {
+        let enforce = fs::read_to_string("/sys/fs/selinux/enforce").ok()?;
+        let context = read_label("/proc/self/attr/current")?;
+        selinux_denial_likely(&enforce, &context).then(|| ("selinux".to_string(), context))
+    }
+
+    fn apparmor_confinement() -> Option<(String, String)> {
+        let label = read_label("/proc/self/attr/apparmor/current")?;
+        apparmor_denial_likely(&label).then(|| ("apparmor".to_string(), label))
+    }
+
+    fn read_label(path: &str) -> Option<String> {
+        let label = fs::read_to_string(path).ok()?;
+        Some(label.trim_end_matches(['\0', '\n']).to_string())
+    }
+
+    fn is_read_only_filesystem(path: &Path) -> bool {
+        let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+            return false;
+        };
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        unsafe {
+            libc::statvfs(path.as_ptr(), &mut stat) == 0 && stat.f_flag & libc::ST_RDONLY != 0
+        }
+    }
+
+    /// 读取文件属性标志；文件系统不支持属性（如 tmpfs）时视为未设置
+    fn is_immutable(path: &Path) -> bool {
+        use std::os::fd::AsRawFd;
+
+        let Ok(file) = fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)
+        else {
+            return false;
+        };
+        let mut flags: libc::c_long = 0;
+        let result = unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) };
+        result == 0 && flags & FS_IMMUTABLE_FL != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_capabilities() {
+        let report = check_capabilities();
+        assert_eq!(report.platform, std::env::consts::OS);
+        assert_eq!(report.providers.len(), 1);
+
+        let caps = &report.providers[0];
+        assert_eq!(caps.identifier, MACHINE_GUID_IDENTIFIER);
+        // 无法写入时必须说明原因
+        assert!(caps.can_write || !caps.issues.is_empty());
+        assert_eq!(caps.can_write, caps.write_method.is_some());
+    }
+
+    #[test]
+    fn test_issue_tags() {
+        let issue = CapabilityIssue::ImmutableAttribute {
+            path: "/etc/machine-id".to_string(),
+        };
+        let value = serde_json::to_value(&issue).unwrap();
+        assert_eq!(value["kind"], "immutable_attribute");
+        assert_eq!(value["path"], "/etc/machine-id");
+    }
+
+    #[cfg(target_os = "linux")]
+    mod linux_probe {
+        use super::super::linux::*;
+        use super::super::*;
+
+        fn blank() -> ProviderCapabilities {
+            ProviderCapabilities {
+                identifier: MACHINE_GUID_IDENTIFIER.to_string(),
+                location: MACHINE_ID_FILES[0].to_string(),
+                can_read: true,
+                can_write: false,
+                write_method: None,
+                can_write_dbus_id: None,
+                read_only_filesystem: None,
+                immutable: None,
+                cap_dac_override: None,
+                mac_denial_likely: None,
+                issues: Vec::new(),
+            }
+        }
+
+        fn root() -> Probe {
+            Probe {
+                machine_id: FileProbe {
+                    path: MACHINE_ID_FILES[0].to_string(),
+                    ..Default::default()
+                },
+                is_root: true,
+                cap_dac_override: true,
+                ..Default::default()
+            }
+        }
+
+        fn apply(probe: &Probe) -> ProviderCapabilities {
+            let mut caps = blank();
+            probe.apply(&mut caps);
+            caps
+        }
+
+        #[test]
+        fn test_root_writes_directly() {
+            let caps = apply(&root());
+            assert!(caps.can_write);
+            assert_eq!(caps.write_method.as_deref(), Some("direct"));
+            assert_eq!(caps.can_write_dbus_id, None);
+            assert!(caps.issues.is_empty());
+        }
+
+        #[test]
+        fn test_user_without_helper_lacks_privilege() {
+            let probe = Probe {
+                is_root: false,
+                cap_dac_override: false,
+                ..root()
+            };
+            let caps = apply(&probe);
+            assert!(!caps.can_write);
+            assert_eq!(caps.write_method, None);
+            assert_eq!(caps.issues, vec![CapabilityIssue::MissingPrivilege]);
+
+            let caps = apply(&Probe {
+                helper_available: true,
+                ..probe
+            });
+            assert_eq!(caps.write_method.as_deref(), Some("polkit_helper"));
+        }
+
+        #[test]
+        fn test_write_method_follows_effective_user() {
+            // 非 root 进程即使具有 CAP_DAC_OVERRIDE，写入时也经辅助程序
+            let caps = apply(&Probe {
+                is_root: false,
+                helper_available: true,
+                ..root()
+            });
+            assert_eq!(caps.write_method.as_deref(), Some("polkit_helper"));
+            let caps = apply(&Probe {
+                is_root: false,
+                ..root()
+            });
+            assert!(!caps.can_write);
+            assert_eq!(caps.issues, vec![CapabilityIssue::MissingPrivilege]);
+
+            // 容器中去掉了 CAP_DAC_OVERRIDE 的 root 不会改用辅助程序
+            let caps = apply(&Probe {
+                cap_dac_override: false,
+                helper_available: true,
+                ..root()
+            });
+            assert!(!caps.can_write);
+            assert_eq!(caps.issues, vec![CapabilityIssue::MissingPrivilege]);
+        }
+
+        #[test]
+        fn test_file_blockers_prevent_write() {
+            let mut probe = root();
+            probe.machine_id.read_only_filesystem = true;
+            probe.machine_id.immutable = true;
+            let caps = apply(&probe);
+            assert!(!caps.can_write);
+            assert_eq!(caps.write_method, None);
+            assert_eq!(caps.read_only_filesystem, Some(true));
+            assert_eq!(caps.immutable, Some(true));
+            assert_eq!(
+                caps.issues,
+                vec![
+                    CapabilityIssue::ReadOnlyFilesystem {
+                        path: MACHINE_ID_FILES[0].to_string()
+                    },
+                    CapabilityIssue::ImmutableAttribute {
+                        path: MACHINE_ID_FILES[0].to_string()
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn test_mac_confinement() {
+            let confined = Probe {
+                mac_confinement: Some(("apparmor".to_string(), "app (enforce)".to_string())),
+                ..root()
+            };
+            let caps = apply(&confined);
+            assert!(!caps.can_write);
+            assert_eq!(caps.mac_denial_likely, Some(true));
+            assert!(matches!(
+                caps.issues[..],
+                [CapabilityIssue::MacDenialLikely { .. }]
+            ));
+
+            // root 写入时不经辅助程序
+            let caps = apply(&Probe {
+                helper_available: true,
+                ..confined.clone()
+            });
+            assert!(!caps.can_write);
+
+            // 辅助程序不受当前进程的限制
+            let caps = apply(&Probe {
+                is_root: false,
+                helper_available: true,
+                ..confined
+            });
+            assert_eq!(caps.write_method.as_deref(), Some("polkit_helper"));
+            assert!(caps.issues.is_empty());
+        }
+
+        #[test]
+        fn test_dbus_id() {
+            let caps = apply(&Probe {
+                dbus_exists: true,
+                ..root()
+            });
+            assert_eq!(caps.can_write_dbus_id, Some(true));
+
+            let dbus = FileProbe {
+                path: MACHINE_ID_FILES[1].to_string(),
+                immutable: true,
+                ..Default::default()
+            };
+            let caps = apply(&Probe {
+                dbus_exists: true,
+                dbus_file: Some(dbus),
+                ..root()
+            });
+            assert!(caps.can_write);
+            assert_eq!(caps.can_write_dbus_id, Some(false));
+            assert_eq!(
+                caps.issues,
+                vec![CapabilityIssue::ImmutableAttribute {
+                    path: MACHINE_ID_FILES[1].to_string()
+                }]
+            );
+        }
+
+        #[test]
+        fn test_parsers() {
+            let status = "Name:\tcat\nCapInh:\t0000000000000000\nCapEff:\t000001ffffffffff\n";
+            assert_eq!(effective_capabilities(status), Some(0x1ff_ffff_ffff));
+            assert_eq!(effective_capabilities("Name:\tcat\n"), None);
+
+            assert!(selinux_denial_likely("1", "system_u:system_r:httpd_t:s0"));
+            assert!(!selinux_denial_likely(
+                "1",
+                "unconfined_u:unconfined_r:unconfined_t:s0-s0:c0.c1023"
+            ));
+            assert!(!selinux_denial_likely("0", "system_u:system_r:httpd_t:s0"));
+
+            assert!(apparmor_denial_likely("snap.app.app (enforce)\n"));
+            assert!(!apparmor_denial_likely("snap.app.app (complain)"));
+            assert!(!apparmor_denial_likely("unconfined"));
+        }
+    }
+}
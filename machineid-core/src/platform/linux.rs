@@ -0,0 +1,124 @@
+//! Linux 机器码文件的读写
+//!
+//! systemd 和 D-Bus 从 `/etc/machine-id` 读取机器标识，格式为 32 位小写十六进制、不含连字符；
+//! 部分发行版另有独立的 `/var/lib/dbus/machine-id`（通常是指向前者的符号链接）。
+//! 写入需要 root 权限，普通用户通过 [`crate::helper`] 经 pkexec 调用特权辅助程序完成。
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+use tracing::info;
+
+use crate::machine_id::{validate_guid_format, BackupError};
+
+/// 机器码文件，按读取优先级排列，文件名均为 `machine-id`
+pub const MACHINE_ID_FILES: [&str; 2] = ["/etc/machine-id", "/var/lib/dbus/machine-id"];
+
+/// 本次开机的标识，由内核在每次开机时随机生成
+pub fn boot_id() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/random/boot_id")
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// 当前进程是否以 root 身份运行
+pub fn is_root() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// 将 GUID 或 machine-id 格式的值转换为 machine-id 格式
+///
+/// 全零值在 systemd 中表示“未初始化”，视为无效。
+pub fn to_machine_id(value: &str) -> Result<String, BackupError> {
+    let hex = if validate_guid_format(value).is_ok() {
+        value.replace('-', "")
+    } else {
+        value.to_string()
+    };
+    let valid = hex.len() == 32
+        && hex.bytes().all(|b| b.is_ascii_hexdigit())
+        && hex.bytes().any(|b| b != b'0');
+    if !valid {
+        return Err(BackupError::InvalidGuidFormat(value.to_string()));
+    }
+    Ok(hex.to_ascii_lowercase())
+}
+
+/// 原子地替换机器码文件：先写入同目录下的临时文件并同步，再重命名覆盖
+fn write_machine_id_at(path: &Path, machine_id: &str) -> Result<(), BackupError> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("/"));
+    let temp = dir.join(format!(".machine-id.{}.tmp", std::process::id()));
+    let result = (|| -> std::io::Result<()> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o444)
+            .open(&temp)?;
+        file.write_all(format!("{}\n", machine_id).as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp, path)?;
+        fs::File::open(dir)?.sync_all()
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result.map_err(|e| BackupError::RegistryWriteError(format!("{}: {}", path.display(), e)))
+}
+
+/// 写入机器码，需要 root 权限
+///
+/// `/etc/machine-id` 总是写入；`/var/lib/dbus/machine-id` 仅在它是独立的普通文件时同步更新。
+pub fn write_machine_id(value: &str) -> Result<(), BackupError> {
+    let machine_id = to_machine_id(value)?;
+    write_machine_id_at(Path::new(MACHINE_ID_FILES[0]), &machine_id)?;
+    let dbus = Path::new(MACHINE_ID_FILES[1]);
+    if fs::symlink_metadata(dbus).is_ok_and(|metadata| metadata.file_type().is_file()) {
+        write_machine_id_at(dbus, &machine_id)?;
+    }
+    info!("已写入机器码文件: {}", machine_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_machine_id() {
+        assert_eq!(
+            to_machine_id("550E8400-E29B-41D4-A716-446655440000").unwrap(),
+            "550e8400e29b41d4a716446655440000"
+        );
+        assert_eq!(
+            to_machine_id("550e8400e29b41d4a716446655440000").unwrap(),
+            "550e8400e29b41d4a716446655440000"
+        );
+        for invalid in [
+            "",
+            "550e8400e29b41d4a71644665544000",
+            "550e8400-e29b41d4a716446655440000",
+            "550e8400e29b41d4a71644665544000g",
+            "00000000000000000000000000000000",
+        ] {
+            assert!(to_machine_id(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_write_machine_id_at_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("machine-id");
+        fs::write(&path, "old\n").unwrap();
+
+        write_machine_id_at(&path, "550e8400e29b41d4a716446655440000").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "550e8400e29b41d4a716446655440000\n"
+        );
+        // 不留下临时文件
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
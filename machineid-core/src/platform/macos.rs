@@ -0,0 +1,18 @@
+// macOS 平台特定实现
+// 目前权限检测逻辑在 permissions.rs 中统一处理
+
+/// 本次开机的时间（Unix 秒），读取自 `kern.boottime`
+pub fn boot_time() -> Option<u64> {
+    let mut boot_time: libc::timeval = unsafe { std::mem::zeroed() };
+    let mut size = std::mem::size_of::<libc::timeval>();
+    let ret = unsafe {
+        libc::sysctlbyname(
+            c"kern.boottime".as_ptr(),
+            &mut boot_time as *mut libc::timeval as *mut libc::c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    (ret == 0 && boot_time.tv_sec > 0).then_some(boot_time.tv_sec as u64)
+}
@@ -1,3 +1,4 @@
+pub mod capabilities;
 pub mod permissions;
 
 #[cfg(target_os = "windows")]
@@ -1,7 +1,12 @@
+#[cfg(any(target_os = "linux", windows))]
+use crate::i18n::{tr, Message};
 use crate::machine_id::BackupError;
+use crate::restart_state::PendingAction;
 use serde::Serialize;
-use std::path::PathBuf;
-use tracing::{error, info, warn};
+use std::time::Duration;
+#[cfg(windows)]
+use tracing::error;
+use tracing::{info, warn};
 
 /// 权限检查结果
 #[derive(Debug, Clone, Serialize)]
@@ -52,6 +57,8 @@ pub struct RestartResult {
     pub success: bool,
     pub message: String,
     pub platform: String,
+    /// 程序是否会重启；为 false 时无需重启即可继续操作
+    pub restarting: bool,
 }
 
 /// 检查是否以管理员身份运行
@@ -88,7 +95,7 @@ pub fn check_admin_permissions() -> PermissionCheckResult {
 
 /// Windows: 检查注册表写入权限
 #[cfg(windows)]
-fn check_registry_write_permission() -> PermissionCheckResult {
+pub(crate) fn check_registry_write_permission() -> PermissionCheckResult {
     use winreg::enums::*;
     use winreg::RegKey;
 
@@ -137,25 +144,26 @@ pub fn check_admin_permissions() -> PermissionCheckResult {
     )
 }
 
+/// Linux 下普通用户可以通过 pkexec 调用特权辅助程序写入，辅助程序可用时同样视为有权限
 #[cfg(target_os = "linux")]
 pub fn check_admin_permissions() -> PermissionCheckResult {
     info!("开始检查管理员权限 (Linux)");
     let uid = unsafe { libc::getuid() };
-    let has_permission = uid == 0;
+    let method = if uid == 0 {
+        "root_user"
+    } else if crate::helper::is_available() {
+        "polkit_helper"
+    } else {
+        "non_root_user"
+    };
+    let has_permission = method != "non_root_user";
 
     info!(
-        "权限检查结果: has_permission={}, uid={}",
-        has_permission, uid
+        "权限检查结果: has_permission={}, uid={}, method={}",
+        has_permission, uid, method
     );
 
-    PermissionCheckResult::success(
-        has_permission,
-        if has_permission {
-            "root_user"
-        } else {
-            "non_root_user"
-        },
-    )
+    PermissionCheckResult::success(has_permission, method)
 }
 
 #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
@@ -164,18 +172,27 @@ pub fn check_admin_permissions() -> PermissionCheckResult {
     PermissionCheckResult::error("unsupported_platform", "不支持的操作系统", None)
 }
 
+/// 提权重启时传给新进程的参数，后跟原进程 PID
+/// 新进程等待原进程退出后才开始初始化，保证任何时刻只有一个进程写入备份和机器码
+pub const ELEVATION_HANDOFF_ARG: &str = "--handoff-from";
+
+/// 提权成功后原进程的退出延迟（毫秒），留给前端处理响应
+#[cfg(windows)]
+const ELEVATION_EXIT_DELAY_MS: u64 = 500;
+
 /// 申请提升权限（以管理员身份重启）
 /// 使用 Windows ShellExecute API 触发 UAC 提权对话框
 ///
-/// 返回 RestartResult，成功后会延迟退出当前进程
+/// 返回 RestartResult，成功后会延迟退出当前进程；新进程带 [`ELEVATION_HANDOFF_ARG`] 启动，
+/// 在当前进程退出前不会开始工作。`pending_action` 保存在重启状态中，由新进程继续
 #[cfg(windows)]
-pub fn request_elevation() -> Result<RestartResult, BackupError> {
+pub fn request_elevation(
+    pending_action: Option<PendingAction>,
+) -> Result<RestartResult, BackupError> {
     use std::env;
     use std::ffi::OsStr;
     use std::os::windows::ffi::OsStrExt;
-    use std::ptr;
     use std::thread;
-    use std::time::Duration;
     use windows::Win32::Foundation::HWND;
     use windows::Win32::UI::Shell::ShellExecuteW;
     use windows::Win32::UI::WindowsAndMessaging::SHOW_WINDOW_CMD;
@@ -184,17 +201,21 @@ pub fn request_elevation() -> Result<RestartResult, BackupError> {
 
     let current_exe = env::current_exe().map_err(|e| {
         error!("无法获取当前程序路径: {}", e);
-        BackupError::StorageError(format!("无法获取当前程序路径: {}", e))
+        BackupError::StorageError(tr(Message::ElevationExePathUnavailable {
+            reason: &e.to_string(),
+        }))
     })?;
 
     // 获取当前工作目录
     let current_dir = env::current_dir().map_err(|e| {
         error!("无法获取当前工作目录: {}", e);
-        BackupError::StorageError(format!("无法获取当前工作目录: {}", e))
+        BackupError::StorageError(tr(Message::ElevationWorkingDirUnavailable {
+            reason: &e.to_string(),
+        }))
     })?;
 
-    // 保存重启状态，以便重启后恢复
-    if let Err(e) = save_restart_state() {
+    // 保存重启状态，以便重启后继续用户的操作
+    if let Err(e) = crate::restart_state::save_restart_state(pending_action) {
         warn!("保存重启状态失败: {}", e);
     }
 
@@ -211,6 +232,12 @@ pub fn request_elevation() -> Result<RestartResult, BackupError> {
         .chain(Some(0))
         .collect();
 
+    let parameters_wide: Vec<u16> =
+        OsStr::new(&format!("{} {}", ELEVATION_HANDOFF_ARG, std::process::id()))
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+
     // "runas" 操作 verb - 这会触发 UAC 提权
     let runas_verb: Vec<u16> = OsStr::new("runas").encode_wide().chain(Some(0)).collect();
 
@@ -224,7 +251,7 @@ pub fn request_elevation() -> Result<RestartResult, BackupError> {
             HWND(0),                                          // hwnd - 无父窗口
             windows::core::PCWSTR(runas_verb.as_ptr()),       // lpOperation - "runas" 触发 UAC
             windows::core::PCWSTR(exe_path_wide.as_ptr()),    // lpFile - 可执行文件路径
-            windows::core::PCWSTR(ptr::null()),               // lpParameters - 无参数
+            windows::core::PCWSTR(parameters_wide.as_ptr()),  // lpParameters - 原进程 PID
             windows::core::PCWSTR(working_dir_wide.as_ptr()), // lpDirectory - 工作目录
             SHOW_WINDOW_CMD(1),                               // nShowCmd - SW_SHOWNORMAL
         )
@@ -236,9 +263,9 @@ pub fn request_elevation() -> Result<RestartResult, BackupError> {
     if result_ptr > 32 {
         info!("成功启动管理员进程，返回值: {:?}", result);
 
-        // 延迟退出，给前端时间处理响应，也给新进程时间启动
+        // 延迟退出，给前端时间处理响应；新进程会等待本进程退出后再初始化
         thread::spawn(move || {
-            thread::sleep(Duration::from_secs(2));
+            thread::sleep(Duration::from_millis(ELEVATION_EXIT_DELAY_MS));
             info!("当前进程即将退出，新进程将以管理员身份运行");
             std::process::exit(0);
         });
@@ -247,193 +274,113 @@ pub fn request_elevation() -> Result<RestartResult, BackupError> {
             success: true,
             message: "程序将以管理员身份重启".to_string(),
             platform: "windows".to_string(),
+            restarting: true,
         })
     } else {
+        // 没有新进程领取，丢弃刚保存的重启状态
+        if let Err(e) = crate::restart_state::clear_restart_state() {
+            warn!("清除重启状态失败: {}", e);
+        }
+
         // 返回值小于等于 32 表示错误
         let error_code = result_ptr as u32;
-        let error_msg = match error_code {
-            0 => "内存不足",
-            2 => "文件未找到",
-            3 => "路径未找到",
-            5 => "访问被拒绝（用户可能取消了 UAC 提示）",
-            8 => "内存不足",
-            11 => "EXE 文件无效",
-            26 => "共享错误",
-            27 => "文件关联不完整",
-            28 => "无法加载应用程序",
-            31 => "没有应用程序关联",
-            106 => "用户取消了 UAC 提示",
-            1223 => "用户取消了操作",
-            _ => "未知错误",
-        };
-
-        error!(
-            "无法以管理员身份启动，错误码: {} - {}",
-            error_code, error_msg
-        );
+        error!("无法以管理员身份启动，错误码: {}", error_code);
 
         // 如果用户取消了 UAC，返回更友好的错误信息
         if error_code == 5 || error_code == 106 || error_code == 1223 {
-            Err(BackupError::StorageError(
-                "用户取消了权限提升请求".to_string(),
-            ))
+            Err(BackupError::StorageError(tr(Message::ElevationCancelled)))
         } else {
-            Err(BackupError::StorageError(format!(
-                "无法以管理员身份启动: {} (错误码: {})",
-                error_msg, error_code
+            Err(BackupError::StorageError(tr(
+                Message::ElevationLaunchFailed { code: error_code },
             )))
         }
     }
 }
 
 #[cfg(target_os = "macos")]
-pub fn request_elevation() -> Result<RestartResult, BackupError> {
+pub fn request_elevation(
+    _pending_action: Option<PendingAction>,
+) -> Result<RestartResult, BackupError> {
     info!("申请管理员权限重启 (macOS)");
-
-    // 保存重启状态
-    if let Err(e) = save_restart_state() {
-        warn!("保存重启状态失败: {}", e);
-    }
-
-    Err(BackupError::UnsupportedPlatform(
-        "请使用 sudo 重新启动应用程序".to_string(),
-    ))
+    warn!("macOS 暂不支持自动提升权限，请使用 sudo 重新启动应用程序");
+    Err(BackupError::UnsupportedPlatform)
 }
 
+/// Linux 不重启程序：写入操作各自通过 pkexec 调用特权辅助程序，由 polkit 按次认证，
+/// 调用方直接继续 `pending_action` 即可
 #[cfg(target_os = "linux")]
-pub fn request_elevation() -> Result<RestartResult, BackupError> {
-    info!("申请管理员权限重启 (Linux)");
+pub fn request_elevation(
+    _pending_action: Option<PendingAction>,
+) -> Result<RestartResult, BackupError> {
+    info!("申请管理员权限 (Linux)");
 
-    // 保存重启状态
-    if let Err(e) = save_restart_state() {
-        warn!("保存重启状态失败: {}", e);
+    if unsafe { libc::getuid() } == 0 || crate::helper::is_available() {
+        return Ok(RestartResult {
+            success: true,
+            message: tr(Message::ElevationPolkitOnDemand),
+            platform: "linux".to_string(),
+            restarting: false,
+        });
     }
 
-    Err(BackupError::UnsupportedPlatform(
-        "请使用 sudo 或 pkexec 重新启动应用程序".to_string(),
-    ))
+    warn!("未找到 pkexec 或特权辅助程序，无法获取写入权限");
+    Err(BackupError::HelperFailed(tr(
+        Message::ElevationHelperUnavailable {
+            path: crate::helper::HELPER_INSTALL_PATH,
+        },
+    )))
 }
 
 #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-pub fn request_elevation() -> Result<RestartResult, BackupError> {
+pub fn request_elevation(
+    _pending_action: Option<PendingAction>,
+) -> Result<RestartResult, BackupError> {
     warn!("在不支持的操作系统上申请权限提升");
-    Err(BackupError::UnsupportedPlatform(
-        "不支持的操作系统".to_string(),
-    ))
-}
-
-/// 获取应用程序数据目录
-/// 使用程序所在目录下的 .data 文件夹存储数据
-#[cfg(windows)]
-fn get_app_data_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let exe_path = std::env::current_exe()?;
-    let exe_dir = exe_path
-        .parent()
-        .ok_or("无法获取程序目录")?;
-
-    let mut path = exe_dir.to_path_buf();
-    path.push(".data");
-    Ok(path)
+    Err(BackupError::UnsupportedPlatform)
 }
 
-/// 保存重启状态，以便重启后恢复
+/// 等待指定进程退出，进程不存在时立即返回 true，超时返回 false
 #[cfg(windows)]
-fn save_restart_state() -> Result<(), Box<dyn std::error::Error>> {
-    use serde_json::json;
-    use std::fs;
-
-    let mut state_path = get_app_data_dir()?;
-
-    // 确保目录存在
-    if !state_path.exists() {
-        fs::create_dir_all(&state_path)?;
-    }
-
-    state_path.push("restart_state.json");
-
-    let state = json!({
-        "timestamp": std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)?
-            .as_secs(),
-        "was_restarted": true,
-        "platform": "windows"
-    });
-
-    fs::write(&state_path, state.to_string())?;
-    info!("重启状态已保存到: {:?}", state_path);
-
-    Ok(())
-}
-
-#[cfg(not(windows))]
-fn save_restart_state() -> Result<(), Box<dyn std::error::Error>> {
-    // 非 Windows 平台暂时不实现
-    Ok(())
-}
-
-/// 检查是否是重启后的状态
-#[cfg(windows)]
-pub fn check_restart_state() -> Option<serde_json::Value> {
-    use std::fs;
-
-    let mut state_path = get_app_data_dir().ok()?;
-    state_path.push("restart_state.json");
-
-    if !state_path.exists() {
-        return None;
-    }
-
-    let content = fs::read_to_string(&state_path).ok()?;
-    let state: serde_json::Value = serde_json::from_str(&content).ok()?;
-
-    // 检查时间戳，如果超过 60 秒则认为不是当前的重启
-    let timestamp = state.get("timestamp")?.as_u64()?;
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .ok()?
-        .as_secs();
-
-    if now - timestamp > 60 {
-        // 清理过期的状态文件
-        let _ = fs::remove_file(&state_path);
-        return None;
-    }
-
-    // 清理已使用的状态文件
-    let _ = fs::remove_file(&state_path);
-
-    info!("检测到重启状态: {:?}", state);
-    Some(state)
-}
+pub fn wait_for_process_exit(pid: u32, timeout: Duration) -> bool {
+    use windows::Win32::Foundation::{CloseHandle, WAIT_OBJECT_0};
+    use windows::Win32::System::Threading::{
+        OpenProcess, WaitForSingleObject, PROCESS_SYNCHRONIZE,
+    };
 
-#[cfg(not(windows))]
-pub fn check_restart_state() -> Option<serde_json::Value> {
-    None
+    let handle = match unsafe { OpenProcess(PROCESS_SYNCHRONIZE, false, pid) } {
+        Ok(handle) => handle,
+        // 无法打开说明进程已经退出
+        Err(_) => return true,
+    };
+    let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+    let exited = unsafe { WaitForSingleObject(handle, millis) } == WAIT_OBJECT_0;
+    let _ = unsafe { CloseHandle(handle) };
+    exited
 }
 
-/// 清除重启状态
-#[cfg(windows)]
-#[allow(dead_code)]
-pub fn clear_restart_state() -> Result<(), Box<dyn std::error::Error>> {
-    use std::fs;
-    use std::path::PathBuf;
-
-    let app_data = std::env::var("APPDATA")?;
-    let mut state_path = PathBuf::from(app_data);
-    state_path.push("MachineID-Manage");
-    state_path.push("restart_state.json");
-
-    if state_path.exists() {
-        fs::remove_file(&state_path)?;
-        info!("重启状态已清除");
+#[cfg(unix)]
+pub fn wait_for_process_exit(pid: u32, timeout: Duration) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return true;
+    };
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        // 信号 0 只检查进程是否存在
+        let alive = unsafe { libc::kill(pid, 0) } == 0
+            || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM);
+        if !alive {
+            return true;
+        }
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(100));
     }
-
-    Ok(())
 }
 
-#[cfg(not(windows))]
-pub fn clear_restart_state() -> Result<(), Box<dyn std::error::Error>> {
-    Ok(())
+#[cfg(not(any(windows, unix)))]
+pub fn wait_for_process_exit(_pid: u32, _timeout: Duration) -> bool {
+    true
 }
 
 #[cfg(test)]
@@ -449,6 +396,20 @@ mod tests {
         assert!(result.error_type.is_none());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_for_process_exit() {
+        assert!(!wait_for_process_exit(
+            std::process::id(),
+            Duration::from_millis(200)
+        ));
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        assert!(wait_for_process_exit(pid, Duration::from_secs(1)));
+    }
+
     #[test]
     fn test_permission_check_result_error() {
         let result =
@@ -509,7 +470,7 @@ mod tests {
         assert!(result.check_success);
 
         let uid = unsafe { libc::getuid() };
-        let expected = uid == 0;
+        let expected = uid == 0 || crate::helper::is_available();
         assert_eq!(result.has_permission, expected);
     }
 
@@ -519,6 +480,7 @@ mod tests {
             success: true,
             message: "测试消息".to_string(),
             platform: "test".to_string(),
+            restarting: true,
         };
 
         assert!(result.success);
@@ -0,0 +1,8 @@
+// Windows 平台特定实现
+// 目前权限检测逻辑在 permissions.rs 中统一处理
+
+/// 本次开机的时间（Unix 秒），由当前时间减去开机时长得到，可能有几秒误差
+pub fn boot_time() -> Option<u64> {
+    let uptime = unsafe { ::windows::Win32::System::SystemInformation::GetTickCount64() } / 1000;
+    crate::json_store::now_secs().checked_sub(uptime)
+}
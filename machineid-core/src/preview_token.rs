@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use hmac::{Hmac, Mac};
+use lazy_static::lazy_static;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::i18n::{tr, Message};
+use crate::json_store::now_secs;
+use crate::machine_id::BackupError;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 预览令牌有效期（秒）
+pub const PREVIEW_TOKEN_TTL_SECS: u64 = 120;
+
+lazy_static! {
+    /// 进程级令牌签发器，密钥仅存在于内存中，重启后所有旧令牌自动失效
+    static ref PREVIEW_TOKEN_ISSUER: PreviewTokenIssuer = PreviewTokenIssuer::new();
+}
+
+/// 预览令牌
+/// 格式为 `<guid>.<expires_at>.<nonce>.<hmac>`，HMAC 覆盖前三段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewToken {
+    pub token: String,
+    pub expires_at: u64,
+}
+
+/// 预览令牌签发器
+/// 负责签发与 GUID 绑定的一次性令牌，并记录已使用的令牌防止重放
+pub struct PreviewTokenIssuer {
+    key: [u8; 32],
+    /// 已使用的 nonce 及其过期时间，过期后清理
+    used_nonces: Mutex<HashMap<String, u64>>,
+}
+
+impl PreviewTokenIssuer {
+    pub fn new() -> Self {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        PreviewTokenIssuer {
+            key,
+            used_nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn sign(&self, payload: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC 可以接受任意长度的密钥");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    /// 为指定 GUID 签发令牌
+    pub fn issue(&self, guid: &str, now: u64) -> PreviewToken {
+        let mut nonce = [0u8; 16];
+        OsRng.fill_bytes(&mut nonce);
+
+        let expires_at = now + PREVIEW_TOKEN_TTL_SECS;
+        let payload = format!("{}.{}.{}", guid, expires_at, hex::encode(nonce));
+        let signature = hex::encode(self.sign(&payload).finalize().into_bytes());
+
+        PreviewToken {
+            token: format!("{}.{}", payload, signature),
+            expires_at,
+        }
+    }
+
+    /// 校验并消费令牌，成功时返回令牌绑定的 GUID
+    /// 令牌只能使用一次，过期或签名不匹配的令牌都会被拒绝
+    pub fn redeem(&self, token: &str, now: u64) -> Result<String, BackupError> {
+        let (payload, signature) = token
+            .rsplit_once('.')
+            .ok_or_else(|| BackupError::InvalidPreviewToken(tr(Message::PreviewTokenMalformed)))?;
+        let signature = hex::decode(signature).map_err(|_| {
+            BackupError::InvalidPreviewToken(tr(Message::PreviewTokenSignatureMalformed))
+        })?;
+        self.sign(payload).verify_slice(&signature).map_err(|_| {
+            BackupError::InvalidPreviewToken(tr(Message::PreviewTokenSignatureMismatch))
+        })?;
+
+        let mut parts = payload.splitn(3, '.');
+        let (guid, expires_at, nonce) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(guid), Some(expires_at), Some(nonce)) => (guid, expires_at, nonce),
+            _ => {
+                return Err(BackupError::InvalidPreviewToken(tr(
+                    Message::PreviewTokenMalformed,
+                )))
+            }
+        };
+        let expires_at: u64 = expires_at.parse().map_err(|_| {
+            BackupError::InvalidPreviewToken(tr(Message::PreviewTokenExpiryMalformed))
+        })?;
+
+        if now > expires_at {
+            return Err(BackupError::InvalidPreviewToken(tr(
+                Message::PreviewTokenExpired,
+            )));
+        }
+
+        let mut used = self.used_nonces.lock().unwrap_or_else(|e| e.into_inner());
+        used.retain(|_, expiry| *expiry >= now);
+        if used.insert(nonce.to_string(), expires_at).is_some() {
+            return Err(BackupError::InvalidPreviewToken(tr(
+                Message::PreviewTokenUsed,
+            )));
+        }
+
+        Ok(guid.to_string())
+    }
+}
+
+impl Default for PreviewTokenIssuer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 为预览的 GUID 签发一次性令牌
+pub fn issue_preview_token(guid: &str) -> PreviewToken {
+    PREVIEW_TOKEN_ISSUER.issue(guid, now_secs())
+}
+
+/// 校验并消费预览令牌，返回令牌绑定的 GUID
+pub fn redeem_preview_token(token: &str) -> Result<String, BackupError> {
+    PREVIEW_TOKEN_ISSUER.redeem(token, now_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_GUID: &str = "550e8400-e29b-41d4-a716-446655440000";
+
+    #[test]
+    fn test_issue_and_redeem() {
+        let issuer = PreviewTokenIssuer::new();
+        let token = issuer.issue(TEST_GUID, 1000);
+        assert_eq!(token.expires_at, 1000 + PREVIEW_TOKEN_TTL_SECS);
+        assert_eq!(issuer.redeem(&token.token, 1001).unwrap(), TEST_GUID);
+    }
+
+    #[test]
+    fn test_token_is_single_use() {
+        let issuer = PreviewTokenIssuer::new();
+        let token = issuer.issue(TEST_GUID, 1000);
+        assert!(issuer.redeem(&token.token, 1001).is_ok());
+        assert!(matches!(
+            issuer.redeem(&token.token, 1002),
+            Err(BackupError::InvalidPreviewToken(_))
+        ));
+    }
+
+    #[test]
+    fn test_expired_token_rejected() {
+        let issuer = PreviewTokenIssuer::new();
+        let token = issuer.issue(TEST_GUID, 1000);
+        let result = issuer.redeem(&token.token, 1001 + PREVIEW_TOKEN_TTL_SECS);
+        assert!(matches!(result, Err(BackupError::InvalidPreviewToken(_))));
+    }
+
+    #[test]
+    fn test_tampered_token_rejected() {
+        let issuer = PreviewTokenIssuer::new();
+        let token = issuer.issue(TEST_GUID, 1000);
+
+        // 替换 GUID 后签名不再匹配
+        let tampered = token
+            .token
+            .replacen(TEST_GUID, "12345678-1234-1234-1234-123456789012", 1);
+        assert!(issuer.redeem(&tampered, 1001).is_err());
+
+        // 延长过期时间后签名不再匹配
+        let extended = token.token.replacen(
+            &token.expires_at.to_string(),
+            &(token.expires_at + 3600).to_string(),
+            1,
+        );
+        assert!(issuer.redeem(&extended, 1001).is_err());
+
+        assert!(issuer.redeem("not-a-token", 1001).is_err());
+        assert!(issuer.redeem("", 1001).is_err());
+    }
+
+    #[test]
+    fn test_token_from_other_issuer_rejected() {
+        let issuer = PreviewTokenIssuer::new();
+        let other = PreviewTokenIssuer::new();
+        let token = other.issue(TEST_GUID, 1000);
+        assert!(issuer.redeem(&token.token, 1001).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, Message};
+use crate::json_store::{self, now_secs};
+use crate::machine_id::{
+    get_data_file_path, read_machine_guid, validate_identity_value, write_machine_guid_with,
+    AutoDescription, BackupDescription, BackupError, WriteResult,
+};
+
+/// 配置名称最大长度
+const MAX_PROFILE_NAME_LENGTH: usize = 64;
+/// 身份配置文件名
+const PROFILE_FILE: &str = "profiles.json";
+
+/// MachineGuid 标识符键名
+pub const MACHINE_GUID_IDENTIFIER: &str = "MachineGuid";
+
+/// 当前支持的标识符
+const SUPPORTED_IDENTIFIERS: &[&str] = &[MACHINE_GUID_IDENTIFIER];
+
+/// 身份配置
+/// 将一个名称映射到一组标识符值，例如 "QA-A" -> { MachineGuid: ... }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct IdentityProfile {
+    pub name: String,
+    pub identifiers: BTreeMap<String, String>,
+    pub description: Option<String>,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileStore {
+    pub profiles: Vec<IdentityProfile>,
+}
+
+impl ProfileStore {
+    pub fn get(&self, name: &str) -> Result<&IdentityProfile, BackupError> {
+        self.profiles
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| BackupError::ProfileNotFound(name.to_string()))
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Result<&mut IdentityProfile, BackupError> {
+        self.profiles
+            .iter_mut()
+            .find(|p| p.name == name)
+            .ok_or_else(|| BackupError::ProfileNotFound(name.to_string()))
+    }
+
+    pub fn insert(&mut self, profile: IdentityProfile) -> Result<(), BackupError> {
+        if self.profiles.iter().any(|p| p.name == profile.name) {
+            return Err(BackupError::ProfileAlreadyExists(profile.name));
+        }
+        self.profiles.push(profile);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<IdentityProfile, BackupError> {
+        let index = self
+            .profiles
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| BackupError::ProfileNotFound(name.to_string()))?;
+        Ok(self.profiles.remove(index))
+    }
+}
+
+/// 校验配置名称
+fn validate_profile_name(name: &str) -> Result<(), BackupError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err(BackupError::InvalidProfile(tr(Message::ProfileNameEmpty)));
+    }
+    if trimmed != name {
+        return Err(BackupError::InvalidProfile(tr(
+            Message::ProfileNameWhitespace,
+        )));
+    }
+    if name.chars().count() > MAX_PROFILE_NAME_LENGTH {
+        return Err(BackupError::InvalidProfile(tr(
+            Message::ProfileNameTooLong {
+                max: MAX_PROFILE_NAME_LENGTH,
+            },
+        )));
+    }
+    Ok(())
+}
+
+/// 校验标识符集合：只允许已支持的标识符，且值必须符合当前平台的格式
+fn validate_identifiers(identifiers: &BTreeMap<String, String>) -> Result<(), BackupError> {
+    if identifiers.is_empty() {
+        return Err(BackupError::InvalidProfile(tr(
+            Message::ProfileNoIdentifiers,
+        )));
+    }
+    for (key, value) in identifiers {
+        if !SUPPORTED_IDENTIFIERS.contains(&key.as_str()) {
+            return Err(BackupError::InvalidProfile(tr(
+                Message::ProfileUnsupportedIdentifier { name: key },
+            )));
+        }
+        validate_identity_value(value)?;
+    }
+    Ok(())
+}
+
+/// 读取当前系统的标识符值
+fn capture_current_identifiers() -> Result<BTreeMap<String, String>, BackupError> {
+    let machine_id = read_machine_guid()?;
+    let mut identifiers = BTreeMap::new();
+    identifiers.insert(MACHINE_GUID_IDENTIFIER.to_string(), machine_id.guid);
+    Ok(identifiers)
+}
+
+fn with_profile_store<T, F>(f: F) -> Result<T, BackupError>
+where
+    F: FnOnce(&mut ProfileStore) -> Result<T, BackupError>,
+{
+    json_store::update(&get_data_file_path(PROFILE_FILE)?, f)
+}
+
+/// 只读访问不需要加锁和写回
+fn load_profile_store() -> Result<ProfileStore, BackupError> {
+    json_store::load(&get_data_file_path(PROFILE_FILE)?)
+}
+
+pub fn list_profiles() -> Result<Vec<IdentityProfile>, BackupError> {
+    Ok(load_profile_store()?.profiles)
+}
+
+/// 创建配置
+/// 未提供标识符时使用当前系统的值，便于保存 "original" 等配置
+pub fn create_profile(
+    name: &str,
+    identifiers: Option<BTreeMap<String, String>>,
+    description: Option<String>,
+) -> Result<IdentityProfile, BackupError> {
+    validate_profile_name(name)?;
+    let identifiers = match identifiers {
+        Some(identifiers) => identifiers,
+        None => capture_current_identifiers()?,
+    };
+    validate_identifiers(&identifiers)?;
+
+    let now = now_secs();
+    let profile = IdentityProfile {
+        name: name.to_string(),
+        identifiers,
+        description,
+        created_at: now,
+        updated_at: now,
+    };
+    with_profile_store(|store| store.insert(profile.clone()))?;
+    Ok(profile)
+}
+
+/// 更新配置的标识符或描述，未提供的字段保持不变
+pub fn update_profile(
+    name: &str,
+    identifiers: Option<BTreeMap<String, String>>,
+    description: Option<String>,
+) -> Result<IdentityProfile, BackupError> {
+    if let Some(identifiers) = &identifiers {
+        validate_identifiers(identifiers)?;
+    }
+
+    with_profile_store(|store| {
+        let profile = store.get_mut(name)?;
+        if let Some(identifiers) = identifiers {
+            profile.identifiers = identifiers;
+        }
+        if description.is_some() {
+            profile.description = description;
+        }
+        profile.updated_at = now_secs();
+        Ok(profile.clone())
+    })
+}
+
+pub fn delete_profile(name: &str) -> Result<IdentityProfile, BackupError> {
+    with_profile_store(|store| store.remove(name))
+}
+
+/// 应用配置的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileApplyResult {
+    pub profile: IdentityProfile,
+    #[serde(flatten)]
+    pub write: WriteResult,
+}
+
+/// 应用配置
+/// 写入前会自动备份当前值，便于随时切换回来
+pub fn apply_profile(name: &str) -> Result<ProfileApplyResult, BackupError> {
+    let profile = load_profile_store()?.get(name).cloned()?;
+    let guid = profile
+        .identifiers
+        .get(MACHINE_GUID_IDENTIFIER)
+        .ok_or_else(|| {
+            BackupError::InvalidProfile(tr(Message::ProfileMissingValue {
+                name: MACHINE_GUID_IDENTIFIER,
+            }))
+        })?;
+
+    let write = write_machine_guid_with(
+        guid,
+        Some(BackupDescription::Auto(
+            AutoDescription::BeforeProfileSwitch {
+                profile: profile.name.clone(),
+            },
+        )),
+    )?;
+    Ok(ProfileApplyResult { profile, write })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn profile(name: &str, guid: &str) -> IdentityProfile {
+        let mut identifiers = BTreeMap::new();
+        identifiers.insert(MACHINE_GUID_IDENTIFIER.to_string(), guid.to_string());
+        IdentityProfile {
+            name: name.to_string(),
+            identifiers,
+            description: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("QA-A").is_ok());
+        assert!(validate_profile_name("原始配置").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("   ").is_err());
+        assert!(validate_profile_name(" QA-A").is_err());
+        assert!(validate_profile_name(&"a".repeat(MAX_PROFILE_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_identifiers() {
+        let valid = profile("QA-A", "550E8400-E29B-41D4-A716-446655440000").identifiers;
+        assert!(validate_identifiers(&valid).is_ok());
+
+        assert!(validate_identifiers(&BTreeMap::new()).is_err());
+
+        let invalid_guid = profile("QA-A", "not-a-guid").identifiers;
+        assert!(matches!(
+            validate_identifiers(&invalid_guid),
+            Err(BackupError::InvalidGuidFormat(_))
+        ));
+
+        // Linux 的 machine-id 不含连字符
+        #[cfg(target_os = "linux")]
+        assert!(validate_identifiers(
+            &profile("QA-A", "550e8400e29b41d4a716446655440000").identifiers
+        )
+        .is_ok());
+
+        let mut unknown = valid.clone();
+        unknown.insert(
+            "SerialNumber".to_string(),
+            "550E8400-E29B-41D4-A716-446655440000".to_string(),
+        );
+        assert!(matches!(
+            validate_identifiers(&unknown),
+            Err(BackupError::InvalidProfile(_))
+        ));
+    }
+
+    #[test]
+    fn test_profile_store_operations() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("profiles.json");
+
+        let mut store: ProfileStore = json_store::load(&path).unwrap();
+        store
+            .insert(profile("QA-A", "550E8400-E29B-41D4-A716-446655440000"))
+            .unwrap();
+        store
+            .insert(profile("QA-B", "12345678-1234-1234-1234-123456789012"))
+            .unwrap();
+        assert!(matches!(
+            store.insert(profile("QA-A", "FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF")),
+            Err(BackupError::ProfileAlreadyExists(_))
+        ));
+        json_store::save(&path, &store).unwrap();
+
+        let mut loaded: ProfileStore = json_store::load(&path).unwrap();
+        assert_eq!(loaded.profiles.len(), 2);
+        assert_eq!(
+            loaded.get("QA-B").unwrap().identifiers[MACHINE_GUID_IDENTIFIER],
+            "12345678-1234-1234-1234-123456789012"
+        );
+
+        loaded.remove("QA-A").unwrap();
+        assert!(matches!(
+            loaded.get("QA-A"),
+            Err(BackupError::ProfileNotFound(_))
+        ));
+        assert_eq!(loaded.profiles.len(), 1);
+    }
+}
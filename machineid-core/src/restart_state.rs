@@ -0,0 +1,211 @@
+//! 提权重启前后的状态交接
+//!
+//! 以管理员身份重启前保存重启状态，记录用户当时正在进行的操作（待执行的写入、选中的备份等）。
+//! 新进程启动后领取状态，经用户确认后继续该操作。状态只能领取一次，保存超过
+//! [`RESTART_STATE_TTL_SECS`] 秒视为过期（例如用户取消了提权或新进程启动失败），领取时直接丢弃。
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::json_store::{self, now_secs};
+use crate::machine_id::{get_data_file_path, BackupError};
+
+/// 重启状态文件名，与备份文件位于同一目录
+const RESTART_STATE_FILE: &str = "restart_state.json";
+/// 重启状态的有效期（秒）
+pub const RESTART_STATE_TTL_SECS: u64 = 60;
+
+/// 提权前用户正在进行、需要在新进程中继续的操作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum PendingAction {
+    /// 将机器码写入为指定值
+    WriteGuid {
+        guid: String,
+        description: Option<String>,
+    },
+    /// 从指定备份恢复
+    RestoreBackup { backup_id: String },
+    /// 打开随机生成对话框
+    OpenGenerate,
+    /// 打开自定义替换对话框
+    OpenReplace,
+}
+
+/// 重启状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct RestartState {
+    /// 保存时间（Unix 秒）
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub timestamp: u64,
+    pub platform: String,
+    #[serde(default)]
+    pub pending_action: Option<PendingAction>,
+}
+
+impl RestartState {
+    pub fn new(pending_action: Option<PendingAction>) -> Self {
+        RestartState {
+            timestamp: now_secs(),
+            platform: std::env::consts::OS.to_string(),
+            pending_action,
+        }
+    }
+
+    /// 在 `now` 时是否已过期；时间被回拨时同样视为过期
+    pub fn is_expired(&self, now: u64) -> bool {
+        now < self.timestamp || now - self.timestamp > RESTART_STATE_TTL_SECS
+    }
+
+    /// 读取并删除状态文件，损坏或过期的状态返回 None
+    fn take_from(path: &Path, now: u64) -> Option<RestartState> {
+        let content = fs::read_to_string(path).ok()?;
+        if let Err(e) = fs::remove_file(path) {
+            warn!("删除重启状态失败: {}", e);
+        }
+        let state: RestartState = match serde_json::from_str(&content) {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("重启状态已损坏，忽略: {}", e);
+                return None;
+            }
+        };
+        if state.is_expired(now) {
+            info!("重启状态已过期，忽略: {:?}", state);
+            return None;
+        }
+        Some(state)
+    }
+}
+
+/// 保存重启状态，提权重启前调用
+pub fn save_restart_state(pending_action: Option<PendingAction>) -> Result<(), BackupError> {
+    let path = get_data_file_path(RESTART_STATE_FILE)?;
+    json_store::save(&path, &RestartState::new(pending_action))?;
+    info!("重启状态已保存到: {:?}", path);
+    Ok(())
+}
+
+/// 领取重启状态，领取后即删除；没有状态或已过期时返回 None
+pub fn take_restart_state() -> Option<RestartState> {
+    let path = get_data_file_path(RESTART_STATE_FILE).ok()?;
+    let state = RestartState::take_from(&path, now_secs())?;
+    info!("检测到重启状态: {:?}", state);
+    Some(state)
+}
+
+/// 清除重启状态，例如用户取消了提权
+pub fn clear_restart_state() -> Result<(), BackupError> {
+    let path = get_data_file_path(RESTART_STATE_FILE)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| BackupError::StorageError(e.to_string()))?;
+        info!("重启状态已清除");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_at(timestamp: u64, pending_action: Option<PendingAction>) -> RestartState {
+        RestartState {
+            timestamp,
+            platform: "test".to_string(),
+            pending_action,
+        }
+    }
+
+    #[test]
+    fn test_take_restores_pending_action_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RESTART_STATE_FILE);
+        let action = PendingAction::WriteGuid {
+            guid: "550E8400-E29B-41D4-A716-446655440000".to_string(),
+            description: Some("提权前输入".to_string()),
+        };
+        let state = state_at(1_000, Some(action));
+        json_store::save(&path, &state).unwrap();
+
+        assert_eq!(RestartState::take_from(&path, 1_010), Some(state));
+        assert!(!path.exists());
+        assert_eq!(RestartState::take_from(&path, 1_010), None);
+    }
+
+    #[test]
+    fn test_take_discards_expired_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RESTART_STATE_FILE);
+        let action = PendingAction::RestoreBackup {
+            backup_id: "backup_1".to_string(),
+        };
+
+        let state = state_at(1_000, Some(action));
+        json_store::save(&path, &state).unwrap();
+        assert_eq!(
+            RestartState::take_from(&path, 1_000 + RESTART_STATE_TTL_SECS),
+            Some(state.clone())
+        );
+
+        json_store::save(&path, &state).unwrap();
+        assert_eq!(
+            RestartState::take_from(&path, 1_001 + RESTART_STATE_TTL_SECS),
+            None
+        );
+        // 过期的状态同样被删除
+        assert!(!path.exists());
+
+        // 时间回拨
+        json_store::save(&path, &state).unwrap();
+        assert_eq!(RestartState::take_from(&path, 999), None);
+    }
+
+    #[test]
+    fn test_take_discards_corrupt_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RESTART_STATE_FILE);
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(RestartState::take_from(&path, 0), None);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_legacy_state_without_pending_action() {
+        // 旧版本只保存时间戳和平台
+        let legacy = r#"{"timestamp":1000,"was_restarted":true,"platform":"windows"}"#;
+        let state: RestartState = serde_json::from_str(legacy).unwrap();
+        assert_eq!(state.timestamp, 1_000);
+        assert_eq!(state.platform, "windows");
+        assert_eq!(state.pending_action, None);
+    }
+
+    #[test]
+    fn test_pending_action_tags() {
+        for (action, kind) in [
+            (
+                PendingAction::WriteGuid {
+                    guid: String::new(),
+                    description: None,
+                },
+                "write_guid",
+            ),
+            (
+                PendingAction::RestoreBackup {
+                    backup_id: String::new(),
+                },
+                "restore_backup",
+            ),
+            (PendingAction::OpenGenerate, "open_generate"),
+            (PendingAction::OpenReplace, "open_replace"),
+        ] {
+            assert_eq!(serde_json::to_value(&action).unwrap()["kind"], kind);
+        }
+    }
+}
@@ -0,0 +1,475 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use crate::json_store::{self, now_secs};
+use crate::machine_id::{
+    get_backup_by_id, get_data_file_path, normalize_identity, read_machine_guid,
+    restore_backup_by_id, write_machine_guid_with, AutoDescription, BackupDescription, BackupError,
+    WriteResult,
+};
+
+/// 自动恢复检查间隔（秒）
+const REVERT_TICK_SECS: u64 = 15;
+/// 临时写入的最长持续时间（7 天）
+pub const MAX_REVERT_AFTER_SECS: u64 = 7 * 24 * 3600;
+/// 待恢复记录文件名
+const REVERT_FILE: &str = "pending_reverts.json";
+/// 最多尝试次数，用完后暂停
+const MAX_REVERT_ATTEMPTS: u32 = 5;
+/// 首次重试的等待时间（秒），之后每次翻倍
+const RETRY_BASE_SECS: u64 = 60;
+/// 重试等待时间的上限（秒）
+const MAX_RETRY_DELAY_SECS: u64 = 3600;
+/// 执行中的记录超过该时间仍未完成时，视为执行被中断（如进程被结束），可以重新执行
+const RUNNING_TIMEOUT_SECS: u64 = 600;
+
+/// 自动恢复记录的状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum RevertStatus {
+    /// 等待到期或重试
+    #[default]
+    Pending,
+    /// 正在执行
+    Running,
+    /// 权限不足、认证被取消或多次失败后暂停，不再自动重试
+    Paused,
+}
+
+/// 待执行的自动恢复
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct PendingRevert {
+    pub id: String,
+    /// 临时写入前的原始值
+    pub original_guid: String,
+    /// 临时写入的值
+    pub temporary_guid: String,
+    /// 写入前自动创建的备份，存在时优先从该备份恢复
+    pub pre_backup_id: Option<String>,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub revert_at: u64,
+    /// 最近一次恢复失败的原因
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub status: RevertStatus,
+    /// 已尝试执行的次数
+    #[serde(default)]
+    pub attempts: u32,
+    /// 失败后下次重试的时间
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub retry_at: Option<u64>,
+    /// 本次执行开始的时间，仅在执行中时存在
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub started_at: Option<u64>,
+}
+
+impl PendingRevert {
+    /// 在指定时间是否应当执行：等待中的记录到期或到达重试时间，执行中的记录已超时
+    fn is_due(&self, now: u64) -> bool {
+        match self.status {
+            RevertStatus::Pending => self.retry_at.unwrap_or(self.revert_at) <= now,
+            RevertStatus::Running => self
+                .started_at
+                .is_none_or(|started| started.saturating_add(RUNNING_TIMEOUT_SECS) <= now),
+            RevertStatus::Paused => false,
+        }
+    }
+}
+
+/// 第 `attempts` 次失败后的重试等待时间
+fn retry_delay(attempts: u32) -> u64 {
+    RETRY_BASE_SECS
+        .saturating_mul(1u64 << attempts.saturating_sub(1).min(16))
+        .min(MAX_RETRY_DELAY_SECS)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RevertStore {
+    pub reverts: Vec<PendingRevert>,
+}
+
+impl RevertStore {
+    pub fn remove(&mut self, id: &str) -> Result<PendingRevert, BackupError> {
+        let index = self
+            .reverts
+            .iter()
+            .position(|r| r.id == id)
+            .ok_or_else(|| BackupError::PendingRevertNotFound(id.to_string()))?;
+        Ok(self.reverts.remove(index))
+    }
+
+    /// 返回在指定时间应当执行的记录
+    pub fn due(&self, now: u64) -> Vec<PendingRevert> {
+        self.reverts
+            .iter()
+            .filter(|r| r.is_due(now))
+            .cloned()
+            .collect()
+    }
+
+    /// 将到期的记录标记为执行中并返回，记录已被取消或已由其他进程开始执行时返回 None
+    fn claim(&mut self, id: &str, now: u64) -> Option<PendingRevert> {
+        let revert = self
+            .reverts
+            .iter_mut()
+            .find(|r| r.id == id && r.is_due(now))?;
+        revert.status = RevertStatus::Running;
+        revert.started_at = Some(now);
+        revert.attempts += 1;
+        Some(revert.clone())
+    }
+
+    /// 记录一次失败并返回新的状态：权限不足（包括取消认证）或次数用完时暂停，否则按指数退避等待重试
+    fn record_failure(&mut self, id: &str, error: &BackupError, now: u64) -> Option<RevertStatus> {
+        let revert = self.reverts.iter_mut().find(|r| r.id == id)?;
+        revert.last_error = Some(error.to_string());
+        revert.started_at = None;
+        if matches!(error, BackupError::InsufficientPermissions)
+            || revert.attempts >= MAX_REVERT_ATTEMPTS
+        {
+            revert.status = RevertStatus::Paused;
+            revert.retry_at = None;
+        } else {
+            revert.status = RevertStatus::Pending;
+            revert.retry_at = Some(now.saturating_add(retry_delay(revert.attempts)));
+        }
+        Some(revert.status)
+    }
+}
+
+/// 机器码仍是临时写入的值时才需要恢复，用户或其他程序改过之后不再覆盖
+fn still_temporary(revert: &PendingRevert, current: &str) -> bool {
+    normalize_identity(current) == normalize_identity(&revert.temporary_guid)
+}
+
+/// 校验临时写入的持续时间
+pub fn validate_revert_after(secs: u64) -> Result<(), BackupError> {
+    if secs == 0 || secs > MAX_REVERT_AFTER_SECS {
+        return Err(BackupError::InvalidRevertDuration(secs));
+    }
+    Ok(())
+}
+
+fn with_revert_store<T, F>(f: F) -> Result<T, BackupError>
+where
+    F: FnOnce(&mut RevertStore) -> Result<T, BackupError>,
+{
+    json_store::update(&get_data_file_path(REVERT_FILE)?, f)
+}
+
+/// 为一次成功的写入登记自动恢复
+pub fn schedule_revert(
+    write: &WriteResult,
+    revert_after_secs: u64,
+) -> Result<PendingRevert, BackupError> {
+    validate_revert_after(revert_after_secs)?;
+
+    let now = now_secs();
+    let revert = PendingRevert {
+        id: format!(
+            "revert_{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis()
+        ),
+        original_guid: write.previous_guid.clone(),
+        temporary_guid: write.new_guid.clone(),
+        pre_backup_id: write.pre_backup.as_ref().map(|b| b.id.clone()),
+        created_at: now,
+        revert_at: now + revert_after_secs,
+        last_error: None,
+        status: RevertStatus::Pending,
+        attempts: 0,
+        retry_at: None,
+        started_at: None,
+    };
+
+    with_revert_store(|store| {
+        store.reverts.push(revert.clone());
+        Ok(())
+    })?;
+    info!(
+        "已登记自动恢复: {}，将于 {} 恢复",
+        revert.id, revert.revert_at
+    );
+    Ok(revert)
+}
+
+pub fn list_pending_reverts() -> Result<Vec<PendingRevert>, BackupError> {
+    let store: RevertStore = json_store::load(&get_data_file_path(REVERT_FILE)?)?;
+    Ok(store.reverts)
+}
+
+pub fn cancel_pending_revert(id: &str) -> Result<PendingRevert, BackupError> {
+    with_revert_store(|store| store.remove(id))
+}
+
+/// 执行单个恢复：优先从写入前的备份恢复，备份已被删除时直接写回原始值
+/// 临时写入机器码，到期后自动恢复为写入前的值
+///
+/// 登记自动恢复失败时立即恢复为写入前的值并返回登记失败的原因，不会留下没有恢复计划的临时写入；
+/// 恢复也失败时返回恢复失败的原因。
+pub fn write_temporary(
+    new_guid: &str,
+    description: Option<String>,
+    revert_after_secs: u64,
+) -> Result<(WriteResult, PendingRevert), BackupError> {
+    validate_revert_after(revert_after_secs)?;
+
+    let write = write_machine_guid_with(new_guid, description.map(BackupDescription::Text))?;
+    match schedule_revert(&write, revert_after_secs) {
+        Ok(revert) => Ok((write, revert)),
+        Err(e) => {
+            error!("登记自动恢复失败，恢复为写入前的机器码: {}", e);
+            if let Err(rollback) = write_machine_guid_with(&write.previous_guid, None) {
+                error!("恢复写入前的机器码失败: {}", rollback);
+                return Err(rollback);
+            }
+            Err(e)
+        }
+    }
+}
+
+fn perform_revert(revert: &PendingRevert) -> Result<(), BackupError> {
+    if let Some(backup_id) = &revert.pre_backup_id {
+        if get_backup_by_id(backup_id).is_ok() {
+            restore_backup_by_id(backup_id)?;
+            return Ok(());
+        }
+    }
+    write_machine_guid_with(
+        &revert.original_guid,
+        Some(BackupDescription::Auto(AutoDescription::RevertExpired {
+            revert_id: revert.id.clone(),
+        })),
+    )?;
+    Ok(())
+}
+
+/// 检查机器码后执行单个恢复
+fn revert_if_still_temporary(revert: &PendingRevert) -> Result<(), BackupError> {
+    let current = read_machine_guid()?.guid;
+    if !still_temporary(revert, &current) {
+        info!(
+            "机器码已不是临时写入的值，放弃自动恢复: {} ({})",
+            revert.id, current
+        );
+        return Ok(());
+    }
+    info!("执行自动恢复: {} -> {}", revert.id, revert.original_guid);
+    perform_revert(revert)?;
+    info!("自动恢复成功: {}", revert.id);
+    Ok(())
+}
+
+/// 执行所有到期的自动恢复
+///
+/// 每条记录先在存储锁内标记为执行中再执行，已被取消的记录不会执行，其他进程也不会重复执行；
+/// 记录在完成后才删除，执行中进程被结束时超过 [`RUNNING_TIMEOUT_SECS`] 后重新执行。
+/// 失败后按指数退避重试，权限不足、认证被取消或次数用完时暂停，等待用户处理。
+pub fn run_due_reverts() -> Result<(), BackupError> {
+    let due = json_store::load::<RevertStore>(&get_data_file_path(REVERT_FILE)?)?.due(now_secs());
+
+    for revert in due {
+        let Some(revert) = with_revert_store(|store| Ok(store.claim(&revert.id, now_secs())))?
+        else {
+            info!("自动恢复已被取消或正在执行: {}", revert.id);
+            continue;
+        };
+        let result = revert_if_still_temporary(&revert);
+        let status = with_revert_store(|store| {
+            Ok(match &result {
+                Ok(()) => {
+                    // 记录可能已被用户取消
+                    let _ = store.remove(&revert.id);
+                    None
+                }
+                Err(e) => store.record_failure(&revert.id, e, now_secs()),
+            })
+        })?;
+        if let Err(e) = result {
+            match status {
+                Some(RevertStatus::Paused) => {
+                    error!("自动恢复失败，已暂停: {} ({})", revert.id, e)
+                }
+                _ => warn!("自动恢复失败，将稍后重试: {} ({})", revert.id, e),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 周期性执行到期的自动恢复
+/// 首次检查在启动时立即进行，用于处理程序关闭期间已到期的记录
+pub async fn run_revert_timer() {
+    let mut interval = tokio::time::interval(Duration::from_secs(REVERT_TICK_SECS));
+    loop {
+        interval.tick().await;
+        match tokio::task::spawn_blocking(run_due_reverts).await {
+            Ok(Err(e)) => error!("执行自动恢复失败: {}", e),
+            Err(e) => error!("自动恢复线程异常: {}", e),
+            Ok(Ok(())) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn pending(id: &str, revert_at: u64) -> PendingRevert {
+        PendingRevert {
+            id: id.to_string(),
+            original_guid: "550E8400-E29B-41D4-A716-446655440000".to_string(),
+            temporary_guid: "12345678-1234-1234-1234-123456789012".to_string(),
+            pre_backup_id: None,
+            created_at: 0,
+            revert_at,
+            last_error: None,
+            status: RevertStatus::Pending,
+            attempts: 0,
+            retry_at: None,
+            started_at: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_revert_after() {
+        assert!(validate_revert_after(60).is_ok());
+        assert!(validate_revert_after(MAX_REVERT_AFTER_SECS).is_ok());
+        assert!(matches!(
+            validate_revert_after(0),
+            Err(BackupError::InvalidRevertDuration(0))
+        ));
+        assert!(validate_revert_after(MAX_REVERT_AFTER_SECS + 1).is_err());
+    }
+
+    #[test]
+    fn test_due_reverts() {
+        let store = RevertStore {
+            reverts: vec![pending("revert_1", 100), pending("revert_2", 200)],
+        };
+        assert!(store.due(99).is_empty());
+        let due = store.due(150);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, "revert_1");
+        assert_eq!(store.due(200).len(), 2);
+    }
+
+    #[test]
+    fn test_still_temporary_ignores_format() {
+        let revert = pending("revert_1", 100);
+        assert!(still_temporary(
+            &revert,
+            "12345678-1234-1234-1234-123456789012"
+        ));
+        assert!(still_temporary(&revert, "12345678123412341234123456789012"));
+        // 已被改回或改成其他值
+        assert!(!still_temporary(&revert, &revert.original_guid));
+        assert!(!still_temporary(
+            &revert,
+            "87654321-4321-4321-4321-210987654321"
+        ));
+    }
+
+    #[test]
+    fn test_claimed_revert_stays_until_finished() {
+        let mut store = RevertStore {
+            reverts: vec![pending("revert_1", 100)],
+        };
+        assert!(store.claim("revert_1", 99).is_none());
+        let claimed = store.claim("revert_1", 100).unwrap();
+        assert_eq!(claimed.status, RevertStatus::Running);
+        assert_eq!(claimed.attempts, 1);
+
+        // 执行中的记录仍在存储中，但不会被再次执行
+        assert_eq!(store.reverts.len(), 1);
+        assert!(store.due(100).is_empty());
+        assert!(store.claim("revert_1", 100).is_none());
+
+        // 执行被中断时超时后重新执行
+        assert_eq!(store.due(100 + RUNNING_TIMEOUT_SECS).len(), 1);
+
+        // 已取消的记录不会被执行
+        store.remove("revert_1").unwrap();
+        assert!(store.claim("revert_1", 200).is_none());
+    }
+
+    #[test]
+    fn test_failed_revert_backs_off() {
+        let mut store = RevertStore {
+            reverts: vec![pending("revert_1", 100)],
+        };
+        let error = BackupError::StorageError("写入失败".to_string());
+
+        let mut now = 100;
+        let mut delays = Vec::new();
+        for _ in 1..MAX_REVERT_ATTEMPTS {
+            store.claim("revert_1", now).unwrap();
+            assert_eq!(
+                store.record_failure("revert_1", &error, now),
+                Some(RevertStatus::Pending)
+            );
+            let retry_at = store.reverts[0].retry_at.unwrap();
+            delays.push(retry_at - now);
+            assert!(store.due(retry_at - 1).is_empty());
+            now = retry_at;
+        }
+        assert_eq!(delays, vec![60, 120, 240, 480]);
+        assert!(store.reverts[0].last_error.is_some());
+
+        // 次数用完后暂停
+        store.claim("revert_1", now).unwrap();
+        assert_eq!(
+            store.record_failure("revert_1", &error, now),
+            Some(RevertStatus::Paused)
+        );
+        assert!(store.due(u64::MAX).is_empty());
+        assert_eq!(retry_delay(40), MAX_RETRY_DELAY_SECS);
+    }
+
+    #[test]
+    fn test_permission_failure_pauses_revert() {
+        let mut store = RevertStore {
+            reverts: vec![pending("revert_1", 100)],
+        };
+        store.claim("revert_1", 100).unwrap();
+        // polkit 认证被取消时也返回 InsufficientPermissions，不再反复弹出认证
+        assert_eq!(
+            store.record_failure("revert_1", &BackupError::InsufficientPermissions, 100),
+            Some(RevertStatus::Paused)
+        );
+        assert!(store.due(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_revert_store_survives_reload() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("pending_reverts.json");
+
+        let store = RevertStore {
+            reverts: vec![pending("revert_1", 100), pending("revert_2", 200)],
+        };
+        json_store::save(&path, &store).unwrap();
+
+        let mut loaded: RevertStore = json_store::load(&path).unwrap();
+        assert_eq!(loaded.reverts.len(), 2);
+        assert_eq!(loaded.remove("revert_1").unwrap().revert_at, 100);
+        assert!(matches!(
+            loaded.remove("revert_1"),
+            Err(BackupError::PendingRevertNotFound(_))
+        ));
+    }
+}
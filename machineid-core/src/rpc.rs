@@ -0,0 +1,516 @@
+//! 本地 JSON-RPC 接口
+//!
+//! 守护进程模式下通过 Unix 域套接字（Windows 上为命名管道）提供与图形界面相同的操作，
+//! 每行一个 JSON-RPC 2.0 请求（不超过 [`MAX_REQUEST_LINE_BYTES`] 字节），每行一个响应。
+//! 业务错误的 `message` 为脱敏后的本地化描述，错误码见 `data.code`。访问控制依赖套接字文件权限：
+//! Unix 上套接字仅对属主可读写（0600），Windows 命名管道使用默认安全描述符并拒绝远程客户端。
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tracing::{error, info, warn};
+
+use crate::api::ApiError;
+use crate::audit::{query_audit_log, verify_audit_log, AuditQuery};
+use crate::i18n::{self, tr, Message};
+use crate::machine_id::{
+    backup_current_machine_guid, generate_random_machine_guid, list_backups,
+    read_machine_guid_audited, restore_backup_by_id, write_machine_guid, BackupError,
+};
+use crate::platform::capabilities::check_capabilities;
+use crate::platform::permissions::check_admin_permissions;
+
+/// 默认套接字文件名，位于备份文件同目录
+#[cfg(unix)]
+const SOCKET_FILE_NAME: &str = "machineid-manage.sock";
+/// 默认命名管道名称
+#[cfg(windows)]
+const PIPE_NAME: &str = r"\\.\pipe\machineid-manage";
+
+/// JSON-RPC 2.0 标准错误码
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// 业务错误（BackupError）统一使用的错误码，具体原因见 data.code
+pub const APPLICATION_ERROR: i64 = -32000;
+/// 单行请求的最大长度，超过时返回错误并关闭连接
+pub const MAX_REQUEST_LINE_BYTES: usize = 64 * 1024;
+
+/// 支持的方法名称
+pub const METHODS: &[&str] = &[
+    "read_machine_id",
+    "backup_machine_guid",
+    "list_backups",
+    "restore_backup",
+    "write_machine_guid",
+    "generate_random_guid",
+    "check_permission",
+    "check_capabilities",
+    "query_audit_log",
+    "verify_audit_log",
+];
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    jsonrpc: Option<String>,
+    /// 缺省时为通知，不返回响应
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    /// 业务错误的错误码（[`crate::api::ErrorCode`]）和结构化详情
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+/// 完整的错误描述可能含有路径等信息，只记入日志，响应中使用脱敏后的描述
+impl From<BackupError> for RpcError {
+    fn from(e: BackupError) -> Self {
+        warn!("RPC 业务错误: {}", e);
+        let error =
+            ApiError::from_backup_error(&e, i18n::error_summary(&e, i18n::current_locale()));
+        RpcError {
+            code: APPLICATION_ERROR,
+            message: error.message,
+            data: Some(json!({ "code": error.code, "details": error.details })),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    fn from_result(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(value) => (Some(value), None),
+            Err(e) => (None, Some(e)),
+        };
+        RpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// 默认的监听地址
+pub fn default_socket_path() -> Result<PathBuf, BackupError> {
+    #[cfg(unix)]
+    {
+        crate::machine_id::get_data_file_path(SOCKET_FILE_NAME)
+    }
+    #[cfg(windows)]
+    {
+        Ok(PathBuf::from(PIPE_NAME))
+    }
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(APPLICATION_ERROR, e.to_string()))
+}
+
+/// 读取可选的字符串参数
+fn optional_str(params: &Value, name: &str) -> Result<Option<String>, RpcError> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(RpcError::new(
+            INVALID_PARAMS,
+            tr(Message::RpcParamNotString { name }),
+        )),
+    }
+}
+
+/// 读取必需的字符串参数
+fn required_str(params: &Value, name: &str) -> Result<String, RpcError> {
+    optional_str(params, name)?
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, tr(Message::RpcParamMissing { name })))
+}
+
+/// 写操作前检查管理员权限，与图形界面的服务端校验保持一致
+fn require_admin() -> Result<(), RpcError> {
+    if check_admin_permissions().has_permission {
+        Ok(())
+    } else {
+        Err(BackupError::InsufficientPermissions.into())
+    }
+}
+
+/// 执行单个方法调用
+fn dispatch(method: &str, params: &Value) -> Result<Value, RpcError> {
+    if !params.is_null() && !params.is_object() {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            tr(Message::RpcParamsNotObject),
+        ));
+    }
+
+    match method {
+        "read_machine_id" => {
+            let machine_id = read_machine_guid_audited()?;
+            Ok(json!({ "guid": machine_id.guid, "source": machine_id.source }))
+        }
+        "backup_machine_guid" => {
+            let backup = backup_current_machine_guid(optional_str(params, "description")?)?;
+            Ok(json!({ "skipped": backup.is_none(), "backup": backup }))
+        }
+        "list_backups" => to_value(list_backups()?),
+        "restore_backup" => {
+            let id = required_str(params, "id")?;
+            require_admin()?;
+            to_value(restore_backup_by_id(&id)?)
+        }
+        "write_machine_guid" => {
+            let guid = required_str(params, "guid")?;
+            let description = optional_str(params, "description")?;
+            require_admin()?;
+            to_value(write_machine_guid(&guid, description)?)
+        }
+        "generate_random_guid" => {
+            let description = optional_str(params, "description")?;
+            require_admin()?;
+            to_value(generate_random_machine_guid(description)?)
+        }
+        "check_permission" => to_value(check_admin_permissions()),
+        "check_capabilities" => to_value(check_capabilities()),
+        "query_audit_log" => {
+            let query: AuditQuery = if params.is_null() {
+                AuditQuery::default()
+            } else {
+                serde_json::from_value(params.clone())
+                    .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?
+            };
+            to_value(query_audit_log(&query)?)
+        }
+        "verify_audit_log" => to_value(verify_audit_log()?),
+        other => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            tr(Message::RpcUnknownMethod { name: other }),
+        )),
+    }
+}
+
+/// 处理一行请求，通知（无 id）返回 None
+pub fn handle_request_line(line: &str) -> Option<RpcResponse> {
+    let raw: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => {
+            return Some(RpcResponse::from_result(
+                Value::Null,
+                Err(RpcError::new(PARSE_ERROR, e.to_string())),
+            ))
+        }
+    };
+
+    let id_hint = raw.get("id").cloned().unwrap_or(Value::Null);
+    let request: RpcRequest = match serde_json::from_value(raw) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(RpcResponse::from_result(
+                id_hint,
+                Err(RpcError::new(INVALID_REQUEST, e.to_string())),
+            ))
+        }
+    };
+
+    if request.jsonrpc.as_deref() != Some("2.0") {
+        return Some(RpcResponse::from_result(
+            id_hint,
+            Err(RpcError::new(
+                INVALID_REQUEST,
+                tr(Message::RpcVersionInvalid),
+            )),
+        ));
+    }
+
+    let result = dispatch(&request.method, &request.params);
+    if let Err(e) = &result {
+        warn!("RPC 调用 {} 失败: {}", request.method, e.message);
+    }
+    request.id.map(|id| RpcResponse::from_result(id, result))
+}
+
+/// 处理单个连接直到对端关闭
+async fn handle_connection<S>(stream: S)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+
+    loop {
+        // 多读一个字节，用于判断是否超过长度上限
+        let mut buf = Vec::new();
+        let limit = MAX_REQUEST_LINE_BYTES as u64 + 1;
+        match (&mut reader).take(limit).read_until(b'\n', &mut buf).await {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                warn!("读取 RPC 请求失败: {}", e);
+                break;
+            }
+        }
+        if buf.len() > MAX_REQUEST_LINE_BYTES && buf.last() != Some(&b'\n') {
+            warn!("RPC 请求超过 {} 字节，关闭连接", MAX_REQUEST_LINE_BYTES);
+            let response = RpcResponse::from_result(
+                Value::Null,
+                Err(RpcError::new(
+                    INVALID_REQUEST,
+                    tr(Message::RequestBodyTooLarge),
+                )),
+            );
+            write_response(&mut writer, &response).await;
+            break;
+        }
+        let line = String::from_utf8_lossy(&buf).into_owned();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // 机器码操作均为阻塞调用，放到阻塞线程池执行
+        let response = match tokio::task::spawn_blocking(move || handle_request_line(&line)).await {
+            Ok(response) => response,
+            Err(e) => {
+                error!("RPC 处理任务异常退出: {}", e);
+                break;
+            }
+        };
+
+        if let Some(response) = response {
+            if !write_response(&mut writer, &response).await {
+                break;
+            }
+        }
+    }
+}
+
+/// 写入一行响应，失败时返回 false
+async fn write_response<W: AsyncWrite + Unpin>(writer: &mut W, response: &RpcResponse) -> bool {
+    let mut payload = serde_json::to_string(response).unwrap_or_default();
+    payload.push('\n');
+    match writer.write_all(payload.as_bytes()).await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("写入 RPC 响应失败: {}", e);
+            false
+        }
+    }
+}
+
+/// 在指定套接字上提供 JSON-RPC 服务，直到出现不可恢复的错误
+#[cfg(unix)]
+pub async fn serve(path: &Path) -> Result<(), BackupError> {
+    use std::fs;
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::os::unix::net::UnixStream as StdUnixStream;
+
+    if path.exists() {
+        // 仍有进程在监听时拒绝覆盖，否则视为残留文件
+        if StdUnixStream::connect(path).is_ok() {
+            return Err(BackupError::StorageError(tr(Message::RpcSocketInUse {
+                path: &path.display().to_string(),
+            })));
+        }
+        fs::remove_file(path).map_err(|e| BackupError::StorageError(e.to_string()))?;
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| BackupError::StorageError(e.to_string()))?;
+    }
+
+    // 先在新建的私有目录（0700）中创建套接字并设为 0600，再移动到目标位置，
+    // bind 与 chmod 之间其他用户无法连接；不修改进程级的 umask，以免影响其他线程创建的文件
+    // 套接字路径长度有限（约 108 字节），临时路径只比目标路径略长
+    let staging = path.with_file_name(format!(".rpc-{:08x}", rand::random::<u32>()));
+    fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .map_err(|e| BackupError::StorageError(e.to_string()))?;
+    let staged = staging.join("sock");
+    let bound = tokio::net::UnixListener::bind(&staged).and_then(|listener| {
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o600))?;
+        fs::rename(&staged, path)?;
+        Ok(listener)
+    });
+    let _ = fs::remove_dir_all(&staging);
+    let listener = bound.map_err(|e| BackupError::StorageError(e.to_string()))?;
+    info!("JSON-RPC 服务已启动: {}", path.display());
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_connection(stream));
+            }
+            Err(e) => {
+                error!("接受 RPC 连接失败: {}", e);
+                return Err(BackupError::StorageError(e.to_string()));
+            }
+        }
+    }
+}
+
+/// 在指定命名管道上提供 JSON-RPC 服务，直到出现不可恢复的错误
+#[cfg(windows)]
+pub async fn serve(path: &Path) -> Result<(), BackupError> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = path.as_os_str();
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(name)
+        .map_err(|e| BackupError::StorageError(e.to_string()))?;
+    info!("JSON-RPC 服务已启动: {}", path.display());
+
+    loop {
+        server
+            .connect()
+            .await
+            .map_err(|e| BackupError::StorageError(e.to_string()))?;
+        let connected = server;
+        // 在处理当前连接前创建下一个实例，避免客户端连接间隙失败
+        server = ServerOptions::new()
+            .reject_remote_clients(true)
+            .create(name)
+            .map_err(|e| BackupError::StorageError(e.to_string()))?;
+        tokio::spawn(handle_connection(connected));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(line: &str) -> RpcResponse {
+        handle_request_line(line).expect("请求应有响应")
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let response = call("{not json");
+        assert_eq!(response.id, Value::Null);
+        assert_eq!(response.error.unwrap().code, PARSE_ERROR);
+    }
+
+    #[test]
+    fn test_invalid_request() {
+        let response = call(r#"{"jsonrpc":"2.0","id":1}"#);
+        assert_eq!(response.id, json!(1));
+        assert_eq!(response.error.unwrap().code, INVALID_REQUEST);
+
+        let response = call(r#"{"jsonrpc":"1.0","id":2,"method":"list_backups"}"#);
+        assert_eq!(response.error.unwrap().code, INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_method_not_found() {
+        let response = call(r#"{"jsonrpc":"2.0","id":"a","method":"format_disk"}"#);
+        assert_eq!(response.id, json!("a"));
+        assert_eq!(response.error.unwrap().code, METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let response = call(r#"{"jsonrpc":"2.0","id":1,"method":"restore_backup"}"#);
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+
+        let response =
+            call(r#"{"jsonrpc":"2.0","id":1,"method":"write_machine_guid","params":{"guid":1}}"#);
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+
+        let response = call(r#"{"jsonrpc":"2.0","id":1,"method":"list_backups","params":[1]}"#);
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_notification_has_no_response() {
+        assert!(handle_request_line(r#"{"jsonrpc":"2.0","method":"check_permission"}"#).is_none());
+    }
+
+    #[test]
+    fn test_check_permission() {
+        let response = call(r#"{"jsonrpc":"2.0","id":7,"method":"check_permission"}"#);
+        assert!(response.error.is_none());
+        assert!(response.result.unwrap().get("has_permission").is_some());
+    }
+
+    #[test]
+    fn test_check_capabilities() {
+        let response = call(r#"{"jsonrpc":"2.0","id":8,"method":"check_capabilities"}"#);
+        let result = response.result.unwrap();
+        assert_eq!(result["providers"][0]["identifier"], "MachineGuid");
+    }
+
+    #[test]
+    fn test_backup_error_carries_code_and_summary() {
+        let error = RpcError::from(BackupError::StorageError(
+            "/home/user/.data/backups.json".to_string(),
+        ));
+        assert_eq!(error.code, APPLICATION_ERROR);
+        assert!(!error.message.contains("/home/user"));
+        assert_eq!(error.data.unwrap()["code"], "storage_error");
+
+        let error = RpcError::from(BackupError::GuidGenerationExhausted(32));
+        assert_eq!(
+            error.data.unwrap(),
+            json!({ "code": "guid_generation_exhausted", "details": { "attempts": 32 } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_oversized_request_line_is_rejected() {
+        let (mut client, server) = tokio::io::duplex(1024);
+        let handler = tokio::spawn(handle_connection(server));
+
+        let line = vec![b' '; MAX_REQUEST_LINE_BYTES + 1];
+        // 服务端拒绝后关闭连接，后续写入可能失败
+        let _ = client.write_all(&line).await;
+        let mut response = String::new();
+        BufReader::new(&mut client)
+            .read_line(&mut response)
+            .await
+            .unwrap();
+        let response: RpcResponse = serde_json::from_str(&response).unwrap();
+        assert_eq!(response.error.unwrap().code, INVALID_REQUEST);
+        handler.await.unwrap();
+    }
+
+    #[test]
+    fn test_query_audit_log_rejects_invalid_params() {
+        let response =
+            call(r#"{"jsonrpc":"2.0","id":9,"method":"query_audit_log","params":{"limit":"x"}}"#);
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+
+        let response = call(
+            r#"{"jsonrpc":"2.0","id":9,"method":"query_audit_log","params":{"operation":"rename"}}"#,
+        );
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,694 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use crate::i18n::{tr, Message};
+use crate::json_store::{self, now_secs};
+use crate::machine_id::{
+    generate_random_machine_guid_with, get_backup_by_id, get_data_file_path, restore_backup_by_id,
+    AutoDescription, BackupDescription, BackupError,
+};
+
+/// 调度器检查间隔（秒）
+const SCHEDULER_TICK_SECS: u64 = 30;
+/// 每个计划任务保留的运行记录数量
+const MAX_RUN_HISTORY: usize = 20;
+/// 固定间隔的上限（一年）
+pub const MAX_INTERVAL_MINUTES: u64 = 366 * 24 * 60;
+/// 计划任务文件名
+const SCHEDULE_FILE: &str = "schedules.json";
+/// 以开机时间标识开机时，允许的误差（秒）
+const BOOT_TIME_TOLERANCE_SECS: u64 = 10;
+
+/// 计划任务执行的操作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum ScheduleAction {
+    /// 生成并写入随机 MachineGuid
+    GenerateRandom,
+    /// 恢复指定备份
+    RestoreBackup { backup_id: String },
+}
+
+/// 计划任务触发条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum ScheduleTrigger {
+    /// 每隔固定分钟数执行一次
+    Interval {
+        #[cfg_attr(feature = "ts", ts(type = "number"))]
+        minutes: u64,
+    },
+    /// 每次开机后程序首次启动时执行一次
+    AtStartup,
+    /// 五段式 cron 表达式（分 时 日 月 周），按 UTC 时间匹配
+    Cron { expression: String },
+}
+
+impl ScheduleTrigger {
+    /// 校验触发条件配置
+    pub fn validate(&self) -> Result<(), BackupError> {
+        match self {
+            ScheduleTrigger::Interval { minutes } if *minutes == 0 => Err(
+                BackupError::InvalidSchedule(tr(Message::ScheduleIntervalZero)),
+            ),
+            ScheduleTrigger::Interval { minutes } if *minutes > MAX_INTERVAL_MINUTES => Err(
+                BackupError::InvalidSchedule(tr(Message::ScheduleIntervalTooLong {
+                    max: MAX_INTERVAL_MINUTES,
+                })),
+            ),
+            ScheduleTrigger::Cron { expression } => CronExpr::parse(expression).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 单次运行记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct ScheduleRun {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub timestamp: u64,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Schedule {
+    pub id: String,
+    pub name: Option<String>,
+    pub action: ScheduleAction,
+    pub trigger: ScheduleTrigger,
+    pub paused: bool,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub last_run: Option<u64>,
+    /// 最近的运行记录，最新的在前
+    #[serde(default)]
+    pub history: Vec<ScheduleRun>,
+}
+
+impl Schedule {
+    /// 判断计划任务在指定时间是否应当执行
+    /// `AtStartup` 类型只在调度器启动时执行，不参与周期检查
+    pub fn is_due(&self, now: u64) -> bool {
+        if self.paused {
+            return false;
+        }
+        match &self.trigger {
+            ScheduleTrigger::Interval { minutes } => {
+                // 文件中的值可能未经校验，溢出时视为永不到期
+                let since = self.last_run.unwrap_or(self.created_at);
+                minutes
+                    .checked_mul(60)
+                    .is_some_and(|secs| now >= since.saturating_add(secs))
+            }
+            ScheduleTrigger::AtStartup => false,
+            ScheduleTrigger::Cron { expression } => {
+                let ran_this_minute = self.last_run.map(|t| t / 60) == Some(now / 60);
+                !ran_this_minute
+                    && CronExpr::parse(expression)
+                        .map(|cron| cron.matches(now))
+                        .unwrap_or(false)
+            }
+        }
+    }
+
+    fn record_run(&mut self, run: ScheduleRun) {
+        self.last_run = Some(run.timestamp);
+        self.history.insert(0, run);
+        self.history.truncate(MAX_RUN_HISTORY);
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleStore {
+    pub schedules: Vec<Schedule>,
+    /// 最近一次执行 `AtStartup` 任务时的开机标识
+    #[serde(default)]
+    pub startup_boot: Option<String>,
+}
+
+impl ScheduleStore {
+    pub fn get_mut(&mut self, id: &str) -> Result<&mut Schedule, BackupError> {
+        self.schedules
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| BackupError::ScheduleNotFound(id.to_string()))
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<Schedule, BackupError> {
+        let index = self
+            .schedules
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| BackupError::ScheduleNotFound(id.to_string()))?;
+        Ok(self.schedules.remove(index))
+    }
+
+    /// 登记本次开机已执行 `AtStartup` 任务，本次开机已执行过时返回 false
+    /// 无法获取开机标识时每次启动都执行
+    fn claim_startup(&mut self, boot: Option<&str>) -> bool {
+        if let (Some(recorded), Some(current)) = (&self.startup_boot, boot) {
+            if same_boot(recorded, current) {
+                return false;
+            }
+        }
+        self.startup_boot = boot.map(str::to_string);
+        true
+    }
+}
+
+/// 本次开机的标识：Linux 使用内核的 boot_id，Windows 和 macOS 使用开机时间
+#[cfg(target_os = "linux")]
+fn current_boot() -> Option<String> {
+    crate::platform::linux::boot_id()
+}
+
+#[cfg(target_os = "windows")]
+fn current_boot() -> Option<String> {
+    crate::platform::windows::boot_time().map(|t| t.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn current_boot() -> Option<String> {
+    crate::platform::macos::boot_time().map(|t| t.to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn current_boot() -> Option<String> {
+    None
+}
+
+/// 两个开机标识是否属于同一次开机，开机时间允许少量误差
+fn same_boot(recorded: &str, current: &str) -> bool {
+    if recorded == current {
+        return true;
+    }
+    match (recorded.parse::<u64>(), current.parse::<u64>()) {
+        (Ok(recorded), Ok(current)) => recorded.abs_diff(current) <= BOOT_TIME_TOLERANCE_SECS,
+        _ => false,
+    }
+}
+
+/// 简化的 cron 表达式
+/// 支持 `*`、数字、范围 `a-b`、步长 `*/n` 与 `a-b/n` 以及逗号分隔的列表
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// 日和周字段都被限定时，按标准 cron 语义任一匹配即可
+    day_restricted: bool,
+    weekday_restricted: bool,
+}
+
+impl CronExpr {
+    pub fn parse(expression: &str) -> Result<Self, BackupError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(BackupError::InvalidSchedule(tr(Message::CronFieldCount {
+                actual: fields.len(),
+            })));
+        }
+
+        Ok(CronExpr {
+            minutes: parse_cron_field(fields[0], 0, 59)?,
+            hours: parse_cron_field(fields[1], 0, 23)?,
+            days: parse_cron_field(fields[2], 1, 31)?,
+            months: parse_cron_field(fields[3], 1, 12)?,
+            // 周日可以写作 0 或 7
+            weekdays: {
+                let bits = parse_cron_field(fields[4], 0, 7)?;
+                (bits | (bits >> 7)) & 0x7f
+            },
+            day_restricted: fields[2] != "*",
+            weekday_restricted: fields[4] != "*",
+        })
+    }
+
+    /// 判断指定的 UTC 时间戳（秒）是否匹配
+    pub fn matches(&self, timestamp: u64) -> bool {
+        let minute = (timestamp / 60) % 60;
+        let hour = (timestamp / 3600) % 24;
+        let days_since_epoch = timestamp / 86400;
+        let (month, day) = month_day_from_days(days_since_epoch);
+        // 1970-01-01 是星期四
+        let weekday = (days_since_epoch + 4) % 7;
+
+        let day_match = bit_set(self.days, day);
+        let weekday_match = bit_set(self.weekdays, weekday);
+        let day_of_month_ok = if self.day_restricted && self.weekday_restricted {
+            day_match || weekday_match
+        } else {
+            day_match && weekday_match
+        };
+
+        bit_set(self.minutes, minute)
+            && bit_set(self.hours, hour)
+            && bit_set(self.months, month)
+            && day_of_month_ok
+    }
+}
+
+fn bit_set(bits: u64, value: u64) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn parse_cron_field(field: &str, min: u64, max: u64) -> Result<u64, BackupError> {
+    let invalid = || BackupError::InvalidSchedule(tr(Message::CronFieldInvalid { field }));
+    let parse_num = |s: &str| -> Result<u64, BackupError> {
+        let value: u64 = s.parse().map_err(|_| invalid())?;
+        if value < min || value > max {
+            return Err(invalid());
+        }
+        Ok(value)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u64 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_num(start)?, parse_num(end)?)
+        } else {
+            let value = parse_num(range)?;
+            // `a/n` 表示从 a 开始到最大值
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(invalid());
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+/// 将自 1970-01-01 起的天数转换为（月, 日）
+fn month_day_from_days(days: u64) -> (u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (month, day)
+}
+
+fn with_schedule_store<T, F>(f: F) -> Result<T, BackupError>
+where
+    F: FnOnce(&mut ScheduleStore) -> Result<T, BackupError>,
+{
+    json_store::update(&get_data_file_path(SCHEDULE_FILE)?, f)
+}
+
+pub fn list_schedules() -> Result<Vec<Schedule>, BackupError> {
+    let store: ScheduleStore = json_store::load(&get_data_file_path(SCHEDULE_FILE)?)?;
+    Ok(store.schedules)
+}
+
+pub fn add_schedule(
+    name: Option<String>,
+    action: ScheduleAction,
+    trigger: ScheduleTrigger,
+) -> Result<Schedule, BackupError> {
+    trigger.validate()?;
+    if let ScheduleAction::RestoreBackup { backup_id } = &action {
+        get_backup_by_id(backup_id)?;
+    }
+
+    let now = now_secs();
+    let schedule = Schedule {
+        id: format!(
+            "schedule_{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis()
+        ),
+        name,
+        action,
+        trigger,
+        paused: false,
+        created_at: now,
+        last_run: None,
+        history: Vec::new(),
+    };
+
+    with_schedule_store(|store| {
+        store.schedules.push(schedule.clone());
+        Ok(())
+    })?;
+    info!("已添加计划任务: {} ({:?})", schedule.id, schedule.trigger);
+    Ok(schedule)
+}
+
+pub fn set_schedule_paused(id: &str, paused: bool) -> Result<Schedule, BackupError> {
+    with_schedule_store(|store| {
+        let schedule = store.get_mut(id)?;
+        schedule.paused = paused;
+        Ok(schedule.clone())
+    })
+}
+
+pub fn remove_schedule(id: &str) -> Result<Schedule, BackupError> {
+    with_schedule_store(|store| store.remove(id))
+}
+
+/// 执行计划任务对应的操作
+fn run_action(action: &ScheduleAction) -> Result<String, BackupError> {
+    match action {
+        ScheduleAction::GenerateRandom => {
+            let result = generate_random_machine_guid_with(Some(BackupDescription::Auto(
+                AutoDescription::ScheduledRotation,
+            )))?;
+            Ok(tr(Message::ScheduleRotated {
+                guid: &result.write.new_guid,
+            }))
+        }
+        ScheduleAction::RestoreBackup { backup_id } => {
+            let result = restore_backup_by_id(backup_id)?;
+            Ok(tr(Message::ScheduleRestored {
+                guid: &result.restored_guid,
+            }))
+        }
+    }
+}
+
+/// 执行到期的计划任务并记录结果
+/// `at_startup` 为 true 时执行 `AtStartup` 类型的任务，每次开机只执行一次
+///
+/// 任务在存储锁内标记本次运行时间后再执行，图形界面和常驻服务同时运行调度器时不会重复执行。
+fn run_due_schedules(at_startup: bool) -> Result<(), BackupError> {
+    let now = now_secs();
+    let boot = if at_startup { current_boot() } else { None };
+    let due: Vec<Schedule> = with_schedule_store(|store| {
+        if at_startup && !store.claim_startup(boot.as_deref()) {
+            info!("本次开机已执行过启动计划任务");
+            return Ok(Vec::new());
+        }
+        Ok(store
+            .schedules
+            .iter_mut()
+            .filter(|s| {
+                if at_startup {
+                    !s.paused && s.trigger == ScheduleTrigger::AtStartup
+                } else {
+                    s.is_due(now)
+                }
+            })
+            .map(|s| {
+                s.last_run = Some(now);
+                s.clone()
+            })
+            .collect())
+    })?;
+
+    for schedule in due {
+        info!("执行计划任务: {} ({:?})", schedule.id, schedule.action);
+        let run = match run_action(&schedule.action) {
+            Ok(message) => {
+                info!("计划任务 {} 执行成功: {}", schedule.id, message);
+                ScheduleRun {
+                    timestamp: now,
+                    success: true,
+                    message,
+                }
+            }
+            Err(e) => {
+                warn!("计划任务 {} 执行失败: {}", schedule.id, e);
+                ScheduleRun {
+                    timestamp: now,
+                    success: false,
+                    message: e.to_string(),
+                }
+            }
+        };
+
+        // 任务可能在执行期间被删除，此时忽略运行记录
+        with_schedule_store(|store| {
+            if let Ok(current) = store.get_mut(&schedule.id) {
+                current.record_run(run);
+            }
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// 运行调度器
+/// 启动时先执行 `AtStartup` 任务，之后周期性检查其余任务
+pub async fn run_scheduler() {
+    info!("计划任务调度器启动");
+
+    let startup = tokio::task::spawn_blocking(|| run_due_schedules(true)).await;
+    if let Ok(Err(e)) = startup {
+        error!("执行启动计划任务失败: {}", e);
+    }
+
+    let mut interval = tokio::time::interval(Duration::from_secs(SCHEDULER_TICK_SECS));
+    loop {
+        interval.tick().await;
+        match tokio::task::spawn_blocking(|| run_due_schedules(false)).await {
+            Ok(Err(e)) => error!("执行计划任务失败: {}", e),
+            Err(e) => error!("计划任务线程异常: {}", e),
+            Ok(Ok(())) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn schedule_with(trigger: ScheduleTrigger) -> Schedule {
+        Schedule {
+            id: "schedule_1".to_string(),
+            name: None,
+            action: ScheduleAction::GenerateRandom,
+            trigger,
+            paused: false,
+            created_at: 0,
+            last_run: None,
+            history: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_cron_parse_invalid() {
+        let invalid = vec![
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ];
+        for expression in invalid {
+            assert!(
+                CronExpr::parse(expression).is_err(),
+                "应该解析失败: {}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn test_cron_matches() {
+        // 2024-01-01 00:00:00 UTC，星期一
+        let monday_midnight = 1_704_067_200;
+
+        let every_minute = CronExpr::parse("* * * * *").unwrap();
+        assert!(every_minute.matches(monday_midnight));
+
+        let every_six_hours = CronExpr::parse("0 */6 * * *").unwrap();
+        assert!(every_six_hours.matches(monday_midnight));
+        assert!(every_six_hours.matches(monday_midnight + 6 * 3600));
+        assert!(!every_six_hours.matches(monday_midnight + 3600));
+        assert!(!every_six_hours.matches(monday_midnight + 60));
+
+        let weekdays = CronExpr::parse("30 9 * * 1-5").unwrap();
+        assert!(weekdays.matches(monday_midnight + 9 * 3600 + 30 * 60));
+        // 2024-01-06 是星期六
+        assert!(!weekdays.matches(monday_midnight + 5 * 86400 + 9 * 3600 + 30 * 60));
+
+        let sunday = CronExpr::parse("0 0 * * 7").unwrap();
+        assert!(sunday.matches(monday_midnight + 6 * 86400));
+
+        let new_year = CronExpr::parse("0 0 1 1 *").unwrap();
+        assert!(new_year.matches(monday_midnight));
+        assert!(!new_year.matches(monday_midnight + 86400));
+    }
+
+    #[test]
+    fn test_month_day_from_days() {
+        assert_eq!(month_day_from_days(0), (1, 1));
+        // 2024-02-29
+        assert_eq!(month_day_from_days(19_782), (2, 29));
+        // 2024-12-31
+        assert_eq!(month_day_from_days(20_088), (12, 31));
+    }
+
+    #[test]
+    fn test_interval_schedule_is_due() {
+        let mut schedule = schedule_with(ScheduleTrigger::Interval { minutes: 60 });
+        assert!(!schedule.is_due(3599));
+        assert!(schedule.is_due(3600));
+
+        schedule.record_run(ScheduleRun {
+            timestamp: 3600,
+            success: true,
+            message: String::new(),
+        });
+        assert!(!schedule.is_due(3600 + 60));
+        assert!(schedule.is_due(7200));
+
+        schedule.paused = true;
+        assert!(!schedule.is_due(7200));
+
+        // 超大的间隔不会溢出
+        let schedule = schedule_with(ScheduleTrigger::Interval { minutes: u64::MAX });
+        assert!(!schedule.is_due(u64::MAX));
+    }
+
+    #[test]
+    fn test_cron_schedule_runs_once_per_minute() {
+        let mut schedule = schedule_with(ScheduleTrigger::Cron {
+            expression: "* * * * *".to_string(),
+        });
+        assert!(schedule.is_due(120));
+
+        schedule.record_run(ScheduleRun {
+            timestamp: 125,
+            success: true,
+            message: String::new(),
+        });
+        assert!(!schedule.is_due(150));
+        assert!(schedule.is_due(180));
+    }
+
+    #[test]
+    fn test_startup_schedule_never_periodically_due() {
+        let schedule = schedule_with(ScheduleTrigger::AtStartup);
+        assert!(!schedule.is_due(u32::MAX as u64));
+    }
+
+    #[test]
+    fn test_startup_schedules_run_once_per_boot() {
+        let mut store = ScheduleStore::default();
+        assert!(store.claim_startup(Some("boot-a")));
+        // 同一次开机内重新启动程序（如以管理员权限重启）不再执行
+        assert!(!store.claim_startup(Some("boot-a")));
+        assert!(store.claim_startup(Some("boot-b")));
+        assert_eq!(store.startup_boot.as_deref(), Some("boot-b"));
+
+        // 开机时间的少量误差视为同一次开机
+        assert!(store.claim_startup(Some("1700000000")));
+        assert!(!store.claim_startup(Some("1700000003")));
+        assert!(store.claim_startup(Some("1700003600")));
+
+        // 无法获取开机标识时每次都执行
+        assert!(store.claim_startup(None));
+        assert!(store.claim_startup(None));
+    }
+
+    #[test]
+    fn test_run_history_is_capped() {
+        let mut schedule = schedule_with(ScheduleTrigger::AtStartup);
+        for i in 0..(MAX_RUN_HISTORY as u64 + 5) {
+            schedule.record_run(ScheduleRun {
+                timestamp: i,
+                success: true,
+                message: String::new(),
+            });
+        }
+        assert_eq!(schedule.history.len(), MAX_RUN_HISTORY);
+        assert_eq!(schedule.history[0].timestamp, MAX_RUN_HISTORY as u64 + 4);
+        assert_eq!(schedule.last_run, Some(MAX_RUN_HISTORY as u64 + 4));
+    }
+
+    #[test]
+    fn test_trigger_validate() {
+        assert!(ScheduleTrigger::Interval { minutes: 0 }.validate().is_err());
+        assert!(ScheduleTrigger::Interval { minutes: 30 }.validate().is_ok());
+        assert!(ScheduleTrigger::Interval {
+            minutes: MAX_INTERVAL_MINUTES
+        }
+        .validate()
+        .is_ok());
+        assert!(ScheduleTrigger::Interval {
+            minutes: MAX_INTERVAL_MINUTES + 1
+        }
+        .validate()
+        .is_err());
+        assert!(ScheduleTrigger::AtStartup.validate().is_ok());
+        assert!(ScheduleTrigger::Cron {
+            expression: "0 3 * * *".to_string()
+        }
+        .validate()
+        .is_ok());
+        assert!(ScheduleTrigger::Cron {
+            expression: "invalid".to_string()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_schedule_store_persistence() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("schedules.json");
+
+        let mut store: ScheduleStore = json_store::load(&path).unwrap();
+        assert!(store.schedules.is_empty());
+
+        store.schedules.push(schedule_with(ScheduleTrigger::Cron {
+            expression: "0 */6 * * *".to_string(),
+        }));
+        json_store::save(&path, &store).unwrap();
+
+        let mut loaded: ScheduleStore = json_store::load(&path).unwrap();
+        assert_eq!(loaded.schedules.len(), 1);
+        assert_eq!(loaded.schedules[0].action, ScheduleAction::GenerateRandom);
+
+        loaded.get_mut("schedule_1").unwrap().paused = true;
+        assert!(loaded.schedules[0].paused);
+
+        assert!(loaded.remove("schedule_1").is_ok());
+        assert!(matches!(
+            loaded.remove("schedule_1"),
+            Err(BackupError::ScheduleNotFound(_))
+        ));
+    }
+}
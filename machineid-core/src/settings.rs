@@ -0,0 +1,642 @@
+//! 应用配置
+//!
+//! 配置按以下顺序逐层覆盖，后者优先：
+//!
+//! 1. 内置默认值
+//! 2. 系统配置文件（Windows `%ProgramData%\MachineID-Manage\config.toml`，
+//!    macOS `/Library/Application Support/MachineID-Manage/config.toml`，
+//!    Linux `/etc/machineid-manage/config.toml`）
+//! 3. 用户配置文件（可通过 `MACHINEID_CONFIG` 环境变量或 `--config` 参数指定）
+//! 4. `MACHINEID_` 前缀的环境变量，例如 `MACHINEID_DATA_DIR`、`MACHINEID_LOG_LEVEL`
+//! 5. 命令行参数
+//!
+//! 配置文件为 TOML 格式，所有字段均可省略：
+//!
+//! ```toml
+//! data_dir_mode = "installed"
+//! max_description_length = 200
+//! log_level = "info"
+//! log_max_size_mb = 10
+//! log_max_files = 5
+//! debug_mode = false
+//! locale = "en"
+//! http_port = 17800
+//! watch_identity = true
+//! watch_interval_secs = 5
+//! watch_auto_backup = true
+//! watch_action = "alert"
+//! minimize_to_tray = true
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use config::{Config, Environment, File, FileFormat, Map};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::data_dir::DataDirMode;
+use crate::i18n::{self, tr, Locale, Message};
+use crate::machine_id::BackupError;
+use crate::watcher::WatchAction;
+
+/// 环境变量前缀
+pub const ENV_PREFIX: &str = "MACHINEID";
+/// 指定用户配置文件路径的环境变量
+pub const CONFIG_ENV: &str = "MACHINEID_CONFIG";
+/// 配置文件名
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+/// 默认的描述长度上限（字符数）
+pub const DEFAULT_MAX_DESCRIPTION_LENGTH: usize = 200;
+/// 描述长度上限允许配置的最大值
+pub const MAX_DESCRIPTION_LENGTH_LIMIT: usize = 2000;
+/// 本地 HTTP 接口的默认端口
+pub const DEFAULT_HTTP_PORT: u16 = 17_800;
+/// 默认的机器码轮询间隔（秒）
+pub const DEFAULT_WATCH_INTERVAL_SECS: u64 = 5;
+/// 机器码轮询间隔允许配置的最大值（秒）
+pub const MAX_WATCH_INTERVAL_SECS: u64 = 3600;
+/// 默认的单个日志文件大小上限（MB）
+pub const DEFAULT_LOG_MAX_SIZE_MB: u64 = 10;
+/// 单个日志文件大小上限允许配置的最大值（MB）
+pub const MAX_LOG_SIZE_MB: u64 = 1024;
+/// 默认保留的已轮转日志文件数
+pub const DEFAULT_LOG_MAX_FILES: usize = 5;
+/// 已轮转日志文件保留数量允许配置的最大值
+pub const MAX_LOG_FILES: usize = 100;
+
+lazy_static! {
+    /// 当前生效的配置，首次使用时加载
+    static ref SETTINGS: RwLock<Option<Settings>> = RwLock::new(None);
+    /// 启动时传入的命令行覆盖项，重新加载时沿用
+    static ref OVERRIDES: RwLock<SettingsOverrides> = RwLock::new(SettingsOverrides::default());
+}
+
+/// 日志级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// 日志过滤器使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+
+    /// 按名称解析，不区分大小写
+    pub fn from_name(name: &str) -> Option<LogLevel> {
+        match name.to_ascii_lowercase().as_str() {
+            "trace" => Some(LogLevel::Trace),
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+/// 应用配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub struct Settings {
+    /// 自定义数据目录（备份、计划任务等文件的位置），设置后忽略 `data_dir_mode`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub data_dir: Option<PathBuf>,
+    /// 数据目录模式，未设置时根据标记文件自动判断，见 [`crate::data_dir`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub data_dir_mode: Option<DataDirMode>,
+    /// 备份、计划任务和身份配置描述的最大字符数
+    pub max_description_length: usize,
+    /// 日志级别，设置了 `RUST_LOG` 时以其为准；修改后重启生效
+    pub log_level: LogLevel,
+    /// 单个日志文件的大小上限（MB），超过后轮转；修改后重启生效
+    pub log_max_size_mb: u64,
+    /// 保留的已轮转日志文件数，见 [`crate::logging`]
+    pub log_max_files: usize,
+    /// 调试模式：日志中保留完整的机器码，仅在排查问题时开启；修改后重启生效
+    pub debug_mode: bool,
+    /// 界面和消息语言，未设置时按系统语言自动选择
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub locale: Option<Locale>,
+    /// 本地 HTTP 接口端口
+    pub http_port: u16,
+    /// 是否监视其他程序对机器码的修改，见 [`crate::watcher`]
+    pub watch_identity: bool,
+    /// 无法使用文件系统通知时轮询机器码的间隔（秒）
+    pub watch_interval_secs: u64,
+    /// 检测到外部修改时是否自动备份新的机器码
+    pub watch_auto_backup: bool,
+    /// 检测到外部修改后的处理方式
+    pub watch_action: WatchAction,
+    /// 关闭主窗口时隐藏到系统托盘，程序保持常驻
+    pub minimize_to_tray: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            data_dir: None,
+            data_dir_mode: None,
+            max_description_length: DEFAULT_MAX_DESCRIPTION_LENGTH,
+            log_level: LogLevel::default(),
+            log_max_size_mb: DEFAULT_LOG_MAX_SIZE_MB,
+            log_max_files: DEFAULT_LOG_MAX_FILES,
+            debug_mode: false,
+            locale: None,
+            http_port: DEFAULT_HTTP_PORT,
+            watch_identity: true,
+            watch_interval_secs: DEFAULT_WATCH_INTERVAL_SECS,
+            watch_auto_backup: true,
+            watch_action: WatchAction::default(),
+            minimize_to_tray: true,
+        }
+    }
+}
+
+impl Settings {
+    /// 校验配置取值
+    pub fn validate(&self) -> Result<(), BackupError> {
+        if let Some(dir) = &self.data_dir {
+            if !dir.is_absolute() {
+                return Err(BackupError::InvalidSettings(tr(
+                    Message::SettingsDataDirNotAbsolute {
+                        path: &dir.display().to_string(),
+                    },
+                )));
+            }
+        }
+        if !(1..=MAX_DESCRIPTION_LENGTH_LIMIT).contains(&self.max_description_length) {
+            return Err(BackupError::InvalidSettings(tr(
+                Message::SettingsDescriptionLengthOutOfRange {
+                    max: MAX_DESCRIPTION_LENGTH_LIMIT,
+                },
+            )));
+        }
+        if self.http_port == 0 {
+            return Err(BackupError::InvalidSettings(tr(
+                Message::SettingsHttpPortZero,
+            )));
+        }
+        if !(1..=MAX_WATCH_INTERVAL_SECS).contains(&self.watch_interval_secs) {
+            return Err(BackupError::InvalidSettings(tr(
+                Message::SettingsWatchIntervalOutOfRange {
+                    max: MAX_WATCH_INTERVAL_SECS,
+                },
+            )));
+        }
+        if !(1..=MAX_LOG_SIZE_MB).contains(&self.log_max_size_mb) {
+            return Err(BackupError::InvalidSettings(tr(
+                Message::SettingsLogSizeOutOfRange {
+                    max: MAX_LOG_SIZE_MB,
+                },
+            )));
+        }
+        if !(1..=MAX_LOG_FILES).contains(&self.log_max_files) {
+            return Err(BackupError::InvalidSettings(tr(
+                Message::SettingsLogFilesOutOfRange { max: MAX_LOG_FILES },
+            )));
+        }
+        Ok(())
+    }
+
+    /// 按配置的上限截断描述
+    pub fn truncate_description(&self, description: Option<String>) -> Option<String> {
+        description.map(|d| d.chars().take(self.max_description_length).collect())
+    }
+}
+
+/// 命令行参数提供的覆盖项，优先级最高
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SettingsOverrides {
+    /// 替代默认位置的用户配置文件
+    pub config_file: Option<PathBuf>,
+    pub data_dir: Option<PathBuf>,
+    pub data_dir_mode: Option<DataDirMode>,
+    pub log_level: Option<LogLevel>,
+    pub locale: Option<Locale>,
+    pub http_port: Option<u16>,
+}
+
+/// 系统配置文件路径
+#[cfg(windows)]
+pub fn system_config_path() -> Option<PathBuf> {
+    std::env::var_os("ProgramData").map(|dir| {
+        PathBuf::from(dir)
+            .join("MachineID-Manage")
+            .join(CONFIG_FILE_NAME)
+    })
+}
+
+#[cfg(target_os = "macos")]
+pub fn system_config_path() -> Option<PathBuf> {
+    Some(PathBuf::from("/Library/Application Support/MachineID-Manage").join(CONFIG_FILE_NAME))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn system_config_path() -> Option<PathBuf> {
+    Some(PathBuf::from("/etc/machineid-manage").join(CONFIG_FILE_NAME))
+}
+
+#[cfg(not(any(windows, unix)))]
+pub fn system_config_path() -> Option<PathBuf> {
+    None
+}
+
+/// 用户配置文件路径：命令行参数 > `MACHINEID_CONFIG` > 平台默认位置
+pub fn user_config_path(overrides: &SettingsOverrides) -> Option<PathBuf> {
+    overrides
+        .config_file
+        .clone()
+        .or_else(|| std::env::var_os(CONFIG_ENV).map(PathBuf::from))
+        .or_else(default_user_config_path)
+}
+
+#[cfg(windows)]
+fn default_user_config_path() -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(|dir| {
+        PathBuf::from(dir)
+            .join("MachineID-Manage")
+            .join(CONFIG_FILE_NAME)
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn default_user_config_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join("Library/Application Support/MachineID-Manage")
+            .join(CONFIG_FILE_NAME)
+    })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn default_user_config_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("machineid-manage").join(CONFIG_FILE_NAME))
+}
+
+#[cfg(not(any(windows, unix)))]
+fn default_user_config_path() -> Option<PathBuf> {
+    None
+}
+
+/// 按层合并配置并校验
+/// `env` 为 None 时读取进程环境变量，测试时可传入固定的变量表
+fn load_layers(
+    system_file: Option<&Path>,
+    user_file: Option<&Path>,
+    env: Option<Map<String, String>>,
+    overrides: &SettingsOverrides,
+) -> Result<Settings, BackupError> {
+    let invalid = |e: config::ConfigError| BackupError::InvalidSettings(e.to_string());
+
+    let mut builder =
+        Config::builder().add_source(Config::try_from(&Settings::default()).map_err(invalid)?);
+    for path in [system_file, user_file].into_iter().flatten() {
+        builder = builder.add_source(File::from(path).format(FileFormat::Toml).required(false));
+    }
+    let builder = builder
+        .add_source(
+            Environment::with_prefix(ENV_PREFIX)
+                .try_parsing(true)
+                .ignore_empty(true)
+                .source(env),
+        )
+        .set_override_option(
+            "data_dir",
+            overrides
+                .data_dir
+                .as_ref()
+                .map(|dir| dir.to_string_lossy().into_owned()),
+        )
+        .and_then(|b| {
+            b.set_override_option("data_dir_mode", overrides.data_dir_mode.map(|m| m.as_str()))
+        })
+        .and_then(|b| b.set_override_option("log_level", overrides.log_level.map(|l| l.as_str())))
+        .and_then(|b| b.set_override_option("locale", overrides.locale.map(|l| l.as_str())))
+        .and_then(|b| b.set_override_option("http_port", overrides.http_port.map(i64::from)))
+        .map_err(invalid)?;
+
+    let settings: Settings = builder
+        .build()
+        .and_then(|c| c.try_deserialize())
+        .map_err(invalid)?;
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// 从系统、用户配置文件、环境变量和覆盖项加载配置
+pub fn load(overrides: &SettingsOverrides) -> Result<Settings, BackupError> {
+    load_layers(
+        system_config_path().as_deref(),
+        user_config_path(overrides).as_deref(),
+        None,
+        overrides,
+    )
+}
+
+/// 使用命令行覆盖项初始化配置并应用语言设置
+/// 加载失败时回退为默认配置，同时返回错误以便调用方提示
+pub fn init(overrides: SettingsOverrides) -> Result<Settings, BackupError> {
+    *OVERRIDES.write().unwrap_or_else(|e| e.into_inner()) = overrides;
+    reload()
+}
+
+/// 重新读取所有配置层
+pub fn reload() -> Result<Settings, BackupError> {
+    let overrides = OVERRIDES.read().unwrap_or_else(|e| e.into_inner()).clone();
+    let result = load(&overrides);
+    let settings = result.as_ref().cloned().unwrap_or_default();
+    i18n::set_locale(settings.locale);
+    *SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = Some(settings);
+    result
+}
+
+/// 当前生效的配置
+pub fn current() -> Settings {
+    if let Some(settings) = SETTINGS.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return settings.clone();
+    }
+    match reload() {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!("加载配置失败，使用默认配置: {}", e);
+            Settings::default()
+        }
+    }
+}
+
+/// 仅由配置文件决定的配置，不含环境变量和命令行参数
+/// 界面编辑配置时以此为基础，避免把临时的覆盖项写入用户配置文件
+pub fn load_saved() -> Result<Settings, BackupError> {
+    let overrides = OVERRIDES.read().unwrap_or_else(|e| e.into_inner()).clone();
+    load_layers(
+        system_config_path().as_deref(),
+        user_config_path(&overrides).as_deref(),
+        Some(Map::new()),
+        &SettingsOverrides::default(),
+    )
+}
+
+/// 保存用户配置并重新加载，返回合并各层后实际生效的配置
+/// 用户配置文件只记录与默认值和系统配置不同的字段，环境变量和命令行参数仍优先生效
+pub fn update(settings: Settings) -> Result<Settings, BackupError> {
+    settings.validate()?;
+
+    let overrides = OVERRIDES.read().unwrap_or_else(|e| e.into_inner()).clone();
+    let path = user_config_path(&overrides)
+        .ok_or_else(|| BackupError::StorageError(tr(Message::SettingsUserFileUnavailable)))?;
+    let base = load_layers(
+        system_config_path().as_deref(),
+        None,
+        Some(Map::new()),
+        &SettingsOverrides::default(),
+    )
+    .unwrap_or_default();
+
+    save_to(&path, &settings, &base)?;
+    info!("配置已保存: {}", path.display());
+    reload()
+}
+
+/// 写入用户配置文件，省略与 `base` 相同的字段
+fn save_to(path: &Path, settings: &Settings, base: &Settings) -> Result<(), BackupError> {
+    let to_table = |s: &Settings| match toml::Value::try_from(s) {
+        Ok(toml::Value::Table(table)) => Ok(table),
+        Ok(_) => Ok(toml::Table::new()),
+        Err(e) => Err(BackupError::ParseError(e.to_string())),
+    };
+    let base = to_table(base)?;
+    let mut table = to_table(settings)?;
+    table.retain(|key, value| base.get(key) != Some(value));
+
+    let content =
+        toml::to_string_pretty(&table).map_err(|e| BackupError::ParseError(e.to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| BackupError::StorageError(e.to_string()))?;
+    }
+    fs::write(path, content).map_err(|e| BackupError::StorageError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn env(vars: &[(&str, &str)]) -> Option<Map<String, String>> {
+        Some(
+            vars.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_defaults_without_files() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("missing.toml");
+        let settings = load_layers(
+            Some(&missing),
+            Some(&missing),
+            env(&[]),
+            &SettingsOverrides::default(),
+        )
+        .unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_layer_precedence() {
+        let dir = TempDir::new().unwrap();
+        let system = dir.path().join("system.toml");
+        let user = dir.path().join("user.toml");
+        fs::write(
+            &system,
+            "max_description_length = 100\nlog_level = \"debug\"\nhttp_port = 18000\ndata_dir_mode = \"portable\"\n",
+        )
+        .unwrap();
+        fs::write(&user, "max_description_length = 150\nlocale = \"en\"\n").unwrap();
+
+        let data_dir = dir.path().join("data");
+        let overrides = SettingsOverrides {
+            data_dir: Some(data_dir.clone()),
+            ..Default::default()
+        };
+        let settings = load_layers(
+            Some(&system),
+            Some(&user),
+            env(&[
+                ("MACHINEID_LOG_LEVEL", "warn"),
+                ("MACHINEID_DATA_DIR", "/ignored"),
+                ("MACHINEID_DATA_DIR_MODE", "installed"),
+            ]),
+            &overrides,
+        )
+        .unwrap();
+
+        assert_eq!(settings.max_description_length, 150);
+        assert_eq!(settings.log_level, LogLevel::Warn);
+        assert_eq!(settings.http_port, 18000);
+        assert_eq!(settings.locale, Some(Locale::En));
+        assert_eq!(settings.data_dir, Some(data_dir));
+        assert_eq!(settings.data_dir_mode, Some(DataDirMode::Installed));
+    }
+
+    #[test]
+    fn test_env_locale_accepts_system_tags() {
+        let settings = load_layers(
+            None,
+            None,
+            env(&[("MACHINEID_LOCALE", "zh_CN.UTF-8")]),
+            &SettingsOverrides::default(),
+        )
+        .unwrap();
+        assert_eq!(settings.locale, Some(Locale::ZhCn));
+    }
+
+    #[test]
+    fn test_watch_settings_from_env() {
+        let settings = load_layers(
+            None,
+            None,
+            env(&[
+                ("MACHINEID_WATCH_ACTION", "revert"),
+                ("MACHINEID_WATCH_AUTO_BACKUP", "false"),
+                ("MACHINEID_WATCH_INTERVAL_SECS", "30"),
+            ]),
+            &SettingsOverrides::default(),
+        )
+        .unwrap();
+        assert_eq!(settings.watch_action, WatchAction::Revert);
+        assert!(!settings.watch_auto_backup);
+        assert_eq!(settings.watch_interval_secs, 30);
+        assert!(settings.watch_identity);
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        let user = dir.path().join("user.toml");
+
+        fs::write(&user, "log_level = \"verbose\"\n").unwrap();
+        let result = load_layers(None, Some(&user), env(&[]), &SettingsOverrides::default());
+        assert!(matches!(result, Err(BackupError::InvalidSettings(_))));
+
+        fs::write(&user, "max_description_length = 0\n").unwrap();
+        let result = load_layers(None, Some(&user), env(&[]), &SettingsOverrides::default());
+        assert!(matches!(result, Err(BackupError::InvalidSettings(_))));
+
+        let result = load_layers(
+            None,
+            None,
+            env(&[("MACHINEID_HTTP_PORT", "not-a-port")]),
+            &SettingsOverrides::default(),
+        );
+        assert!(matches!(result, Err(BackupError::InvalidSettings(_))));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Settings::default().validate().is_ok());
+
+        let relative = Settings {
+            data_dir: Some(PathBuf::from("relative/dir")),
+            ..Default::default()
+        };
+        assert!(matches!(
+            relative.validate(),
+            Err(BackupError::InvalidSettings(_))
+        ));
+
+        let too_long = Settings {
+            max_description_length: MAX_DESCRIPTION_LENGTH_LIMIT + 1,
+            ..Default::default()
+        };
+        assert!(too_long.validate().is_err());
+
+        let no_port = Settings {
+            http_port: 0,
+            ..Default::default()
+        };
+        assert!(no_port.validate().is_err());
+
+        let no_interval = Settings {
+            watch_interval_secs: 0,
+            ..Default::default()
+        };
+        assert!(no_interval.validate().is_err());
+
+        let huge_log = Settings {
+            log_max_size_mb: MAX_LOG_SIZE_MB + 1,
+            ..Default::default()
+        };
+        assert!(huge_log.validate().is_err());
+
+        let no_log_files = Settings {
+            log_max_files: 0,
+            ..Default::default()
+        };
+        assert!(no_log_files.validate().is_err());
+    }
+
+    #[test]
+    fn test_save_only_writes_changed_fields() {
+        let dir = TempDir::new().unwrap();
+        let user = dir.path().join("nested").join(CONFIG_FILE_NAME);
+        let settings = Settings {
+            max_description_length: 80,
+            locale: Some(Locale::En),
+            ..Default::default()
+        };
+
+        save_to(&user, &settings, &Settings::default()).unwrap();
+        let content = fs::read_to_string(&user).unwrap();
+        assert!(content.contains("max_description_length = 80"));
+        assert!(content.contains("locale = \"en\""));
+        assert!(!content.contains("http_port"));
+        assert!(!content.contains("log_level"));
+
+        let loaded =
+            load_layers(None, Some(&user), env(&[]), &SettingsOverrides::default()).unwrap();
+        assert_eq!(loaded, settings);
+    }
+
+    #[test]
+    fn test_truncate_description() {
+        let settings = Settings {
+            max_description_length: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.truncate_description(Some("描述过长".to_string())),
+            Some("描述过".to_string())
+        );
+        assert_eq!(settings.truncate_description(None), None);
+    }
+}
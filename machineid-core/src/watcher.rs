@@ -0,0 +1,445 @@
+//! 机器码外部修改监视
+//!
+//! 系统更新、其他管理工具或手动编辑都可能在本程序之外改写机器码（Linux `/etc/machine-id`，
+//! Windows 注册表 `MachineGuid`）。Linux 下通过 inotify 监视机器码文件所在目录，收到通知后立即检查；
+//! 其他平台或 inotify 不可用时按配置的 `watch_interval_secs` 轮询。
+//!
+//! 检测到修改后记录日志并发布 `identity-changed`，随后按配置处理：
+//!
+//! - `watch_auto_backup`：自动备份新的机器码
+//! - `watch_action`：`log` 仅记录，`alert` 发布 `identity-alert` 提醒用户，`revert` 写回原来的机器码
+//!
+//! 本进程的写入会提前记录，不会被识别为外部修改；本程序在其他进程中的写入（命令行、守护进程、
+//! 计划任务、自动恢复）通过审计日志中最近一次写入的值识别，只发布 `identity-changed`，不做上述处理。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tracing::{debug, error, info, warn};
+
+use crate::audit;
+use crate::events::{self, ChangeEvent};
+use crate::machine_id::{
+    backup_current_machine_guid_with, read_machine_guid, write_machine_guid_with, AutoDescription,
+    BackupDescription, BackupError, MachineIdBackup,
+};
+use crate::settings::{self, Settings};
+
+/// 使用文件系统通知时，额外全量检查的间隔（秒），用于弥补可能丢失的通知
+const RESYNC_SECS: u64 = 300;
+/// 收到通知后等待写入完成的时间（毫秒）
+const SETTLE_MILLIS: u64 = 200;
+/// 其他进程写入完成后才记入审计日志，未找到记录时等待后再查一次（毫秒）
+const OWN_WRITE_GRACE_MILLIS: u64 = 2000;
+#[cfg(target_os = "linux")]
+use crate::platform::linux::MACHINE_ID_FILES as IDENTITY_FILES;
+
+/// 检测到外部修改后的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(export))]
+pub enum WatchAction {
+    /// 仅记录日志
+    Log,
+    /// 发布 `identity-alert` 事件提醒用户
+    #[default]
+    Alert,
+    /// 写回修改前的机器码，失败时提醒用户
+    Revert,
+}
+
+/// 是否为机器码文件名
+#[cfg(target_os = "linux")]
+fn is_identity_file(name: &std::ffi::OsStr) -> bool {
+    IDENTITY_FILES
+        .iter()
+        .any(|path| std::path::Path::new(path).file_name() == Some(name))
+}
+
+/// 检查和处理修改时用到的操作，测试中替换为内存中的实现
+struct WatchOps<'a> {
+    read: &'a dyn Fn() -> Result<String, BackupError>,
+    /// 记录读取到的值，与上次不同时返回上次的值
+    observe: &'a dyn Fn(&str) -> Option<String>,
+    /// 新的值是否由本程序在其他进程中写入
+    own_write: &'a dyn Fn(&str) -> bool,
+    backup: &'a dyn Fn(AutoDescription) -> Result<Option<MachineIdBackup>, BackupError>,
+    revert: &'a dyn Fn(&str) -> Result<(), BackupError>,
+}
+
+/// 审计日志最近一次写入或恢复的值与 `current` 相同时，视为本程序的写入
+fn written_by_this_app(current: &str) -> bool {
+    audit::last_identity_change_is(current) || {
+        std::thread::sleep(Duration::from_millis(OWN_WRITE_GRACE_MILLIS));
+        audit::last_identity_change_is(current)
+    }
+}
+
+/// 按配置处理一次修改，本程序其他进程的写入只发布事件
+fn handle_change(previous: String, current: String, settings: &Settings, ops: &WatchOps) {
+    events::publish(ChangeEvent::IdentityChanged {
+        previous: Some(previous.clone()),
+        current: current.clone(),
+    });
+    if (ops.own_write)(&current) {
+        info!("机器码由本程序的其他进程修改: {} -> {}", previous, current);
+        return;
+    }
+    warn!("检测到机器码被外部修改: {} -> {}", previous, current);
+
+    if settings.watch_auto_backup {
+        let description = AutoDescription::ExternalChange {
+            previous: previous.clone(),
+            guid: current.clone(),
+        };
+        match (ops.backup)(description) {
+            Ok(Some(backup)) => info!("已自动备份新的机器码: {}", backup.id),
+            Ok(None) => debug!("新的机器码已有备份"),
+            Err(e) => error!("自动备份新的机器码失败: {}", e),
+        }
+    }
+
+    let alert = |error: Option<String>| {
+        events::publish(ChangeEvent::IdentityAlert {
+            previous: previous.clone(),
+            current: current.clone(),
+            action: settings.watch_action,
+            error,
+        })
+    };
+    match settings.watch_action {
+        WatchAction::Log => {}
+        WatchAction::Alert => alert(None),
+        WatchAction::Revert => match (ops.revert)(&previous) {
+            Ok(()) => info!("已恢复被外部修改的机器码: {}", previous),
+            Err(e) => {
+                error!("恢复被外部修改的机器码失败: {}", e);
+                alert(Some(e.to_string()));
+            }
+        },
+    }
+}
+
+/// 读取机器码并与上次记录的比较，监视关闭时只更新记录
+fn check_identity(settings: &Settings, ops: &WatchOps) {
+    let current = match (ops.read)() {
+        // 写入方可能先清空文件再写入
+        Ok(guid) if !guid.is_empty() => guid,
+        Ok(_) => return,
+        Err(e) => {
+            debug!("读取机器码失败: {}", e);
+            return;
+        }
+    };
+    if let Some(previous) = (ops.observe)(&current) {
+        if settings.watch_identity {
+            handle_change(previous, current, settings, ops);
+        }
+    }
+}
+
+/// 使用系统的机器码、备份存储和审计日志检查一次
+fn check_system_identity(settings: &Settings) {
+    check_identity(
+        settings,
+        &WatchOps {
+            read: &|| read_machine_guid().map(|id| id.guid),
+            observe: &events::observe_identity,
+            own_write: &written_by_this_app,
+            backup: &|description| {
+                backup_current_machine_guid_with(Some(BackupDescription::Auto(description)))
+            },
+            revert: &|previous| write_machine_guid_with(previous, None).map(|_| ()),
+        },
+    );
+}
+
+/// 在后台线程中监视机器码文件所在目录，有变化时唤醒 `notify`
+/// 返回的标志表示通知是否可用，监视线程退出时会被清除
+#[cfg(target_os = "linux")]
+fn spawn_file_watch(notify: Arc<Notify>) -> Arc<AtomicBool> {
+    use inotify::{Inotify, WatchMask};
+
+    let active = Arc::new(AtomicBool::new(false));
+    let mut inotify = match Inotify::init() {
+        Ok(inotify) => inotify,
+        Err(e) => {
+            warn!("无法初始化 inotify，改为轮询: {}", e);
+            return active;
+        }
+    };
+    let mask = WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE | WatchMask::DELETE;
+    let mut watched = false;
+    for dir in IDENTITY_FILES
+        .iter()
+        .filter_map(|path| std::path::Path::new(path).parent())
+    {
+        match inotify.watches().add(dir, mask) {
+            Ok(_) => watched = true,
+            Err(e) => debug!("无法监视目录 {}: {}", dir.display(), e),
+        }
+    }
+    if !watched {
+        warn!("无法监视机器码文件，改为轮询");
+        return active;
+    }
+
+    active.store(true, Ordering::Relaxed);
+    let flag = active.clone();
+    let spawned = std::thread::Builder::new()
+        .name("identity-watch".to_string())
+        .spawn(move || {
+            let mut buffer = [0u8; 4096];
+            loop {
+                match inotify.read_events_blocking(&mut buffer) {
+                    Ok(mut events) => {
+                        if events.any(|event| event.name.is_some_and(is_identity_file)) {
+                            notify.notify_one();
+                        }
+                    }
+                    Err(e) => {
+                        warn!("读取 inotify 事件失败，改为轮询: {}", e);
+                        break;
+                    }
+                }
+            }
+            flag.store(false, Ordering::Relaxed);
+            notify.notify_one();
+        });
+    if let Err(e) = spawned {
+        warn!("无法启动机器码监视线程，改为轮询: {}", e);
+        active.store(false, Ordering::Relaxed);
+    }
+    active
+}
+
+#[cfg(not(target_os = "linux"))]
+fn spawn_file_watch(_notify: Arc<Notify>) -> Arc<AtomicBool> {
+    Arc::new(AtomicBool::new(false))
+}
+
+/// 后台监视机器码的外部修改
+/// 首次检查只记录当前机器码；配置在每次检查时重新读取，修改后无需重启
+pub async fn run_identity_watcher() {
+    let notify = Arc::new(Notify::new());
+    let file_watch = spawn_file_watch(notify.clone());
+    if file_watch.load(Ordering::Relaxed) {
+        info!("机器码监视已启动（文件系统通知）");
+    } else {
+        info!("机器码监视已启动（轮询）");
+    }
+
+    loop {
+        let settings = settings::current();
+        let wait = if file_watch.load(Ordering::Relaxed) {
+            RESYNC_SECS
+        } else {
+            settings.watch_interval_secs
+        };
+        if let Err(e) = tokio::task::spawn_blocking(move || check_system_identity(&settings)).await
+        {
+            error!("机器码检查线程异常: {}", e);
+        }
+        if tokio::time::timeout(Duration::from_secs(wait), notify.notified())
+            .await
+            .is_ok()
+        {
+            tokio::time::sleep(Duration::from_millis(SETTLE_MILLIS)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// 记录测试中各操作的调用
+    #[derive(Default)]
+    struct Calls {
+        backups: RefCell<Vec<AutoDescription>>,
+        reverts: RefCell<Vec<String>>,
+    }
+
+    fn settings_with(action: WatchAction, auto_backup: bool) -> Settings {
+        Settings {
+            watch_identity: true,
+            watch_auto_backup: auto_backup,
+            watch_action: action,
+            ..Settings::default()
+        }
+    }
+
+    /// 以 `previous` -> `current` 的修改运行一次检查，返回调用记录和发布的事件
+    fn run_check(
+        previous: Option<&str>,
+        current: &str,
+        settings: &Settings,
+        own_write: bool,
+        revert_result: fn() -> Result<(), BackupError>,
+    ) -> (Calls, Vec<ChangeEvent>) {
+        let calls = Calls::default();
+        let mut receiver = events::subscribe();
+        check_identity(
+            settings,
+            &WatchOps {
+                read: &|| Ok(current.to_string()),
+                observe: &|_| previous.map(str::to_string),
+                own_write: &|_| own_write,
+                backup: &|description| {
+                    calls.backups.borrow_mut().push(description);
+                    Ok(None)
+                },
+                revert: &|guid| {
+                    calls.reverts.borrow_mut().push(guid.to_string());
+                    revert_result()
+                },
+            },
+        );
+        // 其他测试可能同时发布事件，只保留与本次修改相关的
+        let published = std::iter::from_fn(|| receiver.try_recv().ok())
+            .filter(|event| match event {
+                ChangeEvent::IdentityChanged { current: c, .. } => c == current,
+                ChangeEvent::IdentityAlert { current: c, .. } => c == current,
+                _ => false,
+            })
+            .collect();
+        (calls, published)
+    }
+
+    fn alerts(events: &[ChangeEvent]) -> Vec<Option<String>> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                ChangeEvent::IdentityAlert { error, .. } => Some(error.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_check_identity_handles_only_changes() {
+        let settings = settings_with(WatchAction::Revert, true);
+        // 首次读取或值未变化
+        let (calls, events) = run_check(None, "watch-unchanged", &settings, false, || Ok(()));
+        assert!(calls.backups.borrow().is_empty());
+        assert!(calls.reverts.borrow().is_empty());
+        assert!(events.is_empty());
+
+        // 监视关闭时只更新记录
+        let disabled = Settings {
+            watch_identity: false,
+            ..settings
+        };
+        let (calls, events) = run_check(Some("a"), "watch-disabled", &disabled, false, || Ok(()));
+        assert!(calls.reverts.borrow().is_empty());
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_handle_change_log_and_alert() {
+        let (calls, events) = run_check(
+            Some("watch-log-old"),
+            "watch-log-new",
+            &settings_with(WatchAction::Log, false),
+            false,
+            || Ok(()),
+        );
+        assert!(calls.backups.borrow().is_empty());
+        assert!(calls.reverts.borrow().is_empty());
+        assert_eq!(
+            events,
+            vec![ChangeEvent::IdentityChanged {
+                previous: Some("watch-log-old".to_string()),
+                current: "watch-log-new".to_string(),
+            }]
+        );
+
+        let (calls, events) = run_check(
+            Some("watch-alert-old"),
+            "watch-alert-new",
+            &settings_with(WatchAction::Alert, true),
+            false,
+            || Ok(()),
+        );
+        assert_eq!(
+            *calls.backups.borrow(),
+            vec![AutoDescription::ExternalChange {
+                previous: "watch-alert-old".to_string(),
+                guid: "watch-alert-new".to_string(),
+            }]
+        );
+        assert!(calls.reverts.borrow().is_empty());
+        assert_eq!(alerts(&events), vec![None]);
+    }
+
+    #[test]
+    fn test_handle_change_revert() {
+        let settings = settings_with(WatchAction::Revert, false);
+        let (calls, events) = run_check(
+            Some("watch-revert-old"),
+            "watch-revert-new",
+            &settings,
+            false,
+            || Ok(()),
+        );
+        assert_eq!(
+            *calls.reverts.borrow(),
+            vec!["watch-revert-old".to_string()]
+        );
+        assert!(alerts(&events).is_empty());
+
+        // 写回失败时提醒用户
+        let (calls, events) = run_check(
+            Some("watch-failed-old"),
+            "watch-failed-new",
+            &settings,
+            false,
+            || Err(BackupError::InsufficientPermissions),
+        );
+        assert_eq!(calls.reverts.borrow().len(), 1);
+        let alerts = alerts(&events);
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].is_some());
+    }
+
+    #[test]
+    fn test_own_writes_are_not_reverted() {
+        let (calls, events) = run_check(
+            Some("watch-own-old"),
+            "watch-own-new",
+            &settings_with(WatchAction::Revert, true),
+            true,
+            || Ok(()),
+        );
+        assert!(calls.backups.borrow().is_empty());
+        assert!(calls.reverts.borrow().is_empty());
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], ChangeEvent::IdentityChanged { .. }));
+    }
+
+    #[test]
+    fn test_watch_action_names() {
+        for (action, name) in [
+            (WatchAction::Log, "\"log\""),
+            (WatchAction::Alert, "\"alert\""),
+            (WatchAction::Revert, "\"revert\""),
+        ] {
+            assert_eq!(serde_json::to_string(&action).unwrap(), name);
+            assert_eq!(serde_json::from_str::<WatchAction>(name).unwrap(), action);
+        }
+        assert_eq!(WatchAction::default(), WatchAction::Alert);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_is_identity_file() {
+        assert!(is_identity_file(std::ffi::OsStr::new("machine-id")));
+        assert!(!is_identity_file(std::ffi::OsStr::new("machine-id.tmp")));
+        assert!(!is_identity_file(std::ffi::OsStr::new("hostname")));
+    }
+}
@@ -0,0 +1,76 @@
+//! 以外部依赖方的视角验证 machineid-core 的公开 API
+
+use machineid_core::machine_id::{
+    clear_all_backups, generate_random_guid, get_backup_count, list_backups, validate_guid_format,
+    BackupStore, GuidHistory,
+};
+use machineid_core::preview_token::PreviewTokenIssuer;
+use machineid_core::scheduler::CronExpr;
+use machineid_core::{BackupError, MachineIdBackup};
+use tempfile::TempDir;
+
+#[test]
+fn backup_store_is_usable_from_outside_the_crate() {
+    let mut store = BackupStore::default();
+    assert!(store.is_empty());
+
+    store.add_backup(MachineIdBackup {
+        id: "backup_1".to_string(),
+        guid: "550E8400-E29B-41D4-A716-446655440000".to_string(),
+        source: "test".to_string(),
+        timestamp: 1234567890,
+        description: None,
+        auto_description: None,
+    });
+    assert_eq!(store.len(), 1);
+    assert!(store.has_guid("550E8400-E29B-41D4-A716-446655440000"));
+    assert!(matches!(
+        store.remove_backup("missing"),
+        Err(BackupError::BackupNotFound(_))
+    ));
+}
+
+#[test]
+fn generators_produce_valid_unique_guids() {
+    let guid = generate_random_guid().unwrap();
+    assert!(validate_guid_format(&guid).is_ok());
+
+    let mut history = GuidHistory::default();
+    history.insert(&guid);
+    let unique = history.generate_unique().unwrap();
+    assert_ne!(unique.guid.to_lowercase(), guid.to_lowercase());
+    assert!(validate_guid_format(&unique.guid).is_ok());
+}
+
+#[test]
+fn preview_tokens_round_trip() {
+    let issuer = PreviewTokenIssuer::new();
+    let token = issuer.issue("550e8400-e29b-41d4-a716-446655440000", 1000);
+    assert_eq!(
+        issuer.redeem(&token.token, 1001).unwrap(),
+        "550e8400-e29b-41d4-a716-446655440000"
+    );
+}
+
+#[test]
+fn cron_expressions_are_parsed() {
+    assert!(CronExpr::parse("0 */6 * * *").is_ok());
+    assert!(matches!(
+        CronExpr::parse("every six hours"),
+        Err(BackupError::InvalidSchedule(_))
+    ));
+}
+
+#[test]
+fn backup_storage_honours_test_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("backups.json");
+    std::env::set_var("BACKUP_TEST_PATH", &path);
+
+    clear_all_backups().unwrap();
+    assert!(path.exists());
+    assert_eq!(get_backup_count().unwrap(), 0);
+    assert!(list_backups().unwrap().is_empty());
+
+    std::env::remove_var("BACKUP_TEST_PATH");
+}
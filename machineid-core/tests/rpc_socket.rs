@@ -0,0 +1,94 @@
+//! 通过临时 Unix 套接字驱动 JSON-RPC 服务
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::time::Duration;
+
+use machineid_core::rpc::{serve, RpcResponse, INVALID_PARAMS, METHOD_NOT_FOUND};
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+async fn start_server(path: &Path) {
+    let owned = path.to_path_buf();
+    tokio::spawn(async move { serve(&owned).await });
+    for _ in 0..100 {
+        if UnixStream::connect(path).await.is_ok() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("RPC 服务未能启动");
+}
+
+/// 在同一连接上依次发送请求并读取响应
+async fn call_all(path: &Path, requests: &[serde_json::Value]) -> Vec<RpcResponse> {
+    let stream = UnixStream::connect(path).await.unwrap();
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let mut responses = Vec::new();
+    for request in requests {
+        writer
+            .write_all(format!("{}\n", request).as_bytes())
+            .await
+            .unwrap();
+        let line = lines.next_line().await.unwrap().expect("连接被提前关闭");
+        responses.push(serde_json::from_str(&line).unwrap());
+    }
+    responses
+}
+
+#[tokio::test]
+async fn serves_requests_over_socket() {
+    let temp_dir = TempDir::new().unwrap();
+    std::env::set_var("BACKUP_TEST_PATH", temp_dir.path().join("backups.json"));
+    let socket = temp_dir.path().join("rpc.sock");
+    start_server(&socket).await;
+
+    let mode = std::fs::metadata(&socket).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    // 创建套接字用的临时目录已删除
+    let names: Vec<_> = std::fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert!(!names
+        .iter()
+        .any(|name| name.to_string_lossy().starts_with(".rpc-")));
+
+    let responses = call_all(
+        &socket,
+        &[
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "list_backups" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "check_permission" }),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "no_such_method" }),
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "restore_backup", "params": {} }),
+        ],
+    )
+    .await;
+
+    assert_eq!(responses[0].id, json!(1));
+    assert_eq!(responses[0].result, Some(json!([])));
+    assert!(responses[1].result.as_ref().unwrap()["has_permission"].is_boolean());
+    assert_eq!(responses[2].error.as_ref().unwrap().code, METHOD_NOT_FOUND);
+    assert_eq!(responses[3].error.as_ref().unwrap().code, INVALID_PARAMS);
+
+    std::env::remove_var("BACKUP_TEST_PATH");
+}
+
+#[tokio::test]
+async fn refuses_socket_in_use_and_replaces_stale_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket = temp_dir.path().join("rpc.sock");
+
+    // 残留的普通文件会被替换
+    std::fs::write(&socket, b"stale").unwrap();
+    start_server(&socket).await;
+
+    // 已有服务监听时第二个实例必须失败
+    assert!(serve(&socket).await.is_err());
+    assert!(UnixStream::connect(&socket).await.is_ok());
+}
@@ -0,0 +1,25 @@
+//! MachineID-Manage 特权写入辅助程序
+//!
+//! 由图形界面或命令行通过 `pkexec` 以 root 身份启动，从标准输入读取一个请求，
+//! 执行后将结果写入标准输出。请求协议见 `machineid_core::helper`。
+
+use std::process::ExitCode;
+
+#[cfg(target_os = "linux")]
+fn main() -> ExitCode {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    match machineid_core::helper::serve(stdin.lock(), stdout.lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("machineid-helper: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() -> ExitCode {
+    eprintln!("machineid-helper: only supported on Linux");
+    ExitCode::FAILURE
+}
@@ -0,0 +1,729 @@
+//! 命令行模式
+//!
+//! 以子命令启动时不初始化任何窗口，直接调用 machineid-core 完成操作，
+//! 便于通过 SSH 或部署流水线脚本化使用。
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use machineid_core::api::ErrorCode;
+use machineid_core::audit::verify_audit_log;
+use machineid_core::data_dir::{self, DataDirMode};
+use machineid_core::diagnostics::export_diagnostics;
+use machineid_core::i18n::{tr, Locale, Message};
+use machineid_core::machine_id::{
+    backup_current_machine_guid, delete_backup, generate_random_machine_guid, list_backups,
+    normalize_identity, read_machine_guid_audited, restore_backup_by_id, validate_identity_value,
+    write_machine_guid, BackupError,
+};
+use machineid_core::platform::permissions::check_admin_permissions;
+use machineid_core::settings::{self, LogLevel, SettingsOverrides};
+use machineid_core::{revert, rpc, scheduler, watcher};
+
+use crate::http_api;
+use crate::{init_logging, LogConsole};
+
+/// 成功
+pub const EXIT_OK: i32 = 0;
+/// 命令行参数错误
+pub const EXIT_USAGE: i32 = 2;
+/// verify 子命令发现当前值与期望值不一致
+pub const EXIT_VERIFY_MISMATCH: i32 = 3;
+/// audit-verify 子命令发现审计日志的哈希链断裂
+pub const EXIT_AUDIT_TAMPERED: i32 = 4;
+
+/// 所有子命令名称，用于判断是否以命令行模式启动
+const SUBCOMMANDS: &[&str] = &[
+    "show",
+    "backup",
+    "list",
+    "restore",
+    "set",
+    "generate",
+    "delete",
+    "verify",
+    "audit-verify",
+    "diagnostics",
+    "data-dir",
+    "migrate-data",
+    "daemon",
+    "http",
+    "help",
+    "--help",
+    "-h",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum CliCommand {
+    Show,
+    Backup {
+        description: Option<String>,
+    },
+    List,
+    Restore {
+        id: String,
+    },
+    Set {
+        guid: String,
+        description: Option<String>,
+    },
+    Generate {
+        description: Option<String>,
+    },
+    Delete {
+        id: String,
+    },
+    Verify {
+        expected: Option<String>,
+    },
+    AuditVerify,
+    Diagnostics {
+        path: Option<PathBuf>,
+        include_identifiers: bool,
+    },
+    DataDir,
+    MigrateData {
+        mode: DataDirMode,
+    },
+    Daemon {
+        socket: Option<String>,
+    },
+    Http {
+        /// 未指定时使用配置中的端口
+        port: Option<u16>,
+    },
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CliArgs {
+    command: CliCommand,
+    json: bool,
+    /// 全局选项对应的配置覆盖项
+    overrides: SettingsOverrides,
+}
+
+/// 每个 BackupError 变体对应的稳定退出码
+/// 新增变体时必须在此分配新的退出码，已分配的值不得修改
+pub fn exit_code_for(error: &BackupError) -> i32 {
+    match error {
+        BackupError::RegistryError(_) => 10,
+        BackupError::RegistryWriteError(_) => 11,
+        BackupError::NotFound => 12,
+        BackupError::ParseError(_) => 13,
+        BackupError::StorageError(_) => 14,
+        BackupError::BackupNotFound(_) => 15,
+        BackupError::InvalidGuidFormat(_) => 16,
+        BackupError::InsufficientPermissions => 17,
+        BackupError::UnsupportedPlatform => 18,
+        BackupError::GuidGenerationExhausted(_) => 19,
+        BackupError::InvalidPreviewToken(_) => 20,
+        BackupError::InvalidSchedule(_) => 21,
+        BackupError::ScheduleNotFound(_) => 22,
+        BackupError::ProfileNotFound(_) => 23,
+        BackupError::ProfileAlreadyExists(_) => 24,
+        BackupError::InvalidProfile(_) => 25,
+        BackupError::PendingRevertNotFound(_) => 26,
+        BackupError::InvalidRevertDuration(_) => 27,
+        BackupError::InvalidSettings(_) => 28,
+        BackupError::DataDirConflict(_) => 29,
+        BackupError::HelperFailed(_) => 30,
+        BackupError::InvalidExport(_) => 31,
+        BackupError::AuditLogFailed(_) => 32,
+        BackupError::GuidAlreadyUsed(_) => 33,
+        BackupError::DataDirBusy(_) => 34,
+    }
+}
+
+/// 判断启动参数是否为命令行模式
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    args.get(1)
+        .map(|arg| SUBCOMMANDS.contains(&arg.as_str()))
+        .unwrap_or(false)
+}
+
+fn parse_args(args: &[String]) -> Result<CliArgs, String> {
+    let mut json = false;
+    let mut help = false;
+    let mut description = None;
+    let mut socket = None;
+    let mut port = None;
+    let mut include_identifiers = false;
+    let mut overrides = SettingsOverrides::default();
+    let mut positional = Vec::new();
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--include-identifiers" => include_identifiers = true,
+            "--description" | "-d" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| tr(Message::CliOptionNeedsValue { option: arg }))?;
+                description = Some(value.clone());
+            }
+            "--socket" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| tr(Message::CliOptionNeedsValue { option: arg }))?;
+                socket = Some(value.clone());
+            }
+            "--port" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| tr(Message::CliOptionNeedsValue { option: arg }))?;
+                let parsed = value
+                    .parse::<u16>()
+                    .map_err(|_| tr(Message::CliInvalidPort { value }))?;
+                port = Some(parsed);
+            }
+            "--config" | "--data-dir" | "--data-dir-mode" | "--log-level" | "--locale" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| tr(Message::CliOptionNeedsValue { option: arg }))?;
+                let invalid = || tr(Message::CliInvalidOptionValue { option: arg, value });
+                match arg.as_str() {
+                    "--config" => overrides.config_file = Some(PathBuf::from(value)),
+                    "--data-dir" => overrides.data_dir = Some(PathBuf::from(value)),
+                    "--data-dir-mode" => {
+                        overrides.data_dir_mode =
+                            Some(DataDirMode::from_name(value).ok_or_else(invalid)?)
+                    }
+                    "--log-level" => {
+                        overrides.log_level = Some(LogLevel::from_name(value).ok_or_else(invalid)?)
+                    }
+                    _ => overrides.locale = Some(Locale::from_tag(value).ok_or_else(invalid)?),
+                }
+            }
+            "-h" | "--help" => help = true,
+            other if other.starts_with('-') => {
+                return Err(tr(Message::CliUnknownOption { option: other }))
+            }
+            other => positional.push(other.to_string()),
+        }
+    }
+
+    if help {
+        return Ok(CliArgs {
+            command: CliCommand::Help,
+            json,
+            overrides,
+        });
+    }
+
+    let mut positional = positional.into_iter();
+    let subcommand = positional
+        .next()
+        .ok_or_else(|| tr(Message::CliMissingSubcommand))?;
+    let missing = |name: &str| {
+        tr(Message::CliMissingArgument {
+            subcommand: &subcommand,
+            name,
+        })
+    };
+
+    let command = match subcommand.as_str() {
+        "show" => CliCommand::Show,
+        "backup" => CliCommand::Backup { description },
+        "list" => CliCommand::List,
+        "restore" => CliCommand::Restore {
+            id: positional
+                .next()
+                .ok_or_else(|| missing(&tr(Message::CliBackupIdArg)))?,
+        },
+        "set" => CliCommand::Set {
+            guid: positional.next().ok_or_else(|| missing("GUID"))?,
+            description,
+        },
+        "generate" => CliCommand::Generate { description },
+        "delete" => CliCommand::Delete {
+            id: positional
+                .next()
+                .ok_or_else(|| missing(&tr(Message::CliBackupIdArg)))?,
+        },
+        "verify" => CliCommand::Verify {
+            expected: positional.next(),
+        },
+        "audit-verify" => CliCommand::AuditVerify,
+        "diagnostics" => CliCommand::Diagnostics {
+            path: positional.next().map(PathBuf::from),
+            include_identifiers,
+        },
+        "data-dir" => CliCommand::DataDir,
+        "migrate-data" => {
+            let value = positional
+                .next()
+                .ok_or_else(|| missing(&tr(Message::CliDataDirModeArg)))?;
+            CliCommand::MigrateData {
+                mode: DataDirMode::from_name(&value).ok_or_else(|| {
+                    tr(Message::CliInvalidOptionValue {
+                        option: "migrate-data",
+                        value: &value,
+                    })
+                })?,
+            }
+        }
+        "daemon" => CliCommand::Daemon { socket },
+        "http" => CliCommand::Http { port },
+        "help" => CliCommand::Help,
+        other => return Err(tr(Message::CliUnknownSubcommand { name: other })),
+    };
+
+    if let Some(extra) = positional.next() {
+        return Err(tr(Message::CliExtraArgument { value: &extra }));
+    }
+
+    Ok(CliArgs {
+        command,
+        json,
+        overrides,
+    })
+}
+
+fn to_value<T: Serialize>(value: T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// 写操作前检查管理员权限，与图形界面的服务端校验保持一致
+fn require_admin() -> Result<(), BackupError> {
+    if check_admin_permissions().has_permission {
+        Ok(())
+    } else {
+        Err(BackupError::InsufficientPermissions)
+    }
+}
+
+/// 执行子命令，返回 (退出码, JSON 数据, 文本输出)
+fn execute(command: &CliCommand) -> Result<(i32, Value, String), BackupError> {
+    match command {
+        CliCommand::Show => {
+            let machine_id = read_machine_guid_audited()?;
+            let text = format!("{}\t{}", machine_id.guid, machine_id.source);
+            Ok((
+                EXIT_OK,
+                json!({ "guid": machine_id.guid, "source": machine_id.source }),
+                text,
+            ))
+        }
+        CliCommand::Backup { description } => {
+            let backup = backup_current_machine_guid(description.clone())?;
+            let text = match &backup {
+                Some(b) => tr(Message::CliBackedUp {
+                    id: &b.id,
+                    guid: &b.guid,
+                }),
+                None => tr(Message::CliBackupSkipped),
+            };
+            Ok((
+                EXIT_OK,
+                json!({ "skipped": backup.is_none(), "backup": backup }),
+                text,
+            ))
+        }
+        CliCommand::List => {
+            let backups = list_backups()?;
+            let text = backups
+                .iter()
+                .map(|b| {
+                    format!(
+                        "{}\t{}\t{}\t{}",
+                        b.id,
+                        b.guid,
+                        b.timestamp,
+                        b.description.as_deref().unwrap_or("")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Ok((EXIT_OK, to_value(&backups), text))
+        }
+        CliCommand::Restore { id } => {
+            require_admin()?;
+            let info = restore_backup_by_id(id)?;
+            let text = tr(Message::CliRestored {
+                from: &info.previous_guid,
+                to: &info.restored_guid,
+            });
+            Ok((EXIT_OK, to_value(&info), text))
+        }
+        CliCommand::Set { guid, description } => {
+            require_admin()?;
+            let result = write_machine_guid(guid, description.clone())?;
+            let text = tr(Message::CliWritten {
+                from: &result.previous_guid,
+                to: &result.new_guid,
+            });
+            Ok((EXIT_OK, to_value(&result), text))
+        }
+        CliCommand::Generate { description } => {
+            require_admin()?;
+            let result = generate_random_machine_guid(description.clone())?;
+            let text = tr(Message::CliGenerated {
+                from: &result.write.previous_guid,
+                to: &result.write.new_guid,
+            });
+            Ok((EXIT_OK, to_value(&result), text))
+        }
+        CliCommand::Delete { id } => {
+            delete_backup(id)?;
+            Ok((EXIT_OK, json!({ "id": id }), tr(Message::CliDeleted { id })))
+        }
+        CliCommand::Verify { expected } => {
+            let machine_id = read_machine_guid_audited()?;
+            validate_identity_value(&machine_id.guid)?;
+            let matches = expected
+                .as_ref()
+                .map(|e| normalize_identity(e) == normalize_identity(&machine_id.guid));
+            let (code, text) = match matches {
+                Some(false) => (
+                    EXIT_VERIFY_MISMATCH,
+                    tr(Message::CliVerifyMismatch {
+                        current: &machine_id.guid,
+                    }),
+                ),
+                _ => (
+                    EXIT_OK,
+                    tr(Message::CliVerifyOk {
+                        guid: &machine_id.guid,
+                    }),
+                ),
+            };
+            Ok((
+                code,
+                json!({ "guid": machine_id.guid, "valid": true, "matches": matches }),
+                text,
+            ))
+        }
+        CliCommand::AuditVerify => {
+            let verification = verify_audit_log()?;
+            let (code, text) = match verification.broken_at {
+                Some(line) => (
+                    EXIT_AUDIT_TAMPERED,
+                    tr(Message::CliAuditTampered {
+                        line,
+                        verified: verification.entries,
+                    }),
+                ),
+                None => (
+                    EXIT_OK,
+                    tr(Message::CliAuditVerified {
+                        entries: verification.entries,
+                    }),
+                ),
+            };
+            Ok((code, to_value(&verification), text))
+        }
+        CliCommand::Diagnostics {
+            path,
+            include_identifiers,
+        } => {
+            let export = export_diagnostics(path.as_deref(), *include_identifiers)?;
+            let text = tr(Message::CliDiagnosticsExported {
+                path: &export.path.display().to_string(),
+                redacted: !export.include_identifiers,
+            });
+            Ok((EXIT_OK, to_value(&export), text))
+        }
+        CliCommand::DataDir => {
+            let info = data_dir::resolve(&settings::current())?;
+            let text = tr(Message::CliDataDir {
+                path: &info.path.display().to_string(),
+                mode: info.mode.map(|m| m.as_str()).unwrap_or("custom"),
+            });
+            Ok((EXIT_OK, to_value(&info), text))
+        }
+        CliCommand::MigrateData { mode } => {
+            let migration = data_dir::migrate_data_dir(*mode)?;
+            let text = tr(Message::CliDataMigrated {
+                from: &migration.from.display().to_string(),
+                to: &migration.to.display().to_string(),
+                count: migration.moved.len(),
+            });
+            Ok((EXIT_OK, to_value(&migration), text))
+        }
+        CliCommand::Daemon { socket } => {
+            // 常驻模式需要日志记录监视和请求处理的过程，一次性命令只输出结果
+            init_logging(&settings::current(), LogConsole::Stderr);
+            let path = match socket {
+                Some(socket) => PathBuf::from(socket),
+                None => rpc::default_socket_path()?,
+            };
+            eprintln!(
+                "{}",
+                tr(Message::CliRpcListening {
+                    path: &path.display().to_string(),
+                })
+            );
+            // 正常情况下不会返回，只有监听失败时才会退出
+            tauri::async_runtime::block_on(async {
+                tauri::async_runtime::spawn(watcher::run_identity_watcher());
+                // 图形界面退出后，已登记的临时写入仍需按时恢复
+                tauri::async_runtime::spawn(revert::run_revert_timer());
+                tauri::async_runtime::spawn(scheduler::run_scheduler());
+                rpc::serve(&path).await
+            })?;
+            Ok((EXIT_OK, Value::Null, String::new()))
+        }
+        CliCommand::Http { port } => {
+            init_logging(&settings::current(), LogConsole::Stderr);
+            let port = port.unwrap_or_else(|| settings::current().http_port);
+            eprintln!("{}", tr(Message::CliHttpListening { port }));
+            tauri::async_runtime::block_on(async {
+                tauri::async_runtime::spawn(revert::run_revert_timer());
+                http_api::serve(port).await
+            })?;
+            Ok((EXIT_OK, Value::Null, String::new()))
+        }
+        CliCommand::Help => {
+            let usage = tr(Message::CliUsage);
+            Ok((EXIT_OK, json!({ "usage": usage }), usage))
+        }
+    }
+}
+
+/// Windows 发布版本使用 GUI 子系统，需要附加到父进程的控制台才能输出
+#[cfg(windows)]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+/// 以命令行模式运行并返回进程退出码
+pub fn run(args: &[String]) -> i32 {
+    attach_console();
+
+    let parsed = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            let json = args.iter().any(|a| a == "--json");
+            if json {
+                println!(
+                    "{}",
+                    json!({ "success": false, "error": { "code": "usage", "message": message } })
+                );
+            } else {
+                eprintln!("{}\n\n{}", message, tr(Message::CliUsage));
+            }
+            return EXIT_USAGE;
+        }
+    };
+
+    let result = settings::init(parsed.overrides.clone()).and_then(|_| execute(&parsed.command));
+    match result {
+        Ok((code, data, text)) => {
+            if parsed.json {
+                println!("{}", json!({ "success": code == EXIT_OK, "data": data }));
+            } else if !text.is_empty() {
+                println!("{}", text);
+            }
+            code
+        }
+        Err(e) => {
+            let code = exit_code_for(&e);
+            if parsed.json {
+                println!(
+                    "{}",
+                    json!({
+                        "success": false,
+                        "error": { "code": ErrorCode::from(&e).as_str(), "exit_code": code, "message": e.to_string() }
+                    })
+                );
+            } else {
+                eprintln!(
+                    "{}",
+                    tr(Message::CliError {
+                        message: &e.to_string(),
+                    })
+                );
+            }
+            code
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("machineid-manage")
+            .chain(list.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_is_cli_invocation() {
+        assert!(is_cli_invocation(&args(&["show"])));
+        assert!(is_cli_invocation(&args(&["--help"])));
+        assert!(!is_cli_invocation(&args(&[])));
+        assert!(!is_cli_invocation(&args(&["--restore", "backup_1"])));
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(&args(&["show", "--json"])).unwrap(),
+            CliArgs {
+                command: CliCommand::Show,
+                json: true,
+                overrides: SettingsOverrides::default(),
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&[
+                "set",
+                "550E8400-E29B-41D4-A716-446655440000",
+                "-d",
+                "测试"
+            ]))
+            .unwrap()
+            .command,
+            CliCommand::Set {
+                guid: "550E8400-E29B-41D4-A716-446655440000".to_string(),
+                description: Some("测试".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["verify"])).unwrap().command,
+            CliCommand::Verify { expected: None }
+        );
+        assert_eq!(
+            parse_args(&args(&["daemon", "--socket", "/tmp/mid.sock"]))
+                .unwrap()
+                .command,
+            CliCommand::Daemon {
+                socket: Some("/tmp/mid.sock".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["audit-verify"])).unwrap().command,
+            CliCommand::AuditVerify
+        );
+        assert_eq!(
+            parse_args(&args(&["diagnostics"])).unwrap().command,
+            CliCommand::Diagnostics {
+                path: None,
+                include_identifiers: false,
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["diagnostics", "out.zip", "--include-identifiers"]))
+                .unwrap()
+                .command,
+            CliCommand::Diagnostics {
+                path: Some(PathBuf::from("out.zip")),
+                include_identifiers: true,
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["http"])).unwrap().command,
+            CliCommand::Http { port: None }
+        );
+        assert_eq!(
+            parse_args(&args(&["http", "--port", "9000"]))
+                .unwrap()
+                .command,
+            CliCommand::Http { port: Some(9000) }
+        );
+        assert_eq!(
+            parse_args(&args(&["migrate-data", "installed"]))
+                .unwrap()
+                .command,
+            CliCommand::MigrateData {
+                mode: DataDirMode::Installed,
+            }
+        );
+        assert_eq!(
+            parse_args(&args(&["list", "--help"])).unwrap().command,
+            CliCommand::Help
+        );
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["restore"])).is_err());
+        assert!(parse_args(&args(&["delete", "a", "b"])).is_err());
+        assert!(parse_args(&args(&["show", "--unknown"])).is_err());
+        assert!(parse_args(&args(&["backup", "--description"])).is_err());
+        assert!(parse_args(&args(&["frobnicate"])).is_err());
+        assert!(parse_args(&args(&["http", "--port", "70000"])).is_err());
+        assert!(parse_args(&args(&["show", "--log-level", "verbose"])).is_err());
+        assert!(parse_args(&args(&["show", "--data-dir"])).is_err());
+        assert!(parse_args(&args(&["migrate-data"])).is_err());
+        assert!(parse_args(&args(&["migrate-data", "roaming"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_settings_overrides() {
+        let parsed = parse_args(&args(&[
+            "list",
+            "--config",
+            "/etc/custom.toml",
+            "--data-dir",
+            "/srv/machineid",
+            "--log-level",
+            "DEBUG",
+            "--locale",
+            "en-US",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.command, CliCommand::List);
+        assert_eq!(
+            parsed.overrides,
+            SettingsOverrides {
+                config_file: Some(PathBuf::from("/etc/custom.toml")),
+                data_dir: Some(PathBuf::from("/srv/machineid")),
+                data_dir_mode: None,
+                log_level: Some(LogLevel::Debug),
+                locale: Some(Locale::En),
+                http_port: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let errors = vec![
+            BackupError::RegistryError(String::new()),
+            BackupError::RegistryWriteError(String::new()),
+            BackupError::NotFound,
+            BackupError::ParseError(String::new()),
+            BackupError::StorageError(String::new()),
+            BackupError::BackupNotFound(String::new()),
+            BackupError::InvalidGuidFormat(String::new()),
+            BackupError::InsufficientPermissions,
+            BackupError::UnsupportedPlatform,
+            BackupError::GuidGenerationExhausted(0),
+            BackupError::InvalidPreviewToken(String::new()),
+            BackupError::InvalidSchedule(String::new()),
+            BackupError::ScheduleNotFound(String::new()),
+            BackupError::ProfileNotFound(String::new()),
+            BackupError::ProfileAlreadyExists(String::new()),
+            BackupError::InvalidProfile(String::new()),
+            BackupError::PendingRevertNotFound(String::new()),
+            BackupError::InvalidRevertDuration(0),
+            BackupError::InvalidSettings(String::new()),
+            BackupError::DataDirConflict(String::new()),
+            BackupError::HelperFailed(String::new()),
+            BackupError::InvalidExport(String::new()),
+            BackupError::AuditLogFailed(String::new()),
+            BackupError::GuidAlreadyUsed(String::new()),
+            BackupError::DataDirBusy(String::new()),
+        ];
+        let codes: std::collections::HashSet<i32> = errors.iter().map(exit_code_for).collect();
+        assert_eq!(codes.len(), errors.len());
+        for code in &codes {
+            assert!(![EXIT_OK, EXIT_USAGE, EXIT_VERIFY_MISMATCH].contains(code));
+        }
+    }
+}
@@ -0,0 +1,649 @@
+//! 本地 HTTP REST 接口
+//!
+//! 仅监听 `127.0.0.1`，供本机代理或仪表盘调用备份与机器码操作。
+//! 除 `GET /api/openapi.json` 外，所有请求都必须携带 `Authorization: Bearer <令牌>`，
+//! 令牌保存在应用数据目录的 `api_token` 文件中。
+//!
+//! 各接口直接复用图形界面的命令函数，响应体与前端收到的 [`ApiResponse`] 完全一致，
+//! HTTP 状态码由错误码决定。
+
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use machineid_core::api::{ApiError, ApiResponse, ErrorCode};
+use machineid_core::api_token::{bearer_matches, load_or_create_api_token};
+use machineid_core::audit::{AuditEntry, AuditQuery, AuditVerification};
+use machineid_core::i18n::{tr, Message};
+use machineid_core::machine_id::BackupError;
+use machineid_core::platform::capabilities::CapabilityReport;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{error, info, warn};
+
+use crate::{
+    backup_machine_guid, check_capabilities_command, check_permission_command, delete_backup_by_id,
+    generate_random_guid_command, list_backups, preview_random_guid_command,
+    query_audit_log_command, read_machine_id, restore_backup_by_id_command,
+    verify_audit_log_command, write_machine_guid_command, BackupData, BackupListData,
+    GenerateRandomGuidData, MachineIdData, PermissionCheckData, PreviewGuidData, RestoreBackupData,
+    WriteGuidData,
+};
+
+/// 请求行与请求头的最大长度
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+/// 请求体的最大长度
+const MAX_BODY_BYTES: usize = 64 * 1024;
+/// 读取完整请求和写入响应的时限，避免不发送数据的连接一直占用
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 备份请求体
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+struct BackupRequest {
+    description: Option<String>,
+}
+
+/// 写入机器码请求体
+#[derive(Debug, Deserialize, JsonSchema)]
+struct WriteGuidRequest {
+    guid: String,
+    description: Option<String>,
+    /// 提供时为临时写入，到期后自动恢复为写入前的值
+    revert_after_secs: Option<u64>,
+}
+
+/// 生成随机机器码请求体
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+struct GenerateRequest {
+    description: Option<String>,
+    /// 预览接口返回的一次性令牌，提供时写入预览的 GUID
+    preview_token: Option<String>,
+}
+
+/// 接口路由
+#[derive(Debug, Clone, PartialEq)]
+enum Route {
+    OpenApi,
+    ReadMachineId,
+    WriteMachineId,
+    PreviewMachineId,
+    GenerateMachineId,
+    ListBackups,
+    CreateBackup,
+    DeleteBackup(String),
+    RestoreBackup(String),
+    Permissions,
+    Capabilities,
+    QueryAuditLog,
+    VerifyAuditLog,
+}
+
+impl Route {
+    fn resolve(method: &str, path: &str) -> Option<Route> {
+        let path = path.split('?').next().unwrap_or_default();
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let route = match (method, segments.as_slice()) {
+            ("GET", ["api", "openapi.json"]) => Route::OpenApi,
+            ("GET", ["api", "machine-id"]) => Route::ReadMachineId,
+            ("PUT", ["api", "machine-id"]) => Route::WriteMachineId,
+            ("POST", ["api", "machine-id", "preview"]) => Route::PreviewMachineId,
+            ("POST", ["api", "machine-id", "generate"]) => Route::GenerateMachineId,
+            ("GET", ["api", "backups"]) => Route::ListBackups,
+            ("POST", ["api", "backups"]) => Route::CreateBackup,
+            ("DELETE", ["api", "backups", id]) if !id.is_empty() => {
+                Route::DeleteBackup(id.to_string())
+            }
+            ("POST", ["api", "backups", id, "restore"]) if !id.is_empty() => {
+                Route::RestoreBackup(id.to_string())
+            }
+            ("GET", ["api", "permissions"]) => Route::Permissions,
+            ("GET", ["api", "capabilities"]) => Route::Capabilities,
+            ("POST", ["api", "audit-log", "query"]) => Route::QueryAuditLog,
+            ("GET", ["api", "audit-log", "verify"]) => Route::VerifyAuditLog,
+            _ => return None,
+        };
+        Some(route)
+    }
+}
+
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    /// 请求头名称统一为小写
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct HttpResponse {
+    status: u16,
+    body: Value,
+}
+
+/// 错误码对应的 HTTP 状态码
+fn status_for(code: ErrorCode) -> u16 {
+    match code {
+        ErrorCode::InvalidGuidFormat
+        | ErrorCode::InvalidPreviewToken
+        | ErrorCode::InvalidSchedule
+        | ErrorCode::InvalidProfile
+        | ErrorCode::InvalidRevertDuration
+        | ErrorCode::InvalidSettings
+        | ErrorCode::InvalidExport
+        | ErrorCode::InvalidRequest => 400,
+        ErrorCode::Unauthorized => 401,
+        ErrorCode::InsufficientPermissions => 403,
+        ErrorCode::BackupNotFound
+        | ErrorCode::ScheduleNotFound
+        | ErrorCode::ProfileNotFound
+        | ErrorCode::PendingRevertNotFound => 404,
+        ErrorCode::ProfileAlreadyExists
+        | ErrorCode::GuidGenerationExhausted
+        | ErrorCode::GuidAlreadyUsed
+        | ErrorCode::DataDirConflict
+        | ErrorCode::DataDirBusy => 409,
+        ErrorCode::UnsupportedPlatform => 501,
+        ErrorCode::RegistryError
+        | ErrorCode::RegistryWriteError
+        | ErrorCode::HelperFailed
+        | ErrorCode::AuditLogFailed
+        | ErrorCode::NotFound
+        | ErrorCode::ParseError
+        | ErrorCode::StorageError
+        | ErrorCode::Internal => 500,
+    }
+}
+
+impl HttpResponse {
+    fn error(code: ErrorCode, message: impl Into<String>) -> Self {
+        HttpResponse::from_api(ApiResponse::<()>::err(ApiError::new(code, message)))
+    }
+
+    /// 将统一响应转换为 HTTP 响应，状态码由错误码决定
+    fn from_api<T: Serialize>(response: ApiResponse<T>) -> Self {
+        let status = response.error_code().map(status_for).unwrap_or(200);
+        HttpResponse {
+            status,
+            body: serde_json::to_value(response).unwrap_or(Value::Null),
+        }
+    }
+
+    /// 覆盖状态码，用于与业务无关的传输层错误
+    fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            409 => "Conflict",
+            413 => "Payload Too Large",
+            501 => "Not Implemented",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let body = serde_json::to_string(&self.body).unwrap_or_default();
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            self.reason(),
+            body.len()
+        );
+        if self.status == 401 {
+            head.push_str("WWW-Authenticate: Bearer\r\n");
+        }
+        head.push_str("\r\n");
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(body.as_bytes());
+        bytes
+    }
+}
+
+/// 解析请求行和请求头
+fn parse_head(head: &str) -> Result<(String, String, HashMap<String, String>), String> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(path), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method.to_string(), path.to_string())
+        }
+        _ => return Err(tr(Message::RequestLineInvalid { line: request_line })),
+    };
+
+    let mut headers = HashMap::new();
+    for line in lines.filter(|l| !l.is_empty()) {
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| tr(Message::RequestHeaderInvalid { line }))?;
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+    Ok((method, path, headers))
+}
+
+/// 从连接读取一个完整请求
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest, HttpResponse> {
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+    loop {
+        let mut line = String::new();
+        let read = (&mut reader)
+            .take(MAX_HEAD_BYTES.saturating_sub(head.len()) as u64)
+            .read_line(&mut line)
+            .await
+            .map_err(|e| HttpResponse::error(ErrorCode::InvalidRequest, e.to_string()))?;
+        if read == 0 || !line.ends_with('\n') {
+            return Err(HttpResponse::error(
+                ErrorCode::InvalidRequest,
+                tr(Message::RequestHeadIncomplete),
+            ));
+        }
+        if line == "\r\n" || line == "\n" {
+            break;
+        }
+        head.push_str(line.trim_end_matches(['\r', '\n']));
+        head.push_str("\r\n");
+    }
+
+    let (method, path, headers) =
+        parse_head(&head).map_err(|e| HttpResponse::error(ErrorCode::InvalidRequest, e))?;
+    let length = match headers.get("content-length") {
+        Some(value) => value.parse::<usize>().map_err(|_| {
+            HttpResponse::error(ErrorCode::InvalidRequest, tr(Message::ContentLengthInvalid))
+        })?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(HttpResponse::error(
+            ErrorCode::InvalidRequest,
+            tr(Message::RequestBodyTooLarge),
+        )
+        .with_status(413));
+    }
+    let mut body = vec![0u8; length];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| HttpResponse::error(ErrorCode::InvalidRequest, e.to_string()))?;
+
+    Ok(HttpRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+/// 解析 JSON 请求体，空请求体视为默认值
+fn parse_body<T: DeserializeOwned + Default>(body: &[u8]) -> Result<T, HttpResponse> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    parse_required_body(body)
+}
+
+fn parse_required_body<T: DeserializeOwned>(body: &[u8]) -> Result<T, HttpResponse> {
+    serde_json::from_slice(body).map_err(|e| {
+        HttpResponse::error(
+            ErrorCode::InvalidRequest,
+            tr(Message::RequestBodyInvalid {
+                reason: &e.to_string(),
+            }),
+        )
+    })
+}
+
+/// 处理单个请求，`port` 为实际监听的端口
+fn handle(request: &HttpRequest, token: &str, port: u16) -> HttpResponse {
+    let route = match Route::resolve(&request.method, &request.path) {
+        Some(route) => route,
+        None => {
+            return HttpResponse::error(ErrorCode::InvalidRequest, tr(Message::EndpointNotFound))
+                .with_status(404)
+        }
+    };
+
+    if route == Route::OpenApi {
+        return HttpResponse {
+            status: 200,
+            body: openapi_document(port),
+        };
+    }
+
+    let authorization = request.headers.get("authorization").map(String::as_str);
+    if !bearer_matches(token, authorization) {
+        warn!(
+            "拒绝未授权的 HTTP 请求: {} {}",
+            request.method, request.path
+        );
+        return HttpResponse::error(ErrorCode::Unauthorized, tr(Message::Unauthorized));
+    }
+
+    info!("HTTP 请求: {} {}", request.method, request.path);
+    let result = match route {
+        Route::OpenApi => unreachable!(),
+        Route::ReadMachineId => Ok(HttpResponse::from_api(read_machine_id())),
+        Route::WriteMachineId => parse_required_body::<WriteGuidRequest>(&request.body).map(|r| {
+            HttpResponse::from_api(write_machine_guid_command(
+                r.guid,
+                r.description,
+                r.revert_after_secs,
+            ))
+        }),
+        Route::PreviewMachineId => Ok(HttpResponse::from_api(preview_random_guid_command())),
+        Route::GenerateMachineId => parse_body::<GenerateRequest>(&request.body).map(|r| {
+            HttpResponse::from_api(generate_random_guid_command(r.description, r.preview_token))
+        }),
+        Route::ListBackups => Ok(HttpResponse::from_api(list_backups())),
+        Route::CreateBackup => parse_body::<BackupRequest>(&request.body)
+            .map(|r| HttpResponse::from_api(backup_machine_guid(r.description))),
+        Route::DeleteBackup(id) => Ok(HttpResponse::from_api(delete_backup_by_id(id))),
+        Route::RestoreBackup(id) => Ok(HttpResponse::from_api(restore_backup_by_id_command(id))),
+        Route::Permissions => Ok(HttpResponse::from_api(check_permission_command())),
+        Route::Capabilities => Ok(HttpResponse::from_api(check_capabilities_command())),
+        Route::QueryAuditLog => parse_body::<AuditQuery>(&request.body)
+            .map(|query| HttpResponse::from_api(query_audit_log_command(Some(query)))),
+        Route::VerifyAuditLog => Ok(HttpResponse::from_api(verify_audit_log_command())),
+    };
+    result.unwrap_or_else(|response| response)
+}
+
+async fn handle_connection(mut stream: TcpStream, token: String, port: u16) {
+    let response = match tokio::time::timeout(IO_TIMEOUT, read_request(&mut stream)).await {
+        // 命令函数均为阻塞调用，放到阻塞线程池执行
+        Ok(Ok(request)) => {
+            match tokio::task::spawn_blocking(move || handle(&request, &token, port)).await {
+                Ok(response) => response,
+                Err(e) => {
+                    error!("HTTP 处理任务异常退出: {}", e);
+                    HttpResponse::error(ErrorCode::Internal, tr(Message::InternalError))
+                }
+            }
+        }
+        Ok(Err(response)) => response,
+        Err(_) => {
+            warn!("读取 HTTP 请求超时");
+            HttpResponse::error(ErrorCode::InvalidRequest, tr(Message::RequestTimeout))
+                .with_status(408)
+        }
+    };
+
+    match tokio::time::timeout(IO_TIMEOUT, stream.write_all(&response.to_bytes())).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => warn!("写入 HTTP 响应失败: {}", e),
+        Err(_) => warn!("写入 HTTP 响应超时"),
+    }
+    let _ = stream.shutdown().await;
+}
+
+/// 在 127.0.0.1 的指定端口上提供 HTTP 接口，直到出现不可恢复的错误
+pub async fn serve(port: u16) -> Result<(), BackupError> {
+    let token = load_or_create_api_token()?;
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .map_err(|e| BackupError::StorageError(e.to_string()))?;
+    info!("HTTP 接口已启动: http://127.0.0.1:{}", port);
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_connection(stream, token.clone(), port));
+            }
+            Err(e) => {
+                error!("接受 HTTP 连接失败: {}", e);
+                return Err(BackupError::StorageError(e.to_string()));
+            }
+        }
+    }
+}
+
+/// 生成 schema 引用，并把结构体定义登记到 components
+fn schema_ref<T: JsonSchema>(generator: &mut SchemaGenerator) -> Value {
+    serde_json::to_value(generator.subschema_for::<T>()).unwrap_or(Value::Null)
+}
+
+/// 描述一个接口，成功和失败都使用同一个响应外壳
+fn operation(summary: &str, response: Value, request: Option<Value>) -> Value {
+    let mut op = json!({
+        "summary": summary,
+        "responses": {
+            "200": { "description": "操作成功", "content": { "application/json": { "schema": response } } },
+            "default": { "description": "操作失败，error.code 为机器可读的错误码", "content": { "application/json": { "schema": response } } },
+        }
+    });
+    if let Some(schema) = request {
+        op["requestBody"] = json!({ "content": { "application/json": { "schema": schema } } });
+    }
+    op
+}
+
+fn id_parameter() -> Value {
+    json!([{ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }])
+}
+
+/// 由响应结构体生成 OpenAPI 3.0 描述，服务地址使用实际监听的端口
+fn openapi_document(port: u16) -> Value {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+    let machine_id = schema_ref::<ApiResponse<MachineIdData>>(&mut generator);
+    let write = schema_ref::<ApiResponse<WriteGuidData>>(&mut generator);
+    let write_request = schema_ref::<WriteGuidRequest>(&mut generator);
+    let preview = schema_ref::<ApiResponse<PreviewGuidData>>(&mut generator);
+    let generate = schema_ref::<ApiResponse<GenerateRandomGuidData>>(&mut generator);
+    let generate_request = schema_ref::<GenerateRequest>(&mut generator);
+    let backup = schema_ref::<ApiResponse<BackupData>>(&mut generator);
+    let backup_request = schema_ref::<BackupRequest>(&mut generator);
+    let backup_list = schema_ref::<ApiResponse<BackupListData>>(&mut generator);
+    let deleted = schema_ref::<ApiResponse<()>>(&mut generator);
+    let restore = schema_ref::<ApiResponse<RestoreBackupData>>(&mut generator);
+    let permission = schema_ref::<ApiResponse<PermissionCheckData>>(&mut generator);
+    let capabilities = schema_ref::<ApiResponse<CapabilityReport>>(&mut generator);
+    let audit_entries = schema_ref::<ApiResponse<Vec<AuditEntry>>>(&mut generator);
+    let audit_query = schema_ref::<AuditQuery>(&mut generator);
+    let audit_verification = schema_ref::<ApiResponse<AuditVerification>>(&mut generator);
+
+    let mut delete = operation("删除备份", deleted, None);
+    delete["parameters"] = id_parameter();
+    let mut restore_op = operation("从备份恢复机器码", restore, None);
+    restore_op["parameters"] = id_parameter();
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "MachineID-Manage 本地接口",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "servers": [{ "url": format!("http://127.0.0.1:{}", port) }],
+        "security": [{ "bearerAuth": [] }],
+        "paths": {
+            "/api/openapi.json": {
+                "get": { "summary": "获取本接口描述", "security": [], "responses": { "200": { "description": "OpenAPI 文档" } } }
+            },
+            "/api/machine-id": {
+                "get": operation("读取当前机器码", machine_id, None),
+                "put": operation("写入指定机器码", write, Some(write_request)),
+            },
+            "/api/machine-id/preview": {
+                "post": operation("预览随机机器码", preview, None),
+            },
+            "/api/machine-id/generate": {
+                "post": operation("生成并写入随机机器码", generate, Some(generate_request)),
+            },
+            "/api/backups": {
+                "get": operation("列出所有备份", backup_list, None),
+                "post": operation("备份当前机器码", backup, Some(backup_request)),
+            },
+            "/api/backups/{id}": { "delete": delete },
+            "/api/backups/{id}/restore": { "post": restore_op },
+            "/api/permissions": {
+                "get": operation("检查管理员权限", permission, None),
+            },
+            "/api/capabilities": {
+                "get": operation("检查各标识符的读写能力", capabilities, None),
+            },
+            "/api/audit-log/query": {
+                "post": operation("查询审计日志", audit_entries, Some(audit_query)),
+            },
+            "/api/audit-log/verify": {
+                "get": operation("校验审计日志的哈希链", audit_verification, None),
+            },
+        },
+        "components": {
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer" }
+            },
+            "schemas": generator.take_definitions(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use machineid_core::settings::DEFAULT_HTTP_PORT;
+
+    fn request(method: &str, path: &str, authorization: Option<&str>) -> HttpRequest {
+        let mut headers = HashMap::new();
+        if let Some(value) = authorization {
+            headers.insert("authorization".to_string(), value.to_string());
+        }
+        HttpRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            headers,
+            body: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_route_resolve() {
+        assert_eq!(
+            Route::resolve("GET", "/api/machine-id"),
+            Some(Route::ReadMachineId)
+        );
+        assert_eq!(
+            Route::resolve("GET", "/api/backups?limit=1"),
+            Some(Route::ListBackups)
+        );
+        assert_eq!(
+            Route::resolve("POST", "/api/backups/backup_1/restore"),
+            Some(Route::RestoreBackup("backup_1".to_string()))
+        );
+        assert_eq!(
+            Route::resolve("DELETE", "/api/backups/backup_1"),
+            Some(Route::DeleteBackup("backup_1".to_string()))
+        );
+        assert_eq!(
+            Route::resolve("GET", "/api/capabilities"),
+            Some(Route::Capabilities)
+        );
+        assert_eq!(
+            Route::resolve("POST", "/api/audit-log/query"),
+            Some(Route::QueryAuditLog)
+        );
+        assert_eq!(
+            Route::resolve("GET", "/api/audit-log/verify"),
+            Some(Route::VerifyAuditLog)
+        );
+        assert_eq!(Route::resolve("DELETE", "/api/backups"), None);
+        assert_eq!(Route::resolve("GET", "/api/unknown"), None);
+    }
+
+    #[test]
+    fn test_parse_head() {
+        let (method, path, headers) = parse_head(
+            "GET /api/backups HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer x\r\n",
+        )
+        .unwrap();
+        assert_eq!(method, "GET");
+        assert_eq!(path, "/api/backups");
+        assert_eq!(headers.get("authorization").unwrap(), "Bearer x");
+
+        assert!(parse_head("GET /api/backups").is_err());
+        assert!(parse_head("GET / HTTP/1.1\r\nbroken header\r\n").is_err());
+    }
+
+    #[test]
+    fn test_requests_require_token() {
+        let response = handle(
+            &request("GET", "/api/backups", None),
+            "secret",
+            DEFAULT_HTTP_PORT,
+        );
+        assert_eq!(response.status, 401);
+        assert_eq!(response.body["success"], false);
+        assert_eq!(response.body["error"]["code"], "unauthorized");
+        assert!(String::from_utf8(response.to_bytes())
+            .unwrap()
+            .contains("WWW-Authenticate: Bearer"));
+
+        let response = handle(
+            &request("GET", "/api/backups", Some("Bearer wrong")),
+            "secret",
+            DEFAULT_HTTP_PORT,
+        );
+        assert_eq!(response.status, 401);
+    }
+
+    #[test]
+    fn test_unknown_route_and_invalid_body() {
+        let response = handle(
+            &request("GET", "/api/nothing", None),
+            "secret",
+            DEFAULT_HTTP_PORT,
+        );
+        assert_eq!(response.status, 404);
+
+        let mut invalid = request("PUT", "/api/machine-id", Some("Bearer secret"));
+        invalid.body = b"{\"guid\": 1}".to_vec();
+        assert_eq!(handle(&invalid, "secret", DEFAULT_HTTP_PORT).status, 400);
+    }
+
+    #[test]
+    fn test_status_for_error_codes() {
+        assert_eq!(status_for(ErrorCode::InsufficientPermissions), 403);
+        assert_eq!(status_for(ErrorCode::BackupNotFound), 404);
+        assert_eq!(status_for(ErrorCode::InvalidGuidFormat), 400);
+        assert_eq!(status_for(ErrorCode::GuidAlreadyUsed), 409);
+        assert_eq!(status_for(ErrorCode::StorageError), 500);
+
+        let ok = HttpResponse::from_api(ApiResponse::ok(1));
+        assert_eq!(ok.status, 200);
+        assert_eq!(ok.body["data"], 1);
+    }
+
+    #[test]
+    fn test_openapi_document() {
+        let response = handle(&request("GET", "/api/openapi.json", None), "secret", 18080);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["servers"][0]["url"], "http://127.0.0.1:18080");
+
+        let schemas = &response.body["components"]["schemas"];
+        for name in [
+            "BackupData",
+            "WriteGuidData",
+            "RestoreBackupData",
+            "MachineIdBackup",
+            "PendingRevert",
+            "ApiError",
+            "ErrorCode",
+            "CapabilityIssue",
+            "AuditEntry",
+        ] {
+            assert!(schemas.get(name).is_some(), "缺少 schema: {}", name);
+        }
+        assert!(response.body["paths"]["/api/backups/{id}/restore"]["post"].is_object());
+    }
+}
@@ -0,0 +1,129 @@
+//! 单实例与启动参数转发
+//!
+//! 再次启动图形界面时不会创建第二个进程，而是聚焦已有窗口并把启动参数（例如 `--restore <备份ID>`）
+//! 转发给它，避免两个进程同时写入备份文件和机器码。需要前端处理的请求先保存，
+//! 再发送 `launch-request` 事件，前端加载完成或收到事件时领取。
+//!
+//! 以管理员身份重启时，新进程带 `--handoff-from <PID>` 启动，等待原进程退出后才注册单实例并开始初始化。
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use machineid_core::platform::permissions::{wait_for_process_exit, ELEVATION_HANDOFF_ARG};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::tray;
+
+/// 有待处理的启动请求时发送给前端的事件
+pub const LAUNCH_REQUEST_EVENT: &str = "launch-request";
+/// 提权重启时等待原进程退出的最长时间（秒）
+const HANDOFF_TIMEOUT_SECS: u64 = 30;
+
+/// 需要前端处理的启动请求
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+pub struct LaunchRequest {
+    /// 从指定备份恢复，恢复前由用户确认
+    pub restore: Option<String>,
+}
+
+impl LaunchRequest {
+    fn is_empty(&self) -> bool {
+        self.restore.is_none()
+    }
+}
+
+/// 尚未被前端领取的启动请求
+#[derive(Default)]
+pub struct PendingLaunch(Mutex<Option<LaunchRequest>>);
+
+/// 解析图形界面的启动参数，未识别的参数忽略
+pub fn parse_launch_args(args: &[String]) -> LaunchRequest {
+    let mut request = LaunchRequest::default();
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == "--restore" {
+            request.restore = iter.next().cloned();
+        } else if let Some(id) = arg.strip_prefix("--restore=") {
+            request.restore = Some(id.to_string());
+        }
+    }
+    request
+}
+
+/// 提权重启时原进程的 PID
+fn handoff_pid(args: &[String]) -> Option<u32> {
+    let index = args.iter().position(|arg| arg == ELEVATION_HANDOFF_ARG)?;
+    args.get(index + 1)?.parse().ok()
+}
+
+/// 提权重启时等待原进程退出；超时返回 false，调用方应放弃启动
+pub fn wait_for_handoff(args: &[String]) -> bool {
+    match handoff_pid(args) {
+        Some(pid) => {
+            info!("等待原进程 {} 退出", pid);
+            wait_for_process_exit(pid, Duration::from_secs(HANDOFF_TIMEOUT_SECS))
+        }
+        None => true,
+    }
+}
+
+/// 处理启动参数：显示主窗口，有需要前端处理的请求时保存并通知前端
+pub fn handle_launch(app: &AppHandle, args: &[String]) {
+    tray::show_main_window(app);
+    let request = parse_launch_args(args);
+    if request.is_empty() {
+        return;
+    }
+    info!("收到启动请求: {:?}", request);
+    if let Some(pending) = app.try_state::<PendingLaunch>() {
+        *pending.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(request);
+    }
+    if let Err(e) = app.emit(LAUNCH_REQUEST_EVENT, ()) {
+        warn!("发送启动请求事件失败: {}", e);
+    }
+}
+
+/// 领取待处理的启动请求
+pub fn take_launch_request(app: &AppHandle) -> Option<LaunchRequest> {
+    app.try_state::<PendingLaunch>()
+        .and_then(|pending| pending.0.lock().unwrap_or_else(|e| e.into_inner()).take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("machineid-manage")
+            .chain(list.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_launch_args() {
+        assert!(parse_launch_args(&args(&[])).is_empty());
+        assert_eq!(
+            parse_launch_args(&args(&["--restore", "backup_1"])).restore,
+            Some("backup_1".to_string())
+        );
+        assert_eq!(
+            parse_launch_args(&args(&["--unknown", "--restore=backup_2"])).restore,
+            Some("backup_2".to_string())
+        );
+        assert!(parse_launch_args(&args(&["--restore"])).is_empty());
+    }
+
+    #[test]
+    fn test_handoff_pid() {
+        assert_eq!(
+            handoff_pid(&args(&[ELEVATION_HANDOFF_ARG, "1234"])),
+            Some(1234)
+        );
+        assert_eq!(handoff_pid(&args(&[ELEVATION_HANDOFF_ARG, "abc"])), None);
+        assert_eq!(handoff_pid(&args(&["--restore", "backup_1"])), None);
+        assert!(wait_for_handoff(&args(&[])));
+    }
+}
@@ -1,520 +1,386 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use crate::machine_id::clear_all_backups as machine_id_clear_all_backups;
-use crate::machine_id::get_backup_count as machine_id_get_backup_count;
-use crate::machine_id::list_backups as machine_id_list_backups;
-use crate::machine_id::update_backup_description as machine_id_update_backup_description;
-use crate::machine_id::{
-    backup_current_machine_guid, delete_backup, generate_random_guid, generate_random_machine_guid,
-    read_machine_guid, restore_backup_by_id, test_registry_write_access, write_machine_guid,
-    BackupError, MachineIdBackup, RestoreInfo, WriteResult,
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use machineid_core::api::{ApiError, ApiResponse, ErrorCode};
+use machineid_core::audit::{
+    query_audit_log, verify_audit_log, AuditEntry, AuditQuery, AuditVerification,
+};
+use machineid_core::data_dir::{self, DataDirInfo, DataDirMigration, DataDirMode};
+use machineid_core::diagnostics::{export_diagnostics, DiagnosticsExport};
+use machineid_core::events::{self, ChangeEvent};
+use machineid_core::export::{export_backups, ExportRequest, ExportResult};
+use machineid_core::i18n::{self, tr, Locale, Message};
+use machineid_core::logging::{self, RedactingWriter};
+use machineid_core::machine_id::clear_all_backups as machine_id_clear_all_backups;
+use machineid_core::machine_id::get_backup_count as machine_id_get_backup_count;
+use machineid_core::machine_id::list_backups as machine_id_list_backups;
+use machineid_core::machine_id::update_backup_description as machine_id_update_backup_description;
+use machineid_core::machine_id::{
+    backup_current_machine_guid, delete_backup, generate_random_machine_guid, generate_unique_guid,
+    read_machine_guid, read_machine_guid_audited, restore_backup_by_id, test_registry_write_access,
+    validate_identity_value, write_machine_guid, BackupError, GenerateResult, GuidHistory,
+    MachineIdBackup, RestoreInfo, UniqueGuid, WriteResult,
 };
-use crate::platform::permissions::{
-    check_admin_permissions, check_restart_state, request_elevation, RestartResult,
+use machineid_core::platform::capabilities::{check_capabilities, CapabilityReport};
+use machineid_core::platform::permissions::{
+    check_admin_permissions, request_elevation, RestartResult,
 };
+use machineid_core::preview_token::{issue_preview_token, redeem_preview_token, PreviewToken};
+use machineid_core::profiles::{IdentityProfile, ProfileApplyResult};
+use machineid_core::restart_state::{take_restart_state, PendingAction};
+use machineid_core::revert::{validate_revert_after, write_temporary, PendingRevert};
+use machineid_core::scheduler::{Schedule, ScheduleAction, ScheduleTrigger};
+use machineid_core::settings::{self, Settings, SettingsOverrides};
+use machineid_core::{profiles, revert, scheduler, watcher};
+use tauri::{AppHandle, Emitter, WindowEvent};
+use tokio::sync::broadcast::error::RecvError;
 use tracing::{error, info, warn};
+use tracing_subscriber::prelude::*;
 
-mod machine_id;
-mod platform;
+mod cli;
+mod http_api;
+mod instance;
+mod tray;
 
-/// 将内部错误转换为用户友好的错误信息
+/// 将内部错误转换为用户友好的错误信息，使用当前语言
 /// 避免泄露敏感信息如文件路径等
 fn sanitize_error_for_user(error: &BackupError) -> String {
-    match error {
-        BackupError::InsufficientPermissions => {
-            "权限不足，需要管理员权限才能执行此操作".to_string()
-        }
-        BackupError::InvalidGuidFormat(_) => "GUID 格式无效，请检查输入".to_string(),
-        BackupError::NotFound => "未找到 MachineGuid，系统可能尚未初始化".to_string(),
-        BackupError::BackupNotFound(_) => "指定的备份不存在".to_string(),
-        BackupError::RegistryWriteError(_) => "注册表写入失败，请检查权限或系统状态".to_string(),
-        BackupError::RegistryError(_) => "注册表读取失败，请检查系统状态".to_string(),
-        BackupError::StorageError(_) => "存储操作失败，请检查磁盘空间".to_string(),
-        BackupError::ParseError(_) => "数据解析失败".to_string(),
-        BackupError::UnsupportedPlatform => "当前操作系统不支持此功能".to_string(),
+    i18n::error_summary(error, i18n::current_locale())
+}
+
+/// 将内部错误转换为接口错误，message 为脱敏后的用户提示
+fn api_error(error: &BackupError) -> ApiError {
+    ApiError::from_backup_error(error, sanitize_error_for_user(error))
+}
+
+/// 按结果构造统一响应，失败时记录日志
+fn respond<T>(result: Result<T, BackupError>, action: &str) -> ApiResponse<T> {
+    if let Err(e) = &result {
+        warn!("{}失败: {}", action, e);
+    }
+    ApiResponse::from_result(result, sanitize_error_for_user)
+}
+
+/// 服务端二次验证权限，无权限时返回错误响应
+fn require_admin<T>(action: &str) -> Result<(), ApiResponse<T>> {
+    if check_admin_permissions().has_permission {
+        Ok(())
+    } else {
+        warn!("权限不足，拒绝{}", action);
+        Err(ApiResponse::err(api_error(
+            &BackupError::InsufficientPermissions,
+        )))
     }
 }
 
-#[derive(serde::Serialize)]
-struct MachineIdResponse {
-    success: bool,
+/// 按配置限制描述长度
+fn truncate_description(description: Option<String>) -> Option<String> {
+    settings::current().truncate_description(description)
+}
+
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct MachineIdData {
     guid: String,
     source: String,
-    error: Option<String>,
 }
 
-#[derive(serde::Serialize)]
-struct BackupResponse {
-    success: bool,
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct BackupData {
     backup: Option<MachineIdBackup>,
+    /// 当前机器码已有备份时跳过
     skipped: bool,
-    error: Option<String>,
 }
 
-#[derive(serde::Serialize)]
-struct BackupListResponse {
-    success: bool,
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct BackupListData {
     backups: Vec<MachineIdBackup>,
     count: usize,
-    error: Option<String>,
 }
 
 #[tauri::command]
-fn read_machine_id() -> Result<MachineIdResponse, String> {
+fn read_machine_id() -> ApiResponse<MachineIdData> {
     info!("读取机器码");
-    match read_machine_guid() {
-        Ok(machine_id) => Ok(MachineIdResponse {
-            success: true,
+    respond(
+        read_machine_guid_audited().map(|machine_id| MachineIdData {
             guid: machine_id.guid,
             source: machine_id.source,
-            error: None,
         }),
-        Err(e) => {
-            warn!("读取机器码失败: {}", e);
-            Ok(MachineIdResponse {
-                success: false,
-                guid: String::new(),
-                source: String::new(),
-                error: Some(sanitize_error_for_user(&e)),
-            })
-        }
-    }
+        "读取机器码",
+    )
 }
 
 #[tauri::command]
-fn backup_machine_guid(description: Option<String>) -> Result<BackupResponse, String> {
+fn backup_machine_guid(description: Option<String>) -> ApiResponse<BackupData> {
     info!("备份机器码");
-    match backup_current_machine_guid(description) {
-        Ok(backup) => {
-            let skipped = backup.is_none();
-            Ok(BackupResponse {
-                success: true,
-                backup,
-                skipped,
-                error: None,
-            })
-        }
-        Err(e) => {
-            warn!("备份机器码失败: {}", e);
-            Ok(BackupResponse {
-                success: false,
-                backup: None,
-                skipped: false,
-                error: Some(sanitize_error_for_user(&e)),
-            })
-        }
-    }
+    respond(
+        backup_current_machine_guid(description).map(|backup| BackupData {
+            skipped: backup.is_none(),
+            backup,
+        }),
+        "备份机器码",
+    )
 }
 
 #[tauri::command]
-fn list_backups() -> Result<BackupListResponse, String> {
+fn list_backups() -> ApiResponse<BackupListData> {
     info!("获取备份列表");
-    match machine_id_list_backups() {
-        Ok(backups) => Ok(BackupListResponse {
-            success: true,
-            backups: backups.clone(),
+    respond(
+        machine_id_list_backups().map(|backups| BackupListData {
             count: backups.len(),
-            error: None,
+            backups,
         }),
-        Err(e) => {
-            warn!("获取备份列表失败: {}", e);
-            Ok(BackupListResponse {
-                success: false,
-                backups: Vec::new(),
-                count: 0,
-                error: Some(sanitize_error_for_user(&e)),
-            })
-        }
-    }
+        "获取备份列表",
+    )
 }
 
 #[tauri::command]
-fn delete_backup_by_id(id: String) -> Result<BackupResponse, String> {
+fn delete_backup_by_id(id: String) -> ApiResponse<()> {
     info!("删除备份: {}", id);
-    match delete_backup(&id) {
-        Ok(_) => Ok(BackupResponse {
-            success: true,
-            backup: None,
-            skipped: false,
-            error: None,
-        }),
-        Err(e) => {
-            warn!("删除备份失败: {}", e);
-            Ok(BackupResponse {
-                success: false,
-                backup: None,
-                skipped: false,
-                error: Some(sanitize_error_for_user(&e)),
-            })
-        }
-    }
+    respond(delete_backup(&id), "删除备份")
 }
 
 #[tauri::command]
-fn clear_all_backups() -> Result<BackupResponse, String> {
+fn clear_all_backups() -> ApiResponse<()> {
     info!("清空所有备份");
-    match machine_id_clear_all_backups() {
-        Ok(_) => Ok(BackupResponse {
-            success: true,
-            backup: None,
-            skipped: false,
-            error: None,
-        }),
-        Err(e) => {
-            warn!("清空备份失败: {}", e);
-            Ok(BackupResponse {
-                success: false,
-                backup: None,
-                skipped: false,
-                error: Some(sanitize_error_for_user(&e)),
-            })
-        }
-    }
-}
-
-#[derive(serde::Serialize)]
-struct UpdateBackupDescriptionResponse {
-    success: bool,
-    backup: Option<MachineIdBackup>,
-    error: Option<String>,
+    respond(machine_id_clear_all_backups(), "清空备份")
 }
 
 #[tauri::command]
 fn update_backup_description_command(
     id: String,
     description: Option<String>,
-) -> Result<UpdateBackupDescriptionResponse, String> {
+) -> ApiResponse<MachineIdBackup> {
     info!("更新备份描述: {}", id);
-
-    // 限制描述长度
-    let description = description.map(|d| {
-        if d.len() > MAX_DESCRIPTION_LENGTH {
-            d.chars().take(MAX_DESCRIPTION_LENGTH).collect()
-        } else {
-            d
-        }
-    });
-
-    match machine_id_update_backup_description(&id, description) {
-        Ok(backup) => Ok(UpdateBackupDescriptionResponse {
-            success: true,
-            backup: Some(backup),
-            error: None,
-        }),
-        Err(e) => {
-            warn!("更新备份描述失败: {}", e);
-            Ok(UpdateBackupDescriptionResponse {
-                success: false,
-                backup: None,
-                error: Some(sanitize_error_for_user(&e)),
-            })
-        }
-    }
+    respond(
+        machine_id_update_backup_description(&id, truncate_description(description)),
+        "更新备份描述",
+    )
 }
 
-#[derive(serde::Serialize)]
-struct BackupCountResponse {
-    success: bool,
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct BackupCountData {
     count: usize,
-    error: Option<String>,
 }
 
 #[tauri::command]
-fn get_backup_count() -> Result<BackupCountResponse, String> {
-    match machine_id_get_backup_count() {
-        Ok(count) => Ok(BackupCountResponse {
-            success: true,
-            count,
-            error: None,
-        }),
-        Err(e) => Ok(BackupCountResponse {
-            success: false,
-            count: 0,
-            error: Some(sanitize_error_for_user(&e)),
-        }),
-    }
+fn get_backup_count() -> ApiResponse<BackupCountData> {
+    respond(
+        machine_id_get_backup_count().map(|count| BackupCountData { count }),
+        "获取备份数量",
+    )
 }
 
-#[derive(serde::Serialize)]
-struct WriteGuidResponse {
-    success: bool,
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct WriteGuidData {
     previous_guid: String,
     new_guid: String,
     pre_backup: Option<MachineIdBackup>,
     post_backup: Option<MachineIdBackup>,
+    /// 临时写入时登记的自动恢复
+    pending_revert: Option<PendingRevert>,
     message: String,
-    error: Option<String>,
 }
 
-// 常量定义
-const MAX_DESCRIPTION_LENGTH: usize = 200;
-const GUID_LENGTH: usize = 36;
-
+/// 写入机器码
+/// 提供 `revert_after_secs` 时为临时写入，到期后自动恢复为写入前的值；登记自动恢复失败时不保留写入
 #[tauri::command]
 fn write_machine_guid_command(
     new_guid: String,
     description: Option<String>,
-) -> Result<WriteGuidResponse, String> {
+    revert_after_secs: Option<u64>,
+) -> ApiResponse<WriteGuidData> {
     info!("写入机器码: {}", new_guid);
 
-    // 验证 GUID 长度
-    if new_guid.len() != GUID_LENGTH {
-        return Ok(WriteGuidResponse {
-            success: false,
-            previous_guid: String::new(),
-            new_guid: String::new(),
-            pre_backup: None,
-            post_backup: None,
-            message: String::new(),
-            error: Some(format!("GUID 长度必须为 {} 个字符", GUID_LENGTH)),
-        });
+    // 按平台校验格式，Linux 另外接受不含连字符的 machine-id
+    if let Err(e) = validate_identity_value(&new_guid) {
+        return ApiResponse::err(api_error(&e));
     }
 
-    // 限制描述长度
-    let description = description.map(|d| {
-        if d.len() > MAX_DESCRIPTION_LENGTH {
-            d.chars().take(MAX_DESCRIPTION_LENGTH).collect()
-        } else {
-            d
-        }
-    });
+    if let Some(Err(e)) = revert_after_secs.map(validate_revert_after) {
+        return ApiResponse::err(api_error(&e));
+    }
+
+    let description = truncate_description(description);
 
-    // 服务端二次验证权限
-    let perm_check = check_admin_permissions();
-    if !perm_check.has_permission {
-        warn!("权限不足，拒绝写入操作");
-        return Ok(WriteGuidResponse {
-            success: false,
-            previous_guid: String::new(),
-            new_guid: String::new(),
-            pre_backup: None,
-            post_backup: None,
-            message: String::new(),
-            error: Some("权限不足，需要管理员权限".to_string()),
-        });
+    if let Err(denied) = require_admin("写入操作") {
+        return denied;
     }
 
-    match write_machine_guid(&new_guid, description) {
-        Ok(WriteResult {
+    let result = match revert_after_secs {
+        Some(secs) => write_temporary(&new_guid, description, secs)
+            .map(|(write, revert)| (write, Some(revert))),
+        None => write_machine_guid(&new_guid, description).map(|write| (write, None)),
+    };
+    let result = result.map(|(write, pending_revert)| {
+        let WriteResult {
             previous_guid,
             new_guid: current_guid,
             pre_backup,
             post_backup,
-        }) => Ok(WriteGuidResponse {
-            success: true,
+        } = write;
+        WriteGuidData {
             previous_guid,
             new_guid: current_guid.clone(),
             pre_backup,
             post_backup,
-            message: format!("成功将 MachineGuid 替换为: {}", current_guid),
-            error: None,
-        }),
-        Err(e) => {
-            warn!("写入机器码失败: {}", e);
-            Ok(WriteGuidResponse {
-                success: false,
-                previous_guid: String::new(),
-                new_guid: String::new(),
-                pre_backup: None,
-                post_backup: None,
-                message: String::new(),
-                error: Some(sanitize_error_for_user(&e)),
-            })
+            pending_revert,
+            message: tr(Message::WriteSucceeded {
+                guid: &current_guid,
+            }),
         }
-    }
+    });
+    respond(result, "写入机器码")
 }
 
-#[derive(serde::Serialize)]
-struct GenerateRandomGuidResponse {
-    success: bool,
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct GenerateRandomGuidData {
     previous_guid: String,
     new_guid: String,
     pre_backup: Option<MachineIdBackup>,
     post_backup: Option<MachineIdBackup>,
+    /// 因与当前值、历史备份或黑名单冲突而被拒绝的候选 GUID 数量
+    rejected_candidates: usize,
     message: String,
-    error: Option<String>,
 }
 
-#[derive(serde::Serialize)]
-struct PreviewGuidResponse {
-    success: bool,
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct PreviewGuidData {
     guid: String,
-    error: Option<String>,
+    /// 与预览值绑定的一次性令牌，应用时需原样传回
+    token: String,
+    #[ts(type = "number")]
+    expires_at: u64,
+    rejected_candidates: usize,
 }
 
-#[derive(serde::Serialize)]
-struct RestoreBackupResponse {
-    success: bool,
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct RestoreBackupData {
     previous_guid: String,
     restored_guid: String,
     pre_backup: Option<MachineIdBackup>,
-    restored_from: Option<MachineIdBackup>,
+    restored_from: MachineIdBackup,
     message: String,
-    error: Option<String>,
 }
 
 #[tauri::command]
-fn restore_backup_by_id_command(id: String) -> Result<RestoreBackupResponse, String> {
+fn restore_backup_by_id_command(id: String) -> ApiResponse<RestoreBackupData> {
     info!("恢复备份: {}", id);
 
-    // 服务端二次验证权限
-    let perm_check = check_admin_permissions();
-    if !perm_check.has_permission {
-        warn!("权限不足，拒绝恢复操作");
-        return Ok(RestoreBackupResponse {
-            success: false,
-            previous_guid: String::new(),
-            restored_guid: String::new(),
-            pre_backup: None,
-            restored_from: None,
-            message: String::new(),
-            error: Some("权限不足，需要管理员权限".to_string()),
-        });
+    if let Err(denied) = require_admin("恢复操作") {
+        return denied;
     }
 
-    match restore_backup_by_id(&id) {
-        Ok(RestoreInfo {
+    let result = restore_backup_by_id(&id).map(
+        |RestoreInfo {
+             previous_guid,
+             restored_guid,
+             pre_backup,
+             restored_from,
+         }| RestoreBackupData {
+            message: tr(Message::RestoreSucceeded {
+                guid: &restored_guid,
+            }),
             previous_guid,
             restored_guid,
             pre_backup,
             restored_from,
-        }) => Ok(RestoreBackupResponse {
-            success: true,
-            previous_guid,
-            restored_guid: restored_guid.clone(),
-            pre_backup,
-            restored_from: Some(restored_from),
-            message: format!("恢复成功: {}", restored_guid),
-            error: None,
-        }),
-        Err(e) => {
-            warn!("恢复备份失败: {}", e);
-            Ok(RestoreBackupResponse {
-                success: false,
-                previous_guid: String::new(),
-                restored_guid: String::new(),
-                pre_backup: None,
-                restored_from: None,
-                message: String::new(),
-                error: Some(sanitize_error_for_user(&e)),
-            })
-        }
-    }
+        },
+    );
+    respond(result, "恢复备份")
 }
 
 /// 预览随机生成的 GUID
-/// 用于前端显示预览值，确保预览值和实际替换值一致
-#[tauri::command]
-fn preview_random_guid_command() -> Result<PreviewGuidResponse, String> {
-    match generate_random_guid() {
-        Ok(guid) => Ok(PreviewGuidResponse {
-            success: true,
-            guid,
-            error: None,
-        }),
-        Err(e) => {
-            warn!("生成预览 GUID 失败: {}", e);
-            Ok(PreviewGuidResponse {
-                success: false,
-                guid: String::new(),
-                error: Some(sanitize_error_for_user(&e)),
-            })
-        }
-    }
+/// 返回与预览值绑定的一次性令牌，应用时凭令牌写入，确保预览值和实际替换值一致
+#[tauri::command]
+fn preview_random_guid_command() -> ApiResponse<PreviewGuidData> {
+    let result = generate_unique_guid().map(
+        |UniqueGuid {
+             guid,
+             rejected_candidates,
+         }| {
+            let PreviewToken { token, expires_at } = issue_preview_token(&guid);
+            PreviewGuidData {
+                guid,
+                token,
+                expires_at,
+                rejected_candidates,
+            }
+        },
+    );
+    respond(result, "生成预览 GUID")
 }
 
 #[tauri::command]
 fn generate_random_guid_command(
     description: Option<String>,
-    preview_guid: Option<String>,
-) -> Result<GenerateRandomGuidResponse, String> {
+    preview_token: Option<String>,
+) -> ApiResponse<GenerateRandomGuidData> {
     info!("生成随机机器码");
 
-    // 限制描述长度
-    let description = description.map(|d| {
-        if d.len() > MAX_DESCRIPTION_LENGTH {
-            d.chars().take(MAX_DESCRIPTION_LENGTH).collect()
-        } else {
-            d
-        }
-    });
+    let description = truncate_description(description);
 
-    // 服务端二次验证权限
-    let perm_check = check_admin_permissions();
-    if !perm_check.has_permission {
-        warn!("权限不足，拒绝生成操作");
-        return Ok(GenerateRandomGuidResponse {
-            success: false,
-            previous_guid: String::new(),
-            new_guid: String::new(),
-            pre_backup: None,
-            post_backup: None,
-            message: String::new(),
-            error: Some("权限不足，需要管理员权限".to_string()),
-        });
+    if let Err(denied) = require_admin("生成操作") {
+        return denied;
     }
 
-    // 如果提供了预览 GUID，直接使用预览值进行替换，确保一致性
-    if let Some(guid) = preview_guid {
-        match write_machine_guid(&guid, description) {
-            Ok(WriteResult {
-                previous_guid,
-                new_guid: current_guid,
-                pre_backup,
-                post_backup,
-            }) => Ok(GenerateRandomGuidResponse {
-                success: true,
-                previous_guid,
-                new_guid: current_guid.clone(),
-                pre_backup,
-                post_backup,
-                message: format!("成功生成并替换 MachineGuid: {}", current_guid),
-                error: None,
-            }),
-            Err(e) => {
-                warn!("使用预览 GUID 替换失败: {}", e);
-                Ok(GenerateRandomGuidResponse {
-                    success: false,
-                    previous_guid: String::new(),
-                    new_guid: String::new(),
-                    pre_backup: None,
-                    post_backup: None,
-                    message: String::new(),
-                    error: Some(sanitize_error_for_user(&e)),
+    // 如果提供了预览令牌，校验后写入令牌绑定的 GUID，确保写入的就是预览值
+    // 预览值生成后历史记录可能已变化，替换前需再次检查是否已被使用
+    let result = match preview_token {
+        Some(token) => redeem_preview_token(&token).and_then(|guid| {
+            let history = GuidHistory::load()?;
+            if history.contains(&guid) {
+                Err(BackupError::GuidAlreadyUsed(guid))
+            } else {
+                write_machine_guid(&guid, description).map(|write| GenerateResult {
+                    write,
+                    rejected_candidates: 0,
                 })
             }
-        }
-    } else {
-        // 如果没有提供预览 GUID，则生成新的 GUID
-        match generate_random_machine_guid(description) {
-            Ok(WriteResult {
-                previous_guid,
-                new_guid: current_guid,
-                pre_backup,
-                post_backup,
-            }) => Ok(GenerateRandomGuidResponse {
-                success: true,
+        }),
+        // 如果没有提供预览令牌，则生成新的 GUID
+        None => generate_random_machine_guid(description),
+    };
+
+    let result = result.map(
+        |GenerateResult {
+             write:
+                 WriteResult {
+                     previous_guid,
+                     new_guid: current_guid,
+                     pre_backup,
+                     post_backup,
+                 },
+             rejected_candidates,
+         }| {
+            if rejected_candidates > 0 {
+                info!(
+                    "生成随机机器码时拒绝了 {} 个重复候选值",
+                    rejected_candidates
+                );
+            }
+            GenerateRandomGuidData {
                 previous_guid,
                 new_guid: current_guid.clone(),
                 pre_backup,
                 post_backup,
-                message: format!("成功生成并替换 MachineGuid: {}", current_guid),
-                error: None,
-            }),
-            Err(e) => {
-                warn!("生成随机机器码失败: {}", e);
-                Ok(GenerateRandomGuidResponse {
-                    success: false,
-                    previous_guid: String::new(),
-                    new_guid: String::new(),
-                    pre_backup: None,
-                    post_backup: None,
-                    message: String::new(),
-                    error: Some(sanitize_error_for_user(&e)),
-                })
+                rejected_candidates,
+                message: tr(Message::GenerateSucceeded {
+                    guid: &current_guid,
+                }),
             }
-        }
-    }
+        },
+    );
+    respond(result, "生成随机机器码")
 }
 
 #[tauri::command]
@@ -529,10 +395,12 @@ fn get_app_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
-#[derive(serde::Serialize)]
-struct PermissionCheckResponse {
-    success: bool,
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct PermissionCheckData {
     has_permission: bool,
+    /// 检测本身是否成功完成，为 false 时 has_permission 不可信
+    check_success: bool,
     method: String,
     error_type: Option<String>,
     error_message: Option<String>,
@@ -540,16 +408,16 @@ struct PermissionCheckResponse {
 }
 
 #[tauri::command]
-fn check_permission_command() -> Result<PermissionCheckResponse, String> {
+fn check_permission_command() -> ApiResponse<PermissionCheckData> {
     let result = check_admin_permissions();
     info!(
         "权限检查: has_permission={}, method={}, check_success={}",
         result.has_permission, result.method, result.check_success
     );
 
-    Ok(PermissionCheckResponse {
-        success: result.check_success,
+    ApiResponse::ok(PermissionCheckData {
         has_permission: result.has_permission,
+        check_success: result.check_success,
         method: result.method,
         error_type: result.error_type,
         error_message: result.error_message,
@@ -557,100 +425,432 @@ fn check_permission_command() -> Result<PermissionCheckResponse, String> {
     })
 }
 
+/// 按标识符细分的读写能力，说明写入会因为什么失败
 #[tauri::command]
-fn test_write_access_command() -> Result<PermissionCheckResponse, String> {
+fn check_capabilities_command() -> ApiResponse<CapabilityReport> {
+    ApiResponse::ok(check_capabilities())
+}
+
+/// 按条件查询审计日志，按时间从新到旧排列
+#[tauri::command]
+fn query_audit_log_command(query: Option<AuditQuery>) -> ApiResponse<Vec<AuditEntry>> {
+    respond(query_audit_log(&query.unwrap_or_default()), "查询审计日志")
+}
+
+/// 校验审计日志的哈希链是否完整
+#[tauri::command]
+fn verify_audit_log_command() -> ApiResponse<AuditVerification> {
+    respond(verify_audit_log(), "校验审计日志")
+}
+
+/// 导出选中的备份，输出路径由前端通过对话框插件选择
+#[tauri::command]
+fn export_backups_command(request: ExportRequest) -> ApiResponse<ExportResult> {
+    let result = export_backups(&request);
+    if let Ok(export) = &result {
+        info!(
+            "已导出 {} 个备份到 {}（加密: {}）",
+            export.count,
+            export.path.display(),
+            export.encrypted
+        );
+    }
+    respond(result, "导出备份")
+}
+
+/// 导出诊断包，未指定路径时保存到数据目录；默认对机器码脱敏
+#[tauri::command]
+fn export_diagnostics_command(
+    path: Option<String>,
+    include_identifiers: Option<bool>,
+) -> ApiResponse<DiagnosticsExport> {
+    let result = export_diagnostics(
+        path.as_deref().map(Path::new),
+        include_identifiers.unwrap_or(false),
+    );
+    if let Ok(export) = &result {
+        info!("诊断包已导出: {}", export.path.display());
+    }
+    respond(result, "导出诊断包")
+}
+
+#[tauri::command]
+fn test_write_access_command() -> ApiResponse<PermissionCheckData> {
     match test_registry_write_access() {
-        Ok(_) => Ok(PermissionCheckResponse {
-            success: true,
+        Ok(_) => ApiResponse::ok(PermissionCheckData {
             has_permission: true,
+            check_success: true,
             method: "registry_write".to_string(),
             error_type: None,
             error_message: None,
             debug_info: None,
         }),
         Err(e) => {
-            let error_str = e.to_string();
-            let has_permission = !error_str.contains("权限不足");
-            Ok(PermissionCheckResponse {
-                success: false,
+            let has_permission = !matches!(e, BackupError::InsufficientPermissions);
+            ApiResponse::ok(PermissionCheckData {
                 has_permission,
+                check_success: false,
                 method: "registry_write".to_string(),
-                error_type: Some("registry_error".to_string()),
-                error_message: Some(error_str),
+                error_type: Some(ErrorCode::from(&e).as_str().to_string()),
+                error_message: Some(sanitize_error_for_user(&e)),
                 debug_info: None,
             })
         }
     }
 }
 
-#[derive(serde::Serialize)]
-struct RestartAsAdminResponse {
-    success: bool,
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct RestartAsAdminData {
     message: String,
     platform: String,
-    error: Option<String>,
+    /// 为 false 时无需重启，前端直接继续操作
+    restarting: bool,
 }
 
+/// `pending_action` 为用户正在进行的操作，重启后由新进程继续
 #[tauri::command]
-fn restart_as_admin_command() -> Result<RestartAsAdminResponse, String> {
-    info!("收到以管理员权限重启请求");
+fn restart_as_admin_command(
+    pending_action: Option<PendingAction>,
+) -> ApiResponse<RestartAsAdminData> {
+    info!(
+        "收到以管理员权限重启请求，待继续的操作: {:?}",
+        pending_action
+    );
 
-    match request_elevation() {
-        Ok(RestartResult {
-            success,
-            message,
-            platform,
-        }) => {
+    let result = request_elevation(pending_action).map(
+        |RestartResult {
+             message,
+             platform,
+             restarting,
+             ..
+         }| {
             info!("重启请求成功: {}", message);
-            Ok(RestartAsAdminResponse {
-                success,
+            RestartAsAdminData {
                 message,
                 platform,
-                error: None,
-            })
-        }
-        Err(e) => {
-            let error_msg = e.to_string();
-            error!("重启请求失败: {}", error_msg);
-            Ok(RestartAsAdminResponse {
-                success: false,
-                message: "重启失败".to_string(),
-                platform: std::env::consts::OS.to_string(),
-                error: Some(error_msg),
-            })
-        }
+                restarting,
+            }
+        },
+    );
+    if let Err(e) = &result {
+        error!("重启请求失败: {}", e);
     }
+    respond(result, "以管理员权限重启")
 }
 
-#[derive(serde::Serialize)]
-struct RestartStateResponse {
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct RestartStateData {
     was_restarted: bool,
+    #[ts(type = "number | null")]
     timestamp: Option<u64>,
     platform: Option<String>,
+    /// 重启前未完成的操作，经用户确认后继续
+    pending_action: Option<PendingAction>,
 }
 
+/// 领取重启状态，只能领取一次
 #[tauri::command]
-fn check_restart_state_command() -> Result<RestartStateResponse, String> {
-    match check_restart_state() {
+fn check_restart_state_command() -> ApiResponse<RestartStateData> {
+    let data = match take_restart_state() {
         Some(state) => {
             info!("检测到程序是从重启状态恢复");
-            Ok(RestartStateResponse {
-                was_restarted: state
-                    .get("was_restarted")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false),
-                timestamp: state.get("timestamp").and_then(|v| v.as_u64()),
-                platform: state
-                    .get("platform")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string()),
-            })
+            RestartStateData {
+                was_restarted: true,
+                timestamp: Some(state.timestamp),
+                platform: Some(state.platform),
+                pending_action: state.pending_action,
+            }
         }
-        None => Ok(RestartStateResponse {
+        None => RestartStateData {
             was_restarted: false,
             timestamp: None,
             platform: None,
+            pending_action: None,
+        },
+    };
+    ApiResponse::ok(data)
+}
+
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct ScheduleListData {
+    schedules: Vec<Schedule>,
+    count: usize,
+}
+
+#[tauri::command]
+fn list_schedules_command() -> ApiResponse<ScheduleListData> {
+    respond(
+        scheduler::list_schedules().map(|schedules| ScheduleListData {
+            count: schedules.len(),
+            schedules,
+        }),
+        "获取计划任务列表",
+    )
+}
+
+#[tauri::command]
+fn add_schedule_command(
+    name: Option<String>,
+    action: ScheduleAction,
+    trigger: ScheduleTrigger,
+) -> ApiResponse<Schedule> {
+    info!("添加计划任务: {:?} {:?}", action, trigger);
+    let name = truncate_description(name);
+    respond(
+        scheduler::add_schedule(name, action, trigger),
+        "添加计划任务",
+    )
+}
+
+#[tauri::command]
+fn set_schedule_paused_command(id: String, paused: bool) -> ApiResponse<Schedule> {
+    info!("设置计划任务暂停状态: {} paused={}", id, paused);
+    respond(
+        scheduler::set_schedule_paused(&id, paused),
+        "设置计划任务暂停状态",
+    )
+}
+
+#[tauri::command]
+fn remove_schedule_command(id: String) -> ApiResponse<Schedule> {
+    info!("删除计划任务: {}", id);
+    respond(scheduler::remove_schedule(&id), "删除计划任务")
+}
+
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct ProfileListData {
+    profiles: Vec<IdentityProfile>,
+    count: usize,
+}
+
+#[tauri::command]
+fn list_profiles_command() -> ApiResponse<ProfileListData> {
+    respond(
+        profiles::list_profiles().map(|profiles| ProfileListData {
+            count: profiles.len(),
+            profiles,
+        }),
+        "获取身份配置列表",
+    )
+}
+
+/// 创建身份配置，未提供标识符时保存当前系统的值
+#[tauri::command]
+fn create_profile_command(
+    name: String,
+    identifiers: Option<BTreeMap<String, String>>,
+    description: Option<String>,
+) -> ApiResponse<IdentityProfile> {
+    info!("创建身份配置: {}", name);
+    let description = truncate_description(description);
+    respond(
+        profiles::create_profile(&name, identifiers, description),
+        "创建身份配置",
+    )
+}
+
+#[tauri::command]
+fn update_profile_command(
+    name: String,
+    identifiers: Option<BTreeMap<String, String>>,
+    description: Option<String>,
+) -> ApiResponse<IdentityProfile> {
+    info!("更新身份配置: {}", name);
+    let description = truncate_description(description);
+    respond(
+        profiles::update_profile(&name, identifiers, description),
+        "更新身份配置",
+    )
+}
+
+#[tauri::command]
+fn delete_profile_command(name: String) -> ApiResponse<IdentityProfile> {
+    info!("删除身份配置: {}", name);
+    respond(profiles::delete_profile(&name), "删除身份配置")
+}
+
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct ApplyProfileData {
+    profile: IdentityProfile,
+    previous_guid: String,
+    new_guid: String,
+    pre_backup: Option<MachineIdBackup>,
+    post_backup: Option<MachineIdBackup>,
+    message: String,
+}
+
+#[tauri::command]
+fn apply_profile_command(name: String) -> ApiResponse<ApplyProfileData> {
+    info!("应用身份配置: {}", name);
+
+    if let Err(denied) = require_admin("应用身份配置") {
+        return denied;
+    }
+
+    let result = profiles::apply_profile(&name).map(
+        |ProfileApplyResult {
+             profile,
+             write:
+                 WriteResult {
+                     previous_guid,
+                     new_guid,
+                     pre_backup,
+                     post_backup,
+                 },
+         }| ApplyProfileData {
+            message: tr(Message::ProfileApplied {
+                name: &profile.name,
+            }),
+            profile,
+            previous_guid,
+            new_guid,
+            pre_backup,
+            post_backup,
+        },
+    );
+    respond(result, "应用身份配置")
+}
+
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct PendingRevertListData {
+    reverts: Vec<PendingRevert>,
+    count: usize,
+}
+
+#[tauri::command]
+fn list_pending_reverts_command() -> ApiResponse<PendingRevertListData> {
+    respond(
+        revert::list_pending_reverts().map(|reverts| PendingRevertListData {
+            count: reverts.len(),
+            reverts,
         }),
+        "获取自动恢复列表",
+    )
+}
+
+/// 取消自动恢复，临时写入的值将被保留
+#[tauri::command]
+fn cancel_pending_revert_command(id: String) -> ApiResponse<PendingRevert> {
+    info!("取消自动恢复: {}", id);
+    respond(revert::cancel_pending_revert(&id), "取消自动恢复")
+}
+
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct LocaleData {
+    /// 当前使用的语言
+    locale: Locale,
+    /// 支持的语言
+    available: Vec<Locale>,
+}
+
+fn locale_data() -> LocaleData {
+    LocaleData {
+        locale: i18n::current_locale(),
+        available: Locale::ALL.to_vec(),
+    }
+}
+
+/// 获取后端消息使用的语言
+#[tauri::command]
+fn get_locale_command() -> ApiResponse<LocaleData> {
+    ApiResponse::ok(locale_data())
+}
+
+/// 设置后端消息使用的语言并保存到用户配置，传入空值时恢复自动检测
+#[tauri::command]
+fn set_locale_command(app: AppHandle, locale: Option<Locale>) -> ApiResponse<LocaleData> {
+    info!("设置语言: {:?}", locale);
+    let result = settings::load_saved()
+        .and_then(|saved| settings::update(Settings { locale, ..saved }))
+        .map(|_| locale_data());
+    tray::refresh(&app);
+    respond(result, "设置语言")
+}
+
+#[derive(serde::Serialize, schemars::JsonSchema, ts_rs::TS)]
+#[ts(export)]
+struct SettingsData {
+    /// 合并所有配置层后实际生效的配置
+    settings: Settings,
+    /// 仅由配置文件决定的配置，编辑时以此为基础
+    saved: Settings,
+    /// 用户配置文件路径，修改的配置保存在此
+    user_config_path: Option<String>,
+    /// 系统配置文件路径
+    system_config_path: Option<String>,
+}
+
+fn settings_data() -> Result<SettingsData, BackupError> {
+    Ok(SettingsData {
+        settings: settings::current(),
+        saved: settings::load_saved()?,
+        user_config_path: settings::user_config_path(&SettingsOverrides::default())
+            .map(|p| p.display().to_string()),
+        system_config_path: settings::system_config_path().map(|p| p.display().to_string()),
+    })
+}
+
+/// 获取应用配置
+#[tauri::command]
+fn get_settings_command() -> ApiResponse<SettingsData> {
+    respond(settings_data(), "获取配置")
+}
+
+/// 校验并保存应用配置，日志级别修改后重启生效
+#[tauri::command]
+fn update_settings_command(app: AppHandle, settings: Settings) -> ApiResponse<SettingsData> {
+    info!("更新配置: {:?}", settings);
+    let result = settings::update(settings).and_then(|_| settings_data());
+    tray::refresh(&app);
+    respond(result, "更新配置")
+}
+
+/// 获取当前数据目录及便携、安装两种模式的位置
+#[tauri::command]
+fn get_data_dir_command() -> ApiResponse<DataDirInfo> {
+    respond(data_dir::resolve(&settings::current()), "获取数据目录")
+}
+
+/// 将备份、计划任务等数据迁移到指定模式的数据目录
+#[tauri::command]
+fn migrate_data_dir_command(mode: DataDirMode) -> ApiResponse<DataDirMigration> {
+    info!("迁移数据目录: {:?}", mode);
+    respond(data_dir::migrate_data_dir(mode), "迁移数据目录")
+}
+
+/// 领取启动参数中需要前端处理的请求（例如 `--restore <备份ID>`）
+#[tauri::command]
+fn take_launch_request_command(app: AppHandle) -> ApiResponse<Option<instance::LaunchRequest>> {
+    respond(Ok(instance::take_launch_request(&app)), "领取启动请求")
+}
+
+/// 将核心库的变更事件转发给所有窗口；处理不及时丢失事件时通知前端整体刷新
+async fn forward_change_events(app: AppHandle) {
+    let mut receiver = events::subscribe();
+    loop {
+        let event = match receiver.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("变更事件处理不及时，丢失 {} 条", skipped);
+                ChangeEvent::StoreReloaded {
+                    count: machine_id_get_backup_count().unwrap_or(0),
+                }
+            }
+            Err(RecvError::Closed) => break,
+        };
+        if let Err(e) = app.emit(event.name(), &event) {
+            error!("发送变更事件失败: {}", e);
+        }
+        if let ChangeEvent::IdentityChanged { .. } = event {
+            tray::refresh(&app);
+        }
     }
 }
 
@@ -661,6 +861,8 @@ mod tests {
 
     #[test]
     fn test_sanitize_error_for_user() {
+        i18n::set_locale(Some(Locale::ZhCn));
+
         // 测试权限错误
         let perm_error = BackupError::InsufficientPermissions;
         assert_eq!(
@@ -692,54 +894,139 @@ mod tests {
             sanitize_error_for_user(&unsupported_error),
             "当前操作系统不支持此功能"
         );
+
+        // 测试 GUID 生成耗尽错误
+        let exhausted_error = BackupError::GuidGenerationExhausted(32);
+        assert_eq!(
+            sanitize_error_for_user(&exhausted_error),
+            "无法生成未使用过的 GUID，请检查备份记录和黑名单"
+        );
     }
 
     #[test]
-    fn test_guid_length_validation() {
-        // 测试 GUID 长度常量
-        assert_eq!(GUID_LENGTH, 36);
-
-        // 测试有效 GUID 长度
-        let valid_guid = "550E8400-E29B-41D4-A716-446655440000";
-        assert_eq!(valid_guid.len(), GUID_LENGTH);
-
-        // 测试无效 GUID 长度
-        let short_guid = "550E8400-E29B-41D4-A716";
-        assert_ne!(short_guid.len(), GUID_LENGTH);
+    fn test_respond_uses_error_code_and_sanitized_message() {
+        i18n::set_locale(Some(Locale::ZhCn));
+        let response: ApiResponse<()> = respond(
+            Err(BackupError::StorageError("/secret/path".to_string())),
+            "测试",
+        );
+        assert!(!response.success);
+        let error = response.error.unwrap();
+        assert_eq!(error.code, ErrorCode::StorageError);
+        assert_eq!(error.message, "存储操作失败，请检查磁盘空间");
+        assert!(!error.message.contains("/secret/path"));
 
-        let long_guid = "550E8400-E29B-41D4-A716-4466554400000";
-        assert_ne!(long_guid.len(), GUID_LENGTH);
+        let response = respond(Ok(3), "测试");
+        assert!(response.success);
+        assert_eq!(response.data, Some(3));
     }
 
     #[test]
     fn test_description_length_limit() {
         // 测试描述长度限制
-        let long_description = "a".repeat(MAX_DESCRIPTION_LENGTH + 100);
-        let truncated: String = long_description
-            .chars()
-            .take(MAX_DESCRIPTION_LENGTH)
-            .collect();
-        assert_eq!(truncated.len(), MAX_DESCRIPTION_LENGTH);
+        let limit = Settings::default().max_description_length;
+        let long_description = "a".repeat(limit + 100);
+        let truncated = Settings::default()
+            .truncate_description(Some(long_description))
+            .unwrap();
+        assert_eq!(truncated.len(), limit);
     }
 }
 
-fn main() {
-    // 初始化日志
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+/// 日志的控制台输出位置
+#[derive(Clone, Copy)]
+enum LogConsole {
+    Stdout,
+    /// 命令行模式使用，避免与标准输出上的命令结果混在一起
+    Stderr,
+}
+
+/// 初始化日志：同时输出到控制台和数据目录下的轮转日志文件，设置了 RUST_LOG 时以其为准
+/// 发布版本没有控制台窗口，日志文件是唯一的记录；未开启调试模式时机器码经脱敏后写入
+fn init_logging(current_settings: &Settings, console: LogConsole) {
+    let redact = !current_settings.debug_mode;
+    let log_file = logging::open_log_file(current_settings);
+    let file_layer = log_file.as_ref().ok().cloned().map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(move || RedactingWriter::new(file.clone(), redact))
+    });
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                tracing_subscriber::EnvFilter::new(current_settings.log_level.as_str())
+            }),
+        )
+        .with(tracing_subscriber::fmt::layer().with_writer(move || {
+            let writer: Box<dyn std::io::Write> = match console {
+                LogConsole::Stdout => Box::new(std::io::stdout()),
+                LogConsole::Stderr => Box::new(std::io::stderr()),
+            };
+            RedactingWriter::new(writer, redact)
+        }))
+        .with(file_layer)
         .init();
 
+    if let Err(e) = log_file {
+        warn!("无法打开日志文件，日志仅输出到控制台: {}", e);
+    }
+    if !redact {
+        warn!("调试模式已开启，日志中将记录完整的机器码");
+    }
+}
+
+fn main() {
+    // 以子命令启动时进入命令行模式，不初始化任何窗口
+    let args: Vec<String> = std::env::args().collect();
+    if cli::is_cli_invocation(&args) {
+        std::process::exit(cli::run(&args));
+    }
+
+    // 加载配置，失败时使用默认配置继续启动
+    let settings_result = settings::init(SettingsOverrides::default());
+    init_logging(&settings::current(), LogConsole::Stdout);
+    if let Err(e) = settings_result {
+        warn!("加载配置失败，使用默认配置: {}", e);
+    }
+
     info!("MachineID-Manage v2.0 启动");
 
-    // 检查是否是重启后的状态
-    if let Some(state) = check_restart_state() {
-        info!("程序是从权限提升重启后启动的: {:?}", state);
+    // 提权重启时等原进程退出后再继续，避免两个进程同时写入
+    if !instance::wait_for_handoff(&args) {
+        error!("原进程未在限定时间内退出，放弃启动");
+        std::process::exit(1);
     }
 
     tauri::Builder::default()
+        // 单实例插件需最先注册：再次启动时聚焦已有窗口并转发启动参数
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            instance::handle_launch(app, &argv);
+        }))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(instance::PendingLaunch::default())
+        .setup(move |app| {
+            tauri::async_runtime::spawn(scheduler::run_scheduler());
+            tauri::async_runtime::spawn(revert::run_revert_timer());
+            tauri::async_runtime::spawn(events::run_change_monitor());
+            tauri::async_runtime::spawn(watcher::run_identity_watcher());
+            tauri::async_runtime::spawn(forward_change_events(app.handle().clone()));
+            tray::create(app.handle())?;
+            instance::handle_launch(app.handle(), &args);
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            // 开启最小化到托盘时，关闭主窗口只隐藏，通过托盘菜单退出
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                if window.label() == tray::MAIN_WINDOW && settings::current().minimize_to_tray {
+                    api.prevent_close();
+                    if let Err(e) = window.hide() {
+                        warn!("隐藏主窗口失败: {}", e);
+                    }
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             read_machine_id,
@@ -752,12 +1039,35 @@ fn main() {
             generate_random_guid_command,
             restore_backup_by_id_command,
             check_permission_command,
+            check_capabilities_command,
+            query_audit_log_command,
+            verify_audit_log_command,
+            export_diagnostics_command,
+            export_backups_command,
             test_write_access_command,
             restart_as_admin_command,
             check_restart_state_command,
             get_app_version,
             preview_random_guid_command,
-            update_backup_description_command
+            update_backup_description_command,
+            list_schedules_command,
+            add_schedule_command,
+            set_schedule_paused_command,
+            remove_schedule_command,
+            list_profiles_command,
+            create_profile_command,
+            update_profile_command,
+            delete_profile_command,
+            apply_profile_command,
+            list_pending_reverts_command,
+            cancel_pending_revert_command,
+            get_locale_command,
+            set_locale_command,
+            get_settings_command,
+            update_settings_command,
+            get_data_dir_command,
+            migrate_data_dir_command,
+            take_launch_request_command
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
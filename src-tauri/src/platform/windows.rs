@@ -1,2 +0,0 @@
-// Windows 平台特定实现
-// 目前权限检测逻辑在 permissions.rs 中统一处理
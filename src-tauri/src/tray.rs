@@ -0,0 +1,307 @@
+//! 系统托盘
+//!
+//! 托盘菜单显示当前机器码（点击复制）和权限状态，并提供立即备份、生成并应用、恢复上一个备份等快捷操作。
+//! 开启 `minimize_to_tray` 时关闭主窗口只隐藏到托盘，程序保持常驻，通过托盘菜单的“退出”结束。
+//! 会修改机器码的快捷操作先弹出系统确认框，列出当前值和目标值，用户确认后才写入。
+//! 快捷操作成功后界面通过变更事件同步，失败时显示主窗口并发送 `tray-action-failed` 事件。
+
+use machineid_core::i18n::{self, tr, Message};
+use machineid_core::machine_id::{
+    backup_current_machine_guid, generate_random_machine_guid, list_backups, normalize_identity,
+    read_machine_guid, read_machine_guid_audited, restore_backup_by_id, BackupError,
+    MachineIdBackup,
+};
+use machineid_core::platform::permissions::check_admin_permissions;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tracing::{error, info, warn};
+
+/// 托盘图标 ID
+const TRAY_ID: &str = "main";
+/// 主窗口标签
+pub const MAIN_WINDOW: &str = "main";
+/// 快捷操作失败时发送给前端的事件
+pub const TRAY_ACTION_FAILED_EVENT: &str = "tray-action-failed";
+
+const MENU_GUID: &str = "tray-guid";
+const MENU_PERMISSION: &str = "tray-permission";
+const MENU_BACKUP: &str = "tray-backup";
+const MENU_GENERATE: &str = "tray-generate";
+const MENU_RESTORE_LAST: &str = "tray-restore-last";
+const MENU_SHOW: &str = "tray-show";
+const MENU_QUIT: &str = "tray-quit";
+
+/// 托盘快捷操作失败的通知
+#[derive(Clone, serde::Serialize, ts_rs::TS)]
+#[ts(export)]
+pub struct TrayActionFailed {
+    /// 操作名称，使用当前语言
+    action: String,
+    /// 脱敏后的错误提示
+    message: String,
+}
+
+/// 托盘菜单项，语言或状态变化时更新文字
+struct TrayMenu {
+    guid: MenuItem,
+    permission: MenuItem,
+    backup: MenuItem,
+    generate: MenuItem,
+    restore_last: MenuItem,
+    show: MenuItem,
+    quit: MenuItem,
+}
+
+fn guid_text(guid: Option<&str>) -> String {
+    match guid {
+        Some(guid) => tr(Message::TrayCurrentGuid { guid }),
+        None => tr(Message::TrayGuidUnavailable),
+    }
+}
+
+fn permission_text() -> String {
+    if check_admin_permissions().has_permission {
+        tr(Message::TrayPermissionGranted)
+    } else {
+        tr(Message::TrayPermissionDenied)
+    }
+}
+
+fn tooltip_text(guid: Option<&str>) -> String {
+    match guid {
+        Some(guid) => format!("MachineID-Manage\n{}", guid),
+        None => "MachineID-Manage".to_string(),
+    }
+}
+
+/// 创建托盘图标和菜单
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let guid = read_machine_guid().ok().map(|id| id.guid);
+    let item = |id: &str, text: String, enabled: bool| {
+        MenuItem::with_id(app, id, text, enabled, None::<&str>)
+    };
+    let menu = TrayMenu {
+        guid: item(MENU_GUID, guid_text(guid.as_deref()), true)?,
+        permission: item(MENU_PERMISSION, permission_text(), false)?,
+        backup: item(MENU_BACKUP, tr(Message::TrayBackupNow), true)?,
+        generate: item(MENU_GENERATE, tr(Message::TrayGenerateAndApply), true)?,
+        restore_last: item(MENU_RESTORE_LAST, tr(Message::TrayRestoreLast), true)?,
+        show: item(MENU_SHOW, tr(Message::TrayShowWindow), true)?,
+        quit: item(MENU_QUIT, tr(Message::TrayQuit), true)?,
+    };
+    let tray_menu = Menu::with_items(
+        app,
+        &[
+            &menu.guid,
+            &menu.permission,
+            &PredefinedMenuItem::separator(app)?,
+            &menu.backup,
+            &menu.generate,
+            &menu.restore_last,
+            &PredefinedMenuItem::separator(app)?,
+            &menu.show,
+            &menu.quit,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(tooltip_text(guid.as_deref()))
+        .menu(&tray_menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| handle_menu_event(app, event.id.as_ref()))
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    app.manage(menu);
+    Ok(())
+}
+
+/// 按当前机器码、权限和语言更新托盘
+pub fn refresh(app: &AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let guid = read_machine_guid().ok().map(|id| id.guid);
+    let results = [
+        menu.guid.set_text(guid_text(guid.as_deref())),
+        menu.permission.set_text(permission_text()),
+        menu.backup.set_text(tr(Message::TrayBackupNow)),
+        menu.generate.set_text(tr(Message::TrayGenerateAndApply)),
+        menu.restore_last.set_text(tr(Message::TrayRestoreLast)),
+        menu.show.set_text(tr(Message::TrayShowWindow)),
+        menu.quit.set_text(tr(Message::TrayQuit)),
+    ];
+    let tooltip = app
+        .tray_by_id(TRAY_ID)
+        .map(|tray| tray.set_tooltip(Some(tooltip_text(guid.as_deref()))));
+    for result in results.into_iter().chain(tooltip) {
+        if let Err(e) = result {
+            warn!("更新托盘失败: {}", e);
+        }
+    }
+}
+
+/// 显示并聚焦主窗口
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let result = window
+            .unminimize()
+            .and_then(|_| window.show())
+            .and_then(|_| window.set_focus());
+        if let Err(e) = result {
+            warn!("显示主窗口失败: {}", e);
+        }
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        MENU_GUID => copy_guid(app),
+        MENU_BACKUP => run_action(app, tr(Message::TrayBackupNow), backup_now),
+        MENU_GENERATE => run_action(app, tr(Message::TrayGenerateAndApply), generate_and_apply),
+        MENU_RESTORE_LAST => run_action(app, tr(Message::TrayRestoreLast), restore_last_backup),
+        MENU_SHOW => show_main_window(app),
+        MENU_QUIT => {
+            info!("从托盘退出");
+            app.exit(0);
+        }
+        _ => {}
+    }
+}
+
+fn copy_guid(app: &AppHandle) {
+    match read_machine_guid_audited() {
+        Ok(id) => {
+            if let Err(e) = app.clipboard().write_text(id.guid) {
+                warn!("复制机器码失败: {}", e);
+            }
+        }
+        Err(e) => warn!("读取机器码失败: {}", e),
+    }
+}
+
+/// 在后台线程执行快捷操作，失败时显示主窗口并通知前端
+fn run_action(app: &AppHandle, action: String, run: fn(&AppHandle) -> Result<(), BackupError>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        info!("托盘操作: {}", action);
+        if let Err(e) = run(&app) {
+            warn!("托盘操作失败: {}: {}", action, e);
+            show_main_window(&app);
+            let failed = TrayActionFailed {
+                action,
+                message: i18n::error_summary(&e, i18n::current_locale()),
+            };
+            if let Err(e) = app.emit(TRAY_ACTION_FAILED_EVENT, failed) {
+                error!("发送托盘操作失败事件失败: {}", e);
+            }
+        }
+    });
+}
+
+/// 弹出系统确认框并等待用户选择，只能在后台线程调用
+fn confirm(app: &AppHandle, message: String) -> bool {
+    let confirmed = app
+        .dialog()
+        .message(message)
+        .title(tr(Message::TrayConfirmTitle))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            tr(Message::TrayConfirmOk),
+            tr(Message::TrayConfirmCancel),
+        ))
+        .blocking_show();
+    if !confirmed {
+        info!("用户取消了托盘操作");
+    }
+    confirmed
+}
+
+fn backup_now(_app: &AppHandle) -> Result<(), BackupError> {
+    backup_current_machine_guid(None).map(|_| ())
+}
+
+fn generate_and_apply(app: &AppHandle) -> Result<(), BackupError> {
+    if !check_admin_permissions().has_permission {
+        return Err(BackupError::InsufficientPermissions);
+    }
+    let current = read_machine_guid()?.guid;
+    if !confirm(app, tr(Message::TrayConfirmGenerate { current: &current })) {
+        return Ok(());
+    }
+    generate_random_machine_guid(None).map(|_| ())
+}
+
+/// 最近一次与当前机器码不同的备份
+fn last_restorable<'a>(
+    backups: &'a [MachineIdBackup],
+    current: &str,
+) -> Option<&'a MachineIdBackup> {
+    backups
+        .iter()
+        .filter(|backup| normalize_identity(&backup.guid) != normalize_identity(current))
+        .max_by_key(|backup| backup.timestamp)
+}
+
+fn restore_last_backup(app: &AppHandle) -> Result<(), BackupError> {
+    if !check_admin_permissions().has_permission {
+        return Err(BackupError::InsufficientPermissions);
+    }
+    let current = read_machine_guid()?.guid;
+    let backups = list_backups()?;
+    let last = last_restorable(&backups, &current)
+        .ok_or_else(|| BackupError::BackupNotFound(String::new()))?;
+    let message = tr(Message::TrayConfirmRestore {
+        current: &current,
+        guid: &last.guid,
+    });
+    if !confirm(app, message) {
+        return Ok(());
+    }
+    restore_backup_by_id(&last.id).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup(id: &str, guid: &str, timestamp: u64) -> MachineIdBackup {
+        MachineIdBackup {
+            id: id.to_string(),
+            guid: guid.to_string(),
+            source: "test".to_string(),
+            timestamp,
+            description: None,
+            auto_description: None,
+        }
+    }
+
+    #[test]
+    fn test_last_restorable_skips_current_guid() {
+        let current = "550E8400-E29B-41D4-A716-446655440000";
+        let backups = vec![
+            backup("backup_1", "12345678-1234-1234-1234-123456789012", 100),
+            backup("backup_2", "AAAAAAAA-BBBB-CCCC-DDDD-EEEEEEEEEEEE", 200),
+            backup("backup_3", &current.to_lowercase(), 300),
+        ];
+        assert_eq!(
+            last_restorable(&backups, current).map(|b| b.id.as_str()),
+            Some("backup_2")
+        );
+        assert!(last_restorable(&backups[2..], current).is_none());
+    }
+}
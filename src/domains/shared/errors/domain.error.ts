@@ -55,12 +55,17 @@ export class PermissionError extends DomainError {
 
 /**
  * 操作错误
+ * `errorCode` 为后端返回的机器可读错误码（如 `insufficient_permissions`），可据此区分处理
  */
 export class OperationError extends DomainError {
   readonly code = 'OPERATION_FAILED';
   readonly statusCode = 500;
 
-  constructor(message: string) {
+  constructor(
+    message: string,
+    readonly errorCode?: string,
+    readonly details?: Record<string, unknown> | null
+  ) {
     super(message);
     Object.setPrototypeOf(this, OperationError.prototype);
   }
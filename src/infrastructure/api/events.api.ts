@@ -0,0 +1,58 @@
+/**
+ * 后端变更事件订阅
+ *
+ * 机器码或备份存储发生变化时（包括命令行、守护进程等其他进程造成的变化），
+ * 后端向所有窗口广播 {@link ChangeEvent}，事件名称与其 `type` 字段一致
+ */
+
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { ChangeEvent, TrayActionFailed } from '@/types/api';
+
+/**
+ * 变更事件名称
+ */
+export const CHANGE_EVENTS = [
+  'identity-changed',
+  'backup-added',
+  'backup-deleted',
+  'store-reloaded',
+  'identity-alert',
+] as const satisfies readonly ChangeEvent['type'][];
+
+/**
+ * 订阅所有变更事件，返回取消订阅的函数
+ */
+export async function listenChangeEvents(
+  handler: (event: ChangeEvent) => void
+): Promise<UnlistenFn> {
+  const unlisteners = await Promise.all(
+    CHANGE_EVENTS.map((name) => listen<ChangeEvent>(name, (event) => handler(event.payload)))
+  );
+  return () => unlisteners.forEach((unlisten) => unlisten());
+}
+
+/**
+ * 启动请求事件名称，再次启动程序并带有参数（例如 `--restore <备份ID>`）时发送
+ */
+export const LAUNCH_REQUEST_EVENT = 'launch-request';
+
+/**
+ * 订阅启动请求事件，请求内容需通过 `take_launch_request_command` 领取
+ */
+export async function listenLaunchRequest(handler: () => void): Promise<UnlistenFn> {
+  return listen(LAUNCH_REQUEST_EVENT, () => handler());
+}
+
+/**
+ * 托盘快捷操作失败事件名称
+ */
+export const TRAY_ACTION_FAILED_EVENT = 'tray-action-failed';
+
+/**
+ * 订阅托盘快捷操作失败事件，后端会先显示主窗口再发送
+ */
+export async function listenTrayActionFailed(
+  handler: (failed: TrayActionFailed) => void
+): Promise<UnlistenFn> {
+  return listen<TrayActionFailed>(TRAY_ACTION_FAILED_EVENT, (event) => handler(event.payload));
+}
@@ -1,159 +1,61 @@
 /**
  * Tauri API 封装
  * 封装与 Tauri 后端的通信
+ *
+ * 所有命令统一返回 {@link ApiResponse}，数据类型由后端通过 ts-rs 生成，位于 `src/types/generated`
  */
 
 import { invoke } from '@tauri-apps/api/core';
 import { Result, AsyncResult } from '@domains/shared/types/result.type';
 import { OperationError } from '@domains/shared/errors/domain.error';
+import type { ApiResponse } from '@/types/api';
 
 /**
- * Tauri 命令调用结果
+ * 构造内部错误响应
  */
-interface TauriResult<T> {
-  success: boolean;
-  data?: T;
-  error?: string;
+function internalError<T>(message: string): ApiResponse<T> {
+  return {
+    success: false,
+    data: null,
+    error: { code: 'internal', message, details: null },
+  };
 }
 
 /**
- * 调用 Tauri 命令
+ * 调用 Tauri 命令，返回统一响应外壳
+ * 调用本身抛出的异常（命令不存在、参数反序列化失败等）及格式不符的返回值转换为 `internal` 错误
  */
-export async function invokeTauri<T>(
+export async function invokeCommand<T>(
   command: string,
   args?: Record<string, unknown>
-): AsyncResult<T, OperationError> {
+): Promise<ApiResponse<T>> {
   try {
-    const result = await invoke<TauriResult<T>>(command, args);
-
-    if (result.success) {
-      return Result.success(result.data as T);
-    } else {
-      return Result.failure(new OperationError(result.error || '操作失败'));
+    const result = await invoke<ApiResponse<T> | undefined>(command, args);
+    if (!result || typeof result.success !== 'boolean') {
+      return internalError(`命令 ${command} 返回了无法识别的响应`);
     }
+    return result;
   } catch (error) {
     const message = error instanceof Error ? error.message : String(error);
-    return Result.failure(new OperationError(message));
+    return internalError(message);
   }
 }
 
 /**
- * 调用 Tauri 命令（原始格式）
+ * 调用 Tauri 命令
  */
-export async function invokeTauriRaw<T>(
+export async function invokeTauri<T>(
   command: string,
   args?: Record<string, unknown>
-): Promise<T> {
-  return await invoke<T>(command, args);
-}
-
-/**
- * 读取机器码命令结果
- */
-export interface ReadMachineIdCommandResult {
-  success: boolean;
-  guid: string;
-  source: string;
-  error?: string;
-}
-
-/**
- * 写入机器码命令结果
- */
-export interface WriteMachineIdCommandResult {
-  success: boolean;
-  previousGuid: string;
-  newGuid: string;
-  preBackup?: {
-    id: string;
-    guid: string;
-    source: string;
-    timestamp: number;
-    description?: string;
-  };
-  postBackup?: {
-    id: string;
-    guid: string;
-    source: string;
-    timestamp: number;
-    description?: string;
-  };
-  message?: string;
-  error?: string;
-}
-
-/**
- * 权限检查命令结果
- */
-export interface CheckPermissionCommandResult {
-  success: boolean;
-  hasPermission: boolean;
-  error?: string;
-}
-
-/**
- * 备份列表命令结果
- */
-export interface ListBackupsCommandResult {
-  success: boolean;
-  backups: Array<{
-    id: string;
-    guid: string;
-    source: string;
-    timestamp: number;
-    description?: string;
-  }>;
-  count: number;
-  error?: string;
-}
-
-/**
- * 创建备份命令结果
- */
-export interface CreateBackupCommandResult {
-  success: boolean;
-  backup?: {
-    id: string;
-    guid: string;
-    source: string;
-    timestamp: number;
-    description?: string;
-  };
-  skipped: boolean;
-  error?: string;
-}
-
-/**
- * 删除备份命令结果
- */
-export interface DeleteBackupCommandResult {
-  success: boolean;
-  error?: string;
-}
+): AsyncResult<T, OperationError> {
+  const result = await invokeCommand<T>(command, args);
 
-/**
- * 恢复备份命令结果
- */
-export interface RestoreBackupCommandResult {
-  success: boolean;
-  previousGuid: string;
-  restoredGuid: string;
-  preBackup?: {
-    id: string;
-    guid: string;
-    source: string;
-    timestamp: number;
-    description?: string;
-  };
-  restoredFrom: {
-    id: string;
-    guid: string;
-    source: string;
-    timestamp: number;
-    description?: string;
-  };
-  message?: string;
-  error?: string;
+  if (result.success) {
+    return Result.success(result.data as T);
+  }
+  return Result.failure(
+    new OperationError(result.error?.message || '操作失败', result.error?.code, result.error?.details)
+  );
 }
 
 /**
@@ -169,4 +71,11 @@ export const TAURI_COMMANDS = {
   DELETE_BACKUP: 'delete_backup_by_id',
   CLEAR_BACKUPS: 'clear_all_backups',
   RESTORE_BACKUP: 'restore_backup_by_id_command',
+  GET_LOCALE: 'get_locale_command',
+  SET_LOCALE: 'set_locale_command',
+  GET_SETTINGS: 'get_settings_command',
+  UPDATE_SETTINGS: 'update_settings_command',
+  GET_DATA_DIR: 'get_data_dir_command',
+  MIGRATE_DATA_DIR: 'migrate_data_dir_command',
+  TAKE_LAUNCH_REQUEST: 'take_launch_request_command',
 } as const;
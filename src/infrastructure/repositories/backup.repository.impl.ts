@@ -10,14 +10,8 @@ import { Backup } from '@domains/backup/entities/backup.entity';
 import { BackupId } from '@domains/backup/value-objects/backup-id.vo';
 import { MachineId } from '@domains/machine-id/entities/machine-id.entity';
 import { Guid } from '@domains/machine-id/value-objects/guid.vo';
-import {
-  invokeTauriRaw,
-  ListBackupsCommandResult,
-  CreateBackupCommandResult,
-  DeleteBackupCommandResult,
-  RestoreBackupCommandResult,
-  TAURI_COMMANDS,
-} from '../api/tauri.api';
+import type { BackupData, BackupListData, RestoreBackupData } from '@/types/api';
+import { invokeTauri, TAURI_COMMANDS } from '../api/tauri.api';
 
 export class BackupRepositoryImpl implements IBackupRepository {
   /**
@@ -25,13 +19,13 @@ export class BackupRepositoryImpl implements IBackupRepository {
    */
   async findAll(): AsyncResult<BackupListResult, OperationError> {
     try {
-      const result = await invokeTauriRaw<ListBackupsCommandResult>(
+      const response = await invokeTauri<BackupListData>(
         TAURI_COMMANDS.LIST_BACKUPS
       );
-
-      if (!result.success) {
-        return Result.failure(new OperationError(result.error || '加载备份失败'));
+      if (response.isFailure()) {
+        return Result.failure(response.getError());
       }
+      const result = response.getValue();
 
       const backups: Backup[] = [];
       for (const data of result.backups) {
@@ -49,7 +43,7 @@ export class BackupRepositoryImpl implements IBackupRepository {
           id: BackupId.fromString(data.id),
           machineId,
           timestamp: new Date(data.timestamp * 1000),
-          description: data.description,
+          description: data.description ?? undefined,
         });
 
         backups.push(backup);
@@ -83,14 +77,14 @@ export class BackupRepositoryImpl implements IBackupRepository {
     description?: string
   ): AsyncResult<CreateBackupResult, OperationError> {
     try {
-      const result = await invokeTauriRaw<CreateBackupCommandResult>(
+      const response = await invokeTauri<BackupData>(
         TAURI_COMMANDS.CREATE_BACKUP,
         { description }
       );
-
-      if (!result.success) {
-        return Result.failure(new OperationError(result.error || '备份失败'));
+      if (response.isFailure()) {
+        return Result.failure(response.getError());
       }
+      const result = response.getValue();
 
       if (result.skipped) {
         return Result.success({
@@ -121,7 +115,7 @@ export class BackupRepositoryImpl implements IBackupRepository {
         id: BackupId.fromString(result.backup.id),
         machineId: backupMachineId,
         timestamp: new Date(result.backup.timestamp * 1000),
-        description: result.backup.description,
+        description: result.backup.description ?? undefined,
       });
 
       return Result.success({ backup, skipped: false });
@@ -136,13 +130,12 @@ export class BackupRepositoryImpl implements IBackupRepository {
    */
   async delete(id: BackupId): AsyncResult<void, OperationError> {
     try {
-      const result = await invokeTauriRaw<DeleteBackupCommandResult>(
+      const response = await invokeTauri<null>(
         TAURI_COMMANDS.DELETE_BACKUP,
         { id: id.value }
       );
-
-      if (!result.success) {
-        return Result.failure(new OperationError(result.error || '删除备份失败'));
+      if (response.isFailure()) {
+        return Result.failure(response.getError());
       }
 
       return Result.success(undefined);
@@ -157,12 +150,11 @@ export class BackupRepositoryImpl implements IBackupRepository {
    */
   async clearAll(): AsyncResult<void, OperationError> {
     try {
-      const result = await invokeTauriRaw<DeleteBackupCommandResult>(
+      const response = await invokeTauri<null>(
         TAURI_COMMANDS.CLEAR_BACKUPS
       );
-
-      if (!result.success) {
-        return Result.failure(new OperationError(result.error || '清空备份失败'));
+      if (response.isFailure()) {
+        return Result.failure(response.getError());
       }
 
       return Result.success(undefined);
@@ -177,17 +169,17 @@ export class BackupRepositoryImpl implements IBackupRepository {
    */
   async restore(id: BackupId): AsyncResult<RestoreBackupResult, OperationError> {
     try {
-      const result = await invokeTauriRaw<RestoreBackupCommandResult>(
+      const response = await invokeTauri<RestoreBackupData>(
         TAURI_COMMANDS.RESTORE_BACKUP,
         { id: id.value }
       );
-
-      if (!result.success) {
-        return Result.failure(new OperationError(result.error || '恢复备份失败'));
+      if (response.isFailure()) {
+        return Result.failure(response.getError());
       }
+      const result = response.getValue();
 
-      const previousGuidResult = Guid.create(result.previousGuid);
-      const restoredGuidResult = Guid.create(result.restoredGuid);
+      const previousGuidResult = Guid.create(result.previous_guid);
+      const restoredGuidResult = Guid.create(result.restored_guid);
 
       if (previousGuidResult.isFailure() || restoredGuidResult.isFailure()) {
         return Result.failure(new OperationError('返回的 GUID 格式无效'));
@@ -204,17 +196,17 @@ export class BackupRepositoryImpl implements IBackupRepository {
       });
 
       let preBackup: Backup | undefined;
-      if (result.preBackup) {
-        const preGuidResult = Guid.create(result.preBackup.guid);
+      if (result.pre_backup) {
+        const preGuidResult = Guid.create(result.pre_backup.guid);
         if (preGuidResult.isSuccess()) {
           preBackup = new Backup({
-            id: BackupId.fromString(result.preBackup.id),
+            id: BackupId.fromString(result.pre_backup.id),
             machineId: new MachineId({
               guid: preGuidResult.getValue(),
-              source: result.preBackup.source,
+              source: result.pre_backup.source,
             }),
-            timestamp: new Date(result.preBackup.timestamp * 1000),
-            description: result.preBackup.description,
+            timestamp: new Date(result.pre_backup.timestamp * 1000),
+            description: result.pre_backup.description ?? undefined,
           });
         }
       }
@@ -8,13 +8,13 @@ import { OperationError } from '@domains/shared/errors/domain.error';
 import { IMachineIdRepository, ReadMachineIdResult, WriteMachineIdResult, PermissionCheckResult } from '@domains/machine-id/repositories/machine-id.repository';
 import { MachineId } from '@domains/machine-id/entities/machine-id.entity';
 import { Guid } from '@domains/machine-id/value-objects/guid.vo';
-import {
-  invokeTauriRaw,
-  ReadMachineIdCommandResult,
-  WriteMachineIdCommandResult,
-  CheckPermissionCommandResult,
-  TAURI_COMMANDS,
-} from '../api/tauri.api';
+import type {
+  MachineIdData,
+  WriteGuidData,
+  GenerateRandomGuidData,
+  PermissionCheckData,
+} from '@/types/api';
+import { invokeTauri, TAURI_COMMANDS } from '../api/tauri.api';
 
 export class MachineIdRepositoryImpl implements IMachineIdRepository {
   /**
@@ -22,13 +22,11 @@ export class MachineIdRepositoryImpl implements IMachineIdRepository {
    */
   async read(): AsyncResult<ReadMachineIdResult, OperationError> {
     try {
-      const result = await invokeTauriRaw<ReadMachineIdCommandResult>(
-        TAURI_COMMANDS.READ_MACHINE_ID
-      );
-
-      if (!result.success) {
-        return Result.failure(new OperationError(result.error || '读取机器码失败'));
+      const response = await invokeTauri<MachineIdData>(TAURI_COMMANDS.READ_MACHINE_ID);
+      if (response.isFailure()) {
+        return Result.failure(response.getError());
       }
+      const result = response.getValue();
 
       const guidResult = Guid.create(result.guid);
       if (guidResult.isFailure()) {
@@ -55,17 +53,17 @@ export class MachineIdRepositoryImpl implements IMachineIdRepository {
     description?: string
   ): AsyncResult<WriteMachineIdResult, OperationError> {
     try {
-      const result = await invokeTauriRaw<WriteMachineIdCommandResult>(
+      const response = await invokeTauri<WriteGuidData>(
         TAURI_COMMANDS.WRITE_MACHINE_GUID,
         { newGuid: guid.value, description }
       );
-
-      if (!result.success) {
-        return Result.failure(new OperationError(result.error || '写入机器码失败'));
+      if (response.isFailure()) {
+        return Result.failure(response.getError());
       }
+      const result = response.getValue();
 
-      const previousGuidResult = Guid.create(result.previousGuid);
-      const newGuidResult = Guid.create(result.newGuid);
+      const previousGuidResult = Guid.create(result.previous_guid);
+      const newGuidResult = Guid.create(result.new_guid);
 
       if (previousGuidResult.isFailure() || newGuidResult.isFailure()) {
         return Result.failure(new OperationError('返回的 GUID 格式无效'));
@@ -98,17 +96,17 @@ export class MachineIdRepositoryImpl implements IMachineIdRepository {
     description?: string
   ): AsyncResult<WriteMachineIdResult, OperationError> {
     try {
-      const result = await invokeTauriRaw<WriteMachineIdCommandResult>(
+      const response = await invokeTauri<GenerateRandomGuidData>(
         TAURI_COMMANDS.GENERATE_RANDOM_GUID,
         { description }
       );
-
-      if (!result.success) {
-        return Result.failure(new OperationError(result.error || '生成机器码失败'));
+      if (response.isFailure()) {
+        return Result.failure(response.getError());
       }
+      const result = response.getValue();
 
-      const previousGuidResult = Guid.create(result.previousGuid);
-      const newGuidResult = Guid.create(result.newGuid);
+      const previousGuidResult = Guid.create(result.previous_guid);
+      const newGuidResult = Guid.create(result.new_guid);
 
       if (previousGuidResult.isFailure() || newGuidResult.isFailure()) {
         return Result.failure(new OperationError('返回的 GUID 格式无效'));
@@ -139,15 +137,19 @@ export class MachineIdRepositoryImpl implements IMachineIdRepository {
    */
   async checkPermission(): AsyncResult<PermissionCheckResult, OperationError> {
     try {
-      const result = await invokeTauriRaw<CheckPermissionCommandResult>(
-        TAURI_COMMANDS.CHECK_PERMISSION
-      );
+      const response = await invokeTauri<PermissionCheckData>(TAURI_COMMANDS.CHECK_PERMISSION);
+      if (response.isFailure()) {
+        return Result.failure(response.getError());
+      }
+      const result = response.getValue();
 
-      if (!result.success) {
-        return Result.failure(new OperationError(result.error || '权限检查失败'));
+      if (!result.check_success) {
+        return Result.failure(
+          new OperationError(result.error_message || '权限检查失败', result.error_type ?? undefined)
+        );
       }
 
-      return Result.success({ hasPermission: result.hasPermission });
+      return Result.success({ hasPermission: result.has_permission });
     } catch (error) {
       const message = error instanceof Error ? error.message : String(error);
       return Result.failure(new OperationError(`权限检查失败: ${message}`));
@@ -0,0 +1,50 @@
+/**
+ * useElevation 组合式函数
+ * 在缺少管理员权限时请求提权，并把当前操作交给重启后的新进程继续
+ */
+
+import { useDialogStore, useMachineIdStore } from '@stores';
+import type { PendingAction } from '@/types/api';
+
+export interface UseElevationReturn {
+  requestElevation: (message: string, pendingAction?: PendingAction) => Promise<boolean>;
+}
+
+export function useElevation(): UseElevationReturn {
+  const dialogStore = useDialogStore();
+  const machineIdStore = useMachineIdStore();
+
+  /**
+   * 询问用户是否以管理员身份重启
+   *
+   * 返回 true 表示已获得权限且无需重启（例如 Linux 通过 polkit 辅助程序写入），调用方应直接继续操作；
+   * 返回 false 表示用户取消、提权失败或程序即将重启，调用方应停止当前操作。
+   */
+  async function requestElevation(message: string, pendingAction?: PendingAction): Promise<boolean> {
+    const confirmed = await dialogStore.showConfirm({
+      title: '需要管理员权限',
+      message: `${message}是否以管理员身份重启程序？`,
+      confirmText: '是，重启程序',
+      cancelText: '取消',
+      variant: 'default',
+      type: 'permission',
+    });
+    if (!confirmed) return false;
+
+    const result = await machineIdStore.restartAsAdmin(pendingAction);
+    if (!result.success) {
+      await dialogStore.showError('重启失败', result.error || '无法以管理员身份重启程序');
+      return false;
+    }
+    if (!result.data?.restarting) {
+      return true;
+    }
+    // 程序将在 500ms 后自动退出，新进程启动后会询问是否继续当前操作
+    await dialogStore.showSuccess('正在重启', '程序将以管理员身份重启，重启后可继续当前操作');
+    return false;
+  }
+
+  return {
+    requestElevation,
+  };
+}
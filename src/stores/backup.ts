@@ -1,9 +1,19 @@
 import { defineStore } from 'pinia';
 import { ref, computed } from 'vue';
-import { invoke } from '@tauri-apps/api/core';
 import { format } from 'date-fns';
 import { zhCN } from 'date-fns/locale';
+import { invokeCommand } from '@infrastructure/api/tauri.api';
 import type { BackupItem, OperationResult, RestoreResult } from '../types/index';
+import type {
+  BackupData,
+  BackupListData,
+  ChangeEvent,
+  ExportRequest,
+  ExportResult,
+  MachineIdBackup,
+  MachineIdData,
+  RestoreBackupData,
+} from '../types/api';
 
 /**
  * 备份状态管理
@@ -38,29 +48,14 @@ export const useBackupStore = defineStore('backup', () => {
     error.value = null;
 
     try {
-      const result = await invoke<{
-        success: boolean;
-        backups: Array<{
-          id: string;
-          guid: string;
-          source: string;
-          timestamp: number;
-          description?: string;
-        }>;
-        count: number;
-        error?: string;
-      }>('list_backups');
+      const result = await invokeCommand<BackupListData>('list_backups');
 
-      if (result.success) {
-        backups.value = result.backups;
+      if (result.success && result.data) {
+        backups.value = result.data.backups;
         return { success: true, data: backups.value };
-      } else {
-        error.value = result.error || '加载备份失败';
-        return { success: false, error: error.value };
       }
-    } catch (e) {
-      error.value = e instanceof Error ? e.message : '未知错误';
-      return { success: false, error: error.value };
+      error.value = result.error?.message || '加载备份失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
     } finally {
       isLoading.value = false;
     }
@@ -81,17 +76,12 @@ export const useBackupStore = defineStore('backup', () => {
         const loadResult = await loadBackups();
         if (loadResult.success && backups.value.length > 0) {
           // 获取当前机器码
-          const { invoke } = await import('@tauri-apps/api/core');
-          const machineResult = await invoke<{
-            success: boolean;
-            guid: string;
-            source: string;
-            error?: string;
-          }>('read_machine_id');
-
-          if (machineResult.success) {
+          const machineResult = await invokeCommand<MachineIdData>('read_machine_id');
+
+          if (machineResult.success && machineResult.data) {
+            const currentGuid = machineResult.data.guid.toLowerCase();
             const existingBackup = backups.value.find(
-              b => b.guid.toLowerCase() === machineResult.guid.toLowerCase()
+              b => b.guid.toLowerCase() === currentGuid
             );
 
             if (existingBackup) {
@@ -105,35 +95,21 @@ export const useBackupStore = defineStore('backup', () => {
         }
       }
 
-      const result = await invoke<{
-        success: boolean;
-        backup?: {
-          id: string;
-          guid: string;
-          source: string;
-          timestamp: number;
-          description?: string;
-        };
-        skipped: boolean;
-        error?: string;
-      }>('backup_machine_guid', { description });
+      const result = await invokeCommand<BackupData>('backup_machine_guid', { description });
 
-      if (result.success) {
-        if (result.backup) {
+      if (result.success && result.data) {
+        if (result.data.backup) {
           // 添加到列表开头
-          backups.value.unshift(result.backup);
-          return { success: true, data: result.backup, message: '备份成功' };
-        } else if (result.skipped) {
+          backups.value.unshift(result.data.backup);
+          return { success: true, data: result.data.backup, message: '备份成功' };
+        } else if (result.data.skipped) {
           // 后端返回跳过，说明已存在相同 GUID 的备份
           return { success: true, message: '该机器码已存在备份，已跳过' };
         }
       }
 
-      error.value = result.error || '备份失败';
-      return { success: false, error: error.value };
-    } catch (e) {
-      error.value = e instanceof Error ? e.message : '未知错误';
-      return { success: false, error: error.value };
+      error.value = result.error?.message || '备份失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
     } finally {
       isLoading.value = false;
     }
@@ -147,10 +123,7 @@ export const useBackupStore = defineStore('backup', () => {
     error.value = null;
 
     try {
-      const result = await invoke<{
-        success: boolean;
-        error?: string;
-      }>('delete_backup_by_id', { id });
+      const result = await invokeCommand<null>('delete_backup_by_id', { id });
 
       if (result.success) {
         backups.value = backups.value.filter((b) => b.id !== id);
@@ -158,13 +131,9 @@ export const useBackupStore = defineStore('backup', () => {
           selectedBackupId.value = null;
         }
         return { success: true };
-      } else {
-        error.value = result.error || '删除备份失败';
-        return { success: false, error: error.value };
       }
-    } catch (e) {
-      error.value = e instanceof Error ? e.message : '未知错误';
-      return { success: false, error: error.value };
+      error.value = result.error?.message || '删除备份失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
     } finally {
       isLoading.value = false;
     }
@@ -178,22 +147,15 @@ export const useBackupStore = defineStore('backup', () => {
     error.value = null;
 
     try {
-      const result = await invoke<{
-        success: boolean;
-        error?: string;
-      }>('clear_all_backups');
+      const result = await invokeCommand<null>('clear_all_backups');
 
       if (result.success) {
         backups.value = [];
         selectedBackupId.value = null;
         return { success: true };
-      } else {
-        error.value = result.error || '清空备份失败';
-        return { success: false, error: error.value };
       }
-    } catch (e) {
-      error.value = e instanceof Error ? e.message : '未知错误';
-      return { success: false, error: error.value };
+      error.value = result.error?.message || '清空备份失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
     } finally {
       isLoading.value = false;
     }
@@ -207,47 +169,23 @@ export const useBackupStore = defineStore('backup', () => {
     error.value = null;
 
     try {
-      const result = await invoke<{
-        success: boolean;
-        previousGuid: string;
-        restoredGuid: string;
-        preBackup?: {
-          id: string;
-          guid: string;
-          source: string;
-          timestamp: number;
-          description?: string;
-        };
-        restoredFrom: {
-          id: string;
-          guid: string;
-          source: string;
-          timestamp: number;
-          description?: string;
-        };
-        message?: string;
-        error?: string;
-      }>('restore_backup_by_id_command', { id });
+      const result = await invokeCommand<RestoreBackupData>('restore_backup_by_id_command', { id });
 
-      if (result.success) {
+      if (result.success && result.data) {
         const restoreResult: RestoreResult = {
-          previousGuid: result.previousGuid,
-          restoredGuid: result.restoredGuid,
-          preBackup: result.preBackup,
-          restoredFrom: result.restoredFrom,
+          previousGuid: result.data.previous_guid,
+          restoredGuid: result.data.restored_guid,
+          preBackup: result.data.pre_backup ?? undefined,
+          restoredFrom: result.data.restored_from,
         };
 
         // 刷新备份列表
         await loadBackups();
 
-        return { success: true, data: restoreResult, message: result.message };
-      } else {
-        error.value = result.error || '恢复备份失败';
-        return { success: false, error: error.value };
+        return { success: true, data: restoreResult, message: result.data.message };
       }
-    } catch (e) {
-      error.value = e instanceof Error ? e.message : '未知错误';
-      return { success: false, error: error.value };
+      error.value = result.error?.message || '恢复备份失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
     } finally {
       isLoading.value = false;
     }
@@ -289,37 +227,70 @@ export const useBackupStore = defineStore('backup', () => {
     error.value = null;
 
     try {
-      const result = await invoke<{
-        success: boolean;
-        backup?: {
-          id: string;
-          guid: string;
-          source: string;
-          timestamp: number;
-          description?: string;
-        };
-        error?: string;
-      }>('update_backup_description_command', { id, description });
+      const result = await invokeCommand<MachineIdBackup>('update_backup_description_command', {
+        id,
+        description,
+      });
 
-      if (result.success && result.backup) {
+      if (result.success && result.data) {
         // 更新本地列表中的备份
         const index = backups.value.findIndex((b) => b.id === id);
         if (index !== -1) {
-          backups.value[index] = result.backup;
+          backups.value[index] = result.data;
         }
-        return { success: true, data: result.backup };
-      } else {
-        error.value = result.error || '更新备份描述失败';
-        return { success: false, error: error.value };
+        return { success: true, data: result.data };
+      }
+      error.value = result.error?.message || '更新备份描述失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
+    } finally {
+      isLoading.value = false;
+    }
+  }
+
+  /**
+   * 导出备份到文件，ids 省略时导出全部备份，提供 password 时加密
+   */
+  async function exportBackups(request: ExportRequest): Promise<OperationResult<ExportResult>> {
+    isLoading.value = true;
+    error.value = null;
+
+    try {
+      const result = await invokeCommand<ExportResult>('export_backups_command', { request });
+
+      if (result.success && result.data) {
+        return { success: true, data: result.data };
       }
-    } catch (e) {
-      error.value = e instanceof Error ? e.message : '未知错误';
-      return { success: false, error: error.value };
+      error.value = result.error?.message || '导出备份失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
     } finally {
       isLoading.value = false;
     }
   }
 
+  /**
+   * 应用后端推送的变更事件，保持与其他窗口、其他进程一致
+   */
+  async function applyChangeEvent(event: ChangeEvent): Promise<void> {
+    switch (event.type) {
+      case 'backup-added':
+        if (!backups.value.some((b) => b.id === event.backup.id)) {
+          backups.value.unshift(event.backup);
+        }
+        break;
+      case 'backup-deleted':
+        backups.value = backups.value.filter((b) => b.id !== event.id);
+        if (selectedBackupId.value === event.id) {
+          selectedBackupId.value = null;
+        }
+        break;
+      case 'store-reloaded':
+        await loadBackups();
+        break;
+      default:
+        break;
+    }
+  }
+
   return {
     // State
     backups,
@@ -340,5 +311,7 @@ export const useBackupStore = defineStore('backup', () => {
     selectBackup,
     copyBackupGuid,
     updateBackupDescription,
+    exportBackups,
+    applyChangeEvent,
   };
 });
@@ -32,6 +32,9 @@ export const useDialogStore = defineStore('dialog', () => {
   // Generate Modal State
   const generateModalVisible = ref(false);
 
+  // Export Modal State
+  const exportModalVisible = ref(false);
+
   /**
    * 显示确认对话框
    */
@@ -179,6 +182,20 @@ export const useDialogStore = defineStore('dialog', () => {
     generateModalVisible.value = false;
   }
 
+  /**
+   * 打开导出对话框
+   */
+  function openExportModal(): void {
+    exportModalVisible.value = true;
+  }
+
+  /**
+   * 关闭导出对话框
+   */
+  function closeExportModal(): void {
+    exportModalVisible.value = false;
+  }
+
   return {
     // State
     confirmVisible,
@@ -188,6 +205,7 @@ export const useDialogStore = defineStore('dialog', () => {
     alertConfig,
     replaceModalVisible,
     generateModalVisible,
+    exportModalVisible,
     // Actions
     showConfirm,
     confirm,
@@ -203,5 +221,7 @@ export const useDialogStore = defineStore('dialog', () => {
     closeReplaceModal,
     openGenerateModal,
     closeGenerateModal,
+    openExportModal,
+    closeExportModal,
   };
 });
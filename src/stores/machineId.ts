@@ -2,8 +2,23 @@ import { defineStore } from 'pinia';
 import { ref, computed } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
 import { writeText } from '@tauri-apps/plugin-clipboard-manager';
+import { invokeCommand } from '@infrastructure/api/tauri.api';
 import { useBackupStore } from './backup';
 import type { MachineIdInfo, OperationResult, PermissionCheckResult } from '../types/index';
+import type {
+  CapabilityIssue,
+  CapabilityReport,
+  ChangeEvent,
+  DiagnosticsExport,
+  GenerateRandomGuidData,
+  MachineIdData,
+  PendingAction,
+  PermissionCheckData,
+  PreviewGuidData,
+  RestartAsAdminData,
+  RestartStateData,
+  WriteGuidData,
+} from '../types/api';
 
 /**
  * MachineID 状态管理
@@ -19,9 +34,15 @@ export const useMachineIdStore = defineStore('machineId', () => {
   const error = ref<string | null>(null);
   const lastCheckTime = ref<number>(0);
   const appVersion = ref<string>('');
+  const capabilities = ref<CapabilityReport | null>(null);
 
   // Getters
   const canModify = computed(() => hasPermission.value);
+  // 机器码无法写入的原因，能力检测前或可以写入时为空
+  const writeIssues = computed<CapabilityIssue[]>(() => {
+    const provider = capabilities.value?.providers.find((p) => p.identifier === 'MachineGuid');
+    return provider && !provider.can_write ? provider.issues : [];
+  });
   const isPermissionStale = computed(() => {
     // 权限检查超过5分钟视为过期
     return Date.now() - lastCheckTime.value > 5 * 60 * 1000;
@@ -35,27 +56,18 @@ export const useMachineIdStore = defineStore('machineId', () => {
     error.value = null;
 
     try {
-      const result = await invoke<{
-        success: boolean;
-        guid: string;
-        source: string;
-        error?: string;
-      }>('read_machine_id');
-
-      if (result.success) {
-        currentGuid.value = result.guid;
-        source.value = result.source;
+      const result = await invokeCommand<MachineIdData>('read_machine_id');
+
+      if (result.success && result.data) {
+        currentGuid.value = result.data.guid;
+        source.value = result.data.source;
         return {
           success: true,
-          data: { guid: result.guid, source: result.source },
+          data: { guid: result.data.guid, source: result.data.source },
         };
-      } else {
-        error.value = result.error || '读取机器码失败';
-        return { success: false, error: error.value };
       }
-    } catch (e) {
-      error.value = e instanceof Error ? e.message : '读取机器码失败';
-      return { success: false, error: error.value };
+      error.value = result.error?.message || '读取机器码失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
     } finally {
       isLoading.value = false;
     }
@@ -85,65 +97,64 @@ export const useMachineIdStore = defineStore('machineId', () => {
     error.value = null;
 
     try {
-      const result = await invoke<{
-        success: boolean;
-        has_permission: boolean;
-        method: string;
-        error_type?: string;
-        error_message?: string;
-        debug_info?: string;
-      }>('check_permission_command');
+      const result = await invokeCommand<PermissionCheckData>('check_permission_command');
 
       lastCheckTime.value = Date.now();
 
-      if (result.success) {
-        hasPermission.value = result.has_permission;
-        permissionMethod.value = result.method;
+      if (!result.success || !result.data) {
+        const message = result.error?.message || '权限检查失败';
+        hasPermission.value = false;
+        permissionError.value = message;
+
+        return {
+          success: false,
+          error: message,
+          errorCode: result.error?.code,
+          data: {
+            hasPermission: false,
+            checkSuccess: false,
+            method: 'error',
+            errorType: 'exception',
+            errorMessage: message,
+            debugInfo: null,
+          },
+        };
+      }
+
+      const data = result.data;
+      if (data.check_success) {
+        hasPermission.value = data.has_permission;
+        permissionMethod.value = data.method;
         permissionError.value = null;
-        
+
         return {
           success: true,
           data: {
-            hasPermission: result.has_permission,
+            hasPermission: data.has_permission,
             checkSuccess: true,
-            method: result.method,
+            method: data.method,
             errorType: null,
             errorMessage: null,
-            debugInfo: result.debug_info || null,
-          },
-        };
-      } else {
-        hasPermission.value = false;
-        permissionMethod.value = result.method || 'unknown';
-        permissionError.value = result.error_message || '权限检查失败';
-        
-        return {
-          success: false,
-          error: result.error_message || '权限检查失败',
-          data: {
-            hasPermission: false,
-            checkSuccess: false,
-            method: result.method || 'unknown',
-            errorType: result.error_type || 'unknown',
-            errorMessage: result.error_message || null,
-            debugInfo: result.debug_info || null,
+            debugInfo: data.debug_info,
           },
         };
       }
-    } catch (e) {
+
+      // 检测本身失败，has_permission 不可信
       hasPermission.value = false;
-      permissionError.value = e instanceof Error ? e.message : '权限检查失败';
-      
+      permissionMethod.value = data.method || 'unknown';
+      permissionError.value = data.error_message || '权限检查失败';
+
       return {
         success: false,
-        error: e instanceof Error ? e.message : '权限检查失败',
+        error: data.error_message || '权限检查失败',
         data: {
           hasPermission: false,
           checkSuccess: false,
-          method: 'error',
-          errorType: 'exception',
-          errorMessage: e instanceof Error ? e.message : '未知错误',
-          debugInfo: null,
+          method: data.method || 'unknown',
+          errorType: data.error_type || 'unknown',
+          errorMessage: data.error_message,
+          debugInfo: data.debug_info,
         },
       };
     } finally {
@@ -158,6 +169,23 @@ export const useMachineIdStore = defineStore('machineId', () => {
     return checkPermission(true);
   }
 
+  /**
+   * 检测各标识符的读写能力，用于在操作前说明写入会因为什么失败
+   */
+  async function checkCapabilities(): Promise<OperationResult<CapabilityReport>> {
+    const result = await invokeCommand<CapabilityReport>('check_capabilities_command');
+
+    if (result.success && result.data) {
+      capabilities.value = result.data;
+      return { success: true, data: result.data };
+    }
+    return {
+      success: false,
+      error: result.error?.message || '能力检测失败',
+      errorCode: result.error?.code,
+    };
+  }
+
   /**
    * 写入机器码
    */
@@ -169,31 +197,24 @@ export const useMachineIdStore = defineStore('machineId', () => {
     error.value = null;
 
     try {
-      const result = await invoke<{
-        success: boolean;
-        previous_guid: string;
-        new_guid: string;
-        message: string;
-        error?: string;
-      }>('write_machine_guid_command', { newGuid: guid, description });
-
-      if (result.success) {
-        currentGuid.value = result.new_guid;
+      const result = await invokeCommand<WriteGuidData>('write_machine_guid_command', {
+        newGuid: guid,
+        description,
+      });
+
+      if (result.success && result.data) {
+        currentGuid.value = result.data.new_guid;
         // 操作成功后刷新备份列表
         const backupStore = useBackupStore();
         await backupStore.loadBackups();
         return {
           success: true,
-          data: { previousGuid: result.previous_guid, newGuid: result.new_guid },
-          message: result.message,
+          data: { previousGuid: result.data.previous_guid, newGuid: result.data.new_guid },
+          message: result.data.message,
         };
-      } else {
-        error.value = result.error || '写入机器码失败';
-        return { success: false, error: error.value };
       }
-    } catch (e) {
-      error.value = e instanceof Error ? e.message : '写入机器码失败';
-      return { success: false, error: error.value };
+      error.value = result.error?.message || '写入机器码失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
     } finally {
       isLoading.value = false;
     }
@@ -201,73 +222,55 @@ export const useMachineIdStore = defineStore('machineId', () => {
 
   /**
    * 预览随机生成的 GUID
-   * 用于显示预览值，确保预览值和实际替换值一致
+   * 返回预览值及与其绑定的一次性令牌，应用时传回令牌确保预览值和实际替换值一致
    */
-  async function previewRandomGuid(): Promise<OperationResult<string>> {
-    try {
-      const result = await invoke<{
-        success: boolean;
-        guid: string;
-        error?: string;
-      }>('preview_random_guid_command');
+  async function previewRandomGuid(): Promise<OperationResult<{ guid: string; token: string }>> {
+    const result = await invokeCommand<PreviewGuidData>('preview_random_guid_command');
 
-      if (result.success) {
-        return {
-          success: true,
-          data: result.guid,
-        };
-      } else {
-        return {
-          success: false,
-          error: result.error || '生成预览 GUID 失败',
-        };
-      }
-    } catch (e) {
+    if (result.success && result.data) {
       return {
-        success: false,
-        error: e instanceof Error ? e.message : '生成预览 GUID 失败',
+        success: true,
+        data: { guid: result.data.guid, token: result.data.token },
       };
     }
+    return {
+      success: false,
+      error: result.error?.message || '生成预览 GUID 失败',
+      errorCode: result.error?.code,
+    };
   }
 
   /**
    * 生成随机机器码
    * @param description 描述信息
-   * @param previewGuid 预览时生成的 GUID，确保预览值和实际替换值一致
+   * @param previewToken 预览时签发的令牌，确保预览值和实际替换值一致
    */
   async function generateRandomMachineId(
     description?: string,
-    previewGuid?: string
+    previewToken?: string
   ): Promise<OperationResult<{ previousGuid: string; newGuid: string }>> {
     isLoading.value = true;
     error.value = null;
 
     try {
-      const result = await invoke<{
-        success: boolean;
-        previous_guid: string;
-        new_guid: string;
-        message: string;
-        error?: string;
-      }>('generate_random_guid_command', { description, previewGuid });
-
-      if (result.success) {
-        currentGuid.value = result.new_guid;
+      const result = await invokeCommand<GenerateRandomGuidData>('generate_random_guid_command', {
+        description,
+        previewToken,
+      });
+
+      if (result.success && result.data) {
+        currentGuid.value = result.data.new_guid;
         // 操作成功后刷新备份列表
         const backupStore = useBackupStore();
         await backupStore.loadBackups();
         return {
           success: true,
-          data: { previousGuid: result.previous_guid, newGuid: result.new_guid },
-          message: result.message,
+          data: { previousGuid: result.data.previous_guid, newGuid: result.data.new_guid },
+          message: result.data.message,
         };
-      } else {
-        error.value = result.error || '生成随机机器码失败';
-        return { success: false, error: error.value };
       }
-    } catch (e) {
-      error.value = e instanceof Error ? e.message : '生成随机机器码失败';
-      return { success: false, error: error.value };
+      error.value = result.error?.message || '生成随机机器码失败';
+      return { success: false, error: error.value, errorCode: result.error?.code };
     } finally {
       isLoading.value = false;
     }
@@ -275,59 +278,63 @@ export const useMachineIdStore = defineStore('machineId', () => {
 
   /**
    * 以管理员身份重启
+   *
+   * pendingAction 会保存到重启状态中，新进程启动后经用户确认继续执行。
+   * 返回的 restarting 为 false 时表示无需重启（例如 Linux 通过 polkit 辅助程序提权），调用方应直接继续操作。
    */
-  async function restartAsAdmin(): Promise<OperationResult<void>> {
-    try {
-      const result = await invoke<{
-        success: boolean;
-        message: string;
-        platform: string;
-        error?: string;
-      }>('restart_as_admin_command');
-
-      if (result.success) {
-        // 重启请求成功，程序将在短时间内退出
-        // 返回成功，让前端显示相应提示
-        return { success: true, message: result.message };
-      } else {
-        return {
-          success: false,
-          error: result.error || '重启失败',
-        };
+  async function restartAsAdmin(
+    pendingAction?: PendingAction
+  ): Promise<OperationResult<{ restarting: boolean }>> {
+    const result = await invokeCommand<RestartAsAdminData>('restart_as_admin_command', {
+      pendingAction: pendingAction ?? null,
+    });
+
+    if (result.success && result.data) {
+      if (!result.data.restarting) {
+        await refreshPermission();
       }
-    } catch (e) {
       return {
-        success: false,
-        error: e instanceof Error ? e.message : '重启失败',
+        success: true,
+        data: { restarting: result.data.restarting },
+        message: result.data.message,
       };
     }
+    return {
+      success: false,
+      error: result.error?.message || '重启失败',
+      errorCode: result.error?.code,
+    };
   }
 
   /**
-   * 检查是否是重启后的状态
+   * 领取重启前保存的状态，只能领取一次
    */
-  async function checkRestartState(): Promise<OperationResult<{ wasRestarted: boolean; timestamp?: number; platform?: string }>> {
-    try {
-      const result = await invoke<{
-        was_restarted: boolean;
-        timestamp?: number;
-        platform?: string;
-      }>('check_restart_state_command');
+  async function checkRestartState(): Promise<
+    OperationResult<{
+      wasRestarted: boolean;
+      timestamp?: number;
+      platform?: string;
+      pendingAction?: PendingAction;
+    }>
+  > {
+    const result = await invokeCommand<RestartStateData>('check_restart_state_command');
 
+    if (result.success && result.data) {
       return {
         success: true,
         data: {
-          wasRestarted: result.was_restarted,
-          timestamp: result.timestamp,
-          platform: result.platform,
+          wasRestarted: result.data.was_restarted,
+          timestamp: result.data.timestamp ?? undefined,
+          platform: result.data.platform ?? undefined,
+          pendingAction: result.data.pending_action ?? undefined,
         },
       };
-    } catch (e) {
-      return {
-        success: false,
-        error: e instanceof Error ? e.message : '检查重启状态失败',
-      };
     }
+    return {
+      success: false,
+      error: result.error?.message || '检查重启状态失败',
+      errorCode: result.error?.code,
+    };
   }
 
   /**
@@ -345,6 +352,28 @@ export const useMachineIdStore = defineStore('machineId', () => {
     }
   }
 
+  /**
+   * 导出诊断包，写入失败需要反馈问题时使用
+   * 默认对机器码脱敏，includeIdentifiers 为 true 时保留完整值
+   */
+  async function exportDiagnostics(
+    includeIdentifiers: boolean = false
+  ): Promise<OperationResult<DiagnosticsExport>> {
+    const result = await invokeCommand<DiagnosticsExport>('export_diagnostics_command', {
+      path: null,
+      includeIdentifiers,
+    });
+
+    if (result.success && result.data) {
+      return { success: true, data: result.data };
+    }
+    return {
+      success: false,
+      error: result.error?.message || '导出诊断包失败',
+      errorCode: result.error?.code,
+    };
+  }
+
   /**
    * 获取应用程序版本号
    */
@@ -360,12 +389,22 @@ export const useMachineIdStore = defineStore('machineId', () => {
     }
   }
 
+  /**
+   * 应用后端推送的变更事件，机器码被其他窗口或进程修改时同步显示
+   */
+  function applyChangeEvent(event: ChangeEvent): void {
+    if (event.type === 'identity-changed') {
+      currentGuid.value = event.current;
+    }
+  }
+
   /**
    * 初始化
    */
   async function initialize(): Promise<void> {
     await checkPermission();
     await readMachineId();
+    await checkCapabilities();
     await getAppVersion();
   }
 
@@ -380,20 +419,25 @@ export const useMachineIdStore = defineStore('machineId', () => {
     error,
     lastCheckTime,
     appVersion,
+    capabilities,
     // Getters
     canModify,
+    writeIssues,
     isPermissionStale,
     // Actions
     readMachineId,
     checkPermission,
     refreshPermission,
+    checkCapabilities,
     writeMachineId,
     generateRandomMachineId,
     previewRandomGuid,
     restartAsAdmin,
     checkRestartState,
     copyToClipboard,
+    exportDiagnostics,
     getAppVersion,
+    applyChangeEvent,
     initialize,
   };
 });
@@ -0,0 +1,56 @@
+/**
+ * 后端接口类型
+ * 由 Rust 端通过 ts-rs 生成（`cargo test` 时导出），此处统一转出，业务代码不直接引用 generated 目录
+ */
+
+export type { ApiResponse } from './generated/ApiResponse';
+export type { ApiError } from './generated/ApiError';
+export type { ErrorCode } from './generated/ErrorCode';
+export type { MachineIdBackup } from './generated/MachineIdBackup';
+export type { PendingRevert } from './generated/PendingRevert';
+export type { Schedule } from './generated/Schedule';
+export type { ScheduleAction } from './generated/ScheduleAction';
+export type { ScheduleTrigger } from './generated/ScheduleTrigger';
+export type { ScheduleRun } from './generated/ScheduleRun';
+export type { IdentityProfile } from './generated/IdentityProfile';
+export type { MachineIdData } from './generated/MachineIdData';
+export type { BackupData } from './generated/BackupData';
+export type { BackupListData } from './generated/BackupListData';
+export type { BackupCountData } from './generated/BackupCountData';
+export type { WriteGuidData } from './generated/WriteGuidData';
+export type { GenerateRandomGuidData } from './generated/GenerateRandomGuidData';
+export type { PreviewGuidData } from './generated/PreviewGuidData';
+export type { RestoreBackupData } from './generated/RestoreBackupData';
+export type { PermissionCheckData } from './generated/PermissionCheckData';
+export type { CapabilityReport } from './generated/CapabilityReport';
+export type { ProviderCapabilities } from './generated/ProviderCapabilities';
+export type { CapabilityIssue } from './generated/CapabilityIssue';
+export type { RestartAsAdminData } from './generated/RestartAsAdminData';
+export type { RestartStateData } from './generated/RestartStateData';
+export type { PendingAction } from './generated/PendingAction';
+export type { ScheduleListData } from './generated/ScheduleListData';
+export type { ProfileListData } from './generated/ProfileListData';
+export type { ApplyProfileData } from './generated/ApplyProfileData';
+export type { PendingRevertListData } from './generated/PendingRevertListData';
+export type { Locale } from './generated/Locale';
+export type { LocaleData } from './generated/LocaleData';
+export type { AutoDescription } from './generated/AutoDescription';
+export type { Settings } from './generated/Settings';
+export type { SettingsData } from './generated/SettingsData';
+export type { LogLevel } from './generated/LogLevel';
+export type { DataDirMode } from './generated/DataDirMode';
+export type { DataDirInfo } from './generated/DataDirInfo';
+export type { DataDirMigration } from './generated/DataDirMigration';
+export type { ChangeEvent } from './generated/ChangeEvent';
+export type { WatchAction } from './generated/WatchAction';
+export type { TrayActionFailed } from './generated/TrayActionFailed';
+export type { LaunchRequest } from './generated/LaunchRequest';
+export type { AuditOperation } from './generated/AuditOperation';
+export type { AuditEntry } from './generated/AuditEntry';
+export type { AuditQuery } from './generated/AuditQuery';
+export type { AuditBreak } from './generated/AuditBreak';
+export type { AuditVerification } from './generated/AuditVerification';
+export type { DiagnosticsExport } from './generated/DiagnosticsExport';
+export type { ExportFormat } from './generated/ExportFormat';
+export type { ExportRequest } from './generated/ExportRequest';
+export type { ExportResult } from './generated/ExportResult';
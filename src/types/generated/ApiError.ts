@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorCode } from "./ErrorCode";
+
+/**
+ * 接口错误
+ */
+export type ApiError = { code: ErrorCode, 
+/**
+ * 面向用户的本地化描述
+ */
+message: string, 
+/**
+ * 可选的结构化详情，例如重试次数、越界的数值
+ */
+details: Record<string, unknown> | null, };
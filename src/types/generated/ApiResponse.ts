@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ApiError } from "./ApiError";
+
+/**
+ * 统一的响应外壳
+ */
+export type ApiResponse<T> = { success: boolean, data: T | null, error: ApiError | null, };
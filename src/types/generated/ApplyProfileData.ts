@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IdentityProfile } from "./IdentityProfile";
+import type { MachineIdBackup } from "./MachineIdBackup";
+
+export type ApplyProfileData = { profile: IdentityProfile, previous_guid: string, new_guid: string, pre_backup: MachineIdBackup | null, post_backup: MachineIdBackup | null, message: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 哈希链断裂的原因
+ */
+export type AuditBreak = "malformed" | "sequence_gap" | "chain_mismatch" | "hash_mismatch" | "head_mismatch";
@@ -0,0 +1,47 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AuditOperation } from "./AuditOperation";
+
+/**
+ * 审计记录
+ */
+export type AuditEntry = { 
+/**
+ * 序号，从 1 开始连续递增
+ */
+seq: number, 
+/**
+ * 记录时间（Unix 毫秒）
+ */
+timestamp: number, operation: AuditOperation, 
+/**
+ * 执行操作的系统用户
+ */
+operator: string, pid: number, 
+/**
+ * 执行操作的程序名称
+ */
+process: string, 
+/**
+ * 操作对象，例如备份 ID
+ */
+target: string | null, 
+/**
+ * 操作前机器码的 SHA-256 摘要
+ */
+old_hash: string | null, 
+/**
+ * 操作后（或读取到的）机器码的 SHA-256 摘要
+ */
+new_hash: string | null, success: boolean, 
+/**
+ * 失败时的错误码
+ */
+error_code: string | null, 
+/**
+ * 上一条记录的哈希，第一条记录为全零
+ */
+prev_hash: string, 
+/**
+ * 本条记录的哈希
+ */
+hash: string, };
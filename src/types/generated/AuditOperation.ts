@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 被审计的操作
+ */
+export type AuditOperation = "read" | "backup" | "write" | "restore" | "delete" | "clear" | "export";
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AuditOperation } from "./AuditOperation";
+
+/**
+ * 查询条件，均为可选
+ */
+export type AuditQuery = { operation: AuditOperation | null, 
+/**
+ * 起始时间（Unix 毫秒，含）
+ */
+since: number | null, 
+/**
+ * 结束时间（Unix 毫秒，含）
+ */
+until: number | null, 
+/**
+ * 最多返回的条数，按时间从新到旧
+ */
+limit: number | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AuditBreak } from "./AuditBreak";
+
+/**
+ * 校验结果
+ */
+export type AuditVerification = { valid: boolean, 
+/**
+ * 校验通过的记录数
+ */
+entries: number, 
+/**
+ * 第一处异常所在的行号（从 1 开始）
+ */
+broken_at: number | null, reason: AuditBreak | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 自动备份的描述，保存结构化数据以便按任意语言渲染
+ */
+export type AutoDescription = { "kind": "before_restore", backup_id: string, guid: string, } | { "kind": "after_replace", guid: string, } | { "kind": "before_profile_switch", profile: string, } | { "kind": "revert_expired", revert_id: string, } | { "kind": "scheduled_rotation" } | { "kind": "external_change", previous: string, guid: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MachineIdBackup } from "./MachineIdBackup";
+
+export type BackupData = { backup: MachineIdBackup | null, 
+/**
+ * 当前机器码已有备份时跳过
+ */
+skipped: boolean, };
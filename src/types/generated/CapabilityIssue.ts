@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 读取或写入无法进行的原因
+ */
+export type CapabilityIssue = { "kind": "unreadable", reason: string, } | { "kind": "write_unsupported" } | { "kind": "missing_privilege" } | { "kind": "read_only_filesystem", path: string, } | { "kind": "immutable_attribute", path: string, } | { "kind": "mac_denial_likely", module: string, context: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProviderCapabilities } from "./ProviderCapabilities";
+
+/**
+ * 能力检测报告
+ */
+export type CapabilityReport = { platform: string, providers: Array<ProviderCapabilities>, };
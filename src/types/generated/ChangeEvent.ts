@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MachineIdBackup } from "./MachineIdBackup";
+import type { WatchAction } from "./WatchAction";
+
+/**
+ * 变更事件，`type` 字段与事件名称一致
+ */
+export type ChangeEvent = { "type": "identity-changed", previous: string | null, current: string, } | { "type": "backup-added", backup: MachineIdBackup, } | { "type": "backup-deleted", id: string, } | { "type": "store-reloaded", count: number, } | { "type": "identity-alert", previous: string, current: string, action: WatchAction, 
+/**
+ * 自动恢复失败的原因
+ */
+error: string | null, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataDirMode } from "./DataDirMode";
+
+/**
+ * 当前数据目录及两种模式对应的位置
+ */
+export type DataDirInfo = { 
+/**
+ * 当前使用的数据目录
+ */
+path: string, 
+/**
+ * 当前模式，使用配置中的自定义目录时为 None
+ */
+mode: DataDirMode | null, 
+/**
+ * 便携模式的数据目录
+ */
+portable_path: string | null, 
+/**
+ * 安装模式的数据目录
+ */
+installed_path: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataDirMode } from "./DataDirMode";
+
+/**
+ * 数据目录迁移结果
+ */
+export type DataDirMigration = { from: string, to: string, mode: DataDirMode, 
+/**
+ * 已迁移的文件和目录名称
+ */
+moved: Array<string>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 导出结果
+ */
+export type DiagnosticsExport = { 
+/**
+ * 诊断包路径
+ */
+path: string, 
+/**
+ * 诊断包内的文件
+ */
+files: Array<string>, size_bytes: number, include_identifiers: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 机器可读的错误码，与 [`BackupError`] 变体一一对应
+ * 序列化值（snake_case）是对外契约，已发布的值不得修改
+ */
+export type ErrorCode = "registry_error" | "registry_write_error" | "not_found" | "parse_error" | "storage_error" | "backup_not_found" | "invalid_guid_format" | "insufficient_permissions" | "unsupported_platform" | "guid_generation_exhausted" | "invalid_preview_token" | "invalid_schedule" | "schedule_not_found" | "profile_not_found" | "profile_already_exists" | "invalid_profile" | "pending_revert_not_found" | "invalid_revert_duration" | "invalid_settings" | "data_dir_conflict" | "data_dir_busy" | "helper_failed" | "invalid_export" | "audit_log_failed" | "guid_already_used" | "invalid_request" | "unauthorized" | "internal";
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportFormat } from "./ExportFormat";
+
+/**
+ * 导出请求
+ */
+export type ExportRequest = { 
+/**
+ * 输出文件路径
+ */
+path: string, format: ExportFormat, 
+/**
+ * 要导出的备份 ID，按给定顺序导出；省略时导出全部备份
+ */
+ids?: Array<string>, 
+/**
+ * 加密密码，省略时以明文保存
+ */
+password?: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportFormat } from "./ExportFormat";
+
+/**
+ * 导出结果
+ */
+export type ExportResult = { path: string, format: ExportFormat, 
+/**
+ * 导出的备份数
+ */
+count: number, encrypted: boolean, size_bytes: number, };
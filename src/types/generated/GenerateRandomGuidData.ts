@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MachineIdBackup } from "./MachineIdBackup";
+
+export type GenerateRandomGuidData = { previous_guid: string, new_guid: string, pre_backup: MachineIdBackup | null, post_backup: MachineIdBackup | null, 
+/**
+ * 因与当前值、历史备份或黑名单冲突而被拒绝的候选 GUID 数量
+ */
+rejected_candidates: number, message: string, };
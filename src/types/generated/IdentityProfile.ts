@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 身份配置
+ * 将一个名称映射到一组标识符值，例如 "QA-A" -> { MachineGuid: ... }
+ */
+export type IdentityProfile = { name: string, identifiers: { [key in string]?: string }, description: string | null, created_at: number, updated_at: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 需要前端处理的启动请求
+ */
+export type LaunchRequest = { 
+/**
+ * 从指定备份恢复，恢复前由用户确认
+ */
+restore: string | null, };
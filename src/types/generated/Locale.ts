@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 支持的语言
+ * 反序列化时兼容 `zh_CN.UTF-8`、`en-US` 等写法，见 [`Locale::from_tag`]
+ */
+export type Locale = "zh-CN" | "en";
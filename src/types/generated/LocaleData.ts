@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Locale } from "./Locale";
+
+export type LocaleData = { 
+/**
+ * 当前使用的语言
+ */
+locale: Locale, 
+/**
+ * 支持的语言
+ */
+available: Array<Locale>, };
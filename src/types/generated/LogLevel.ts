@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 日志级别
+ */
+export type LogLevel = "trace" | "debug" | "info" | "warn" | "error";
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AutoDescription } from "./AutoDescription";
+
+export type MachineIdBackup = { id: string, guid: string, source: string, timestamp: number, 
+/**
+ * 展示用描述；自动生成的备份读取时按当前语言重新渲染
+ */
+description: string | null, 
+/**
+ * 自动生成的描述，以结构化形式保存，用户修改描述后清空
+ */
+auto_description?: AutoDescription | null, };
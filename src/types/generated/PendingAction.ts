@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 提权前用户正在进行、需要在新进程中继续的操作
+ */
+export type PendingAction = { "kind": "write_guid", guid: string, description: string | null, } | { "kind": "restore_backup", backup_id: string, } | { "kind": "open_generate" } | { "kind": "open_replace" };
@@ -0,0 +1,35 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevertStatus } from "./RevertStatus";
+
+/**
+ * 待执行的自动恢复
+ */
+export type PendingRevert = { id: string, 
+/**
+ * 临时写入前的原始值
+ */
+original_guid: string, 
+/**
+ * 临时写入的值
+ */
+temporary_guid: string, 
+/**
+ * 写入前自动创建的备份，存在时优先从该备份恢复
+ */
+pre_backup_id: string | null, created_at: number, revert_at: number, 
+/**
+ * 最近一次恢复失败的原因
+ */
+last_error: string | null, status: RevertStatus, 
+/**
+ * 已尝试执行的次数
+ */
+attempts: number, 
+/**
+ * 失败后下次重试的时间
+ */
+retry_at: number | null, 
+/**
+ * 本次执行开始的时间，仅在执行中时存在
+ */
+started_at: number | null, };
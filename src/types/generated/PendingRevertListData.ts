@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PendingRevert } from "./PendingRevert";
+
+export type PendingRevertListData = { reverts: Array<PendingRevert>, count: number, };
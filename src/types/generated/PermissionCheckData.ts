@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PermissionCheckData = { has_permission: boolean, 
+/**
+ * 检测本身是否成功完成，为 false 时 has_permission 不可信
+ */
+check_success: boolean, method: string, error_type: string | null, error_message: string | null, debug_info: string | null, };
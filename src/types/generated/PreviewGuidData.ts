@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PreviewGuidData = { guid: string, 
+/**
+ * 与预览值绑定的一次性令牌，应用时需原样传回
+ */
+token: string, expires_at: number, rejected_candidates: number, };
@@ -0,0 +1,43 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CapabilityIssue } from "./CapabilityIssue";
+
+/**
+ * 单个标识符的能力
+ */
+export type ProviderCapabilities = { 
+/**
+ * 标识符键名，与命名身份配置中的键一致
+ */
+identifier: string, 
+/**
+ * 标识符的存储位置（文件路径或注册表路径）
+ */
+location: string, can_read: boolean, can_write: boolean, 
+/**
+ * 写入方式：`direct`、`polkit_helper` 或 `registry`，无法写入时为 None
+ */
+write_method: string | null, 
+/**
+ * 能否同步写入 `/var/lib/dbus/machine-id`，该文件不存在时为 None（仅 Linux）
+ */
+can_write_dbus_id: boolean | null, 
+/**
+ * 所在文件系统是否只读挂载（仅 Linux）
+ */
+read_only_filesystem: boolean | null, 
+/**
+ * 是否设置了 immutable 属性（仅 Linux）
+ */
+immutable: boolean | null, 
+/**
+ * 当前进程是否具有 `CAP_DAC_OVERRIDE`（仅 Linux）
+ */
+cap_dac_override: boolean | null, 
+/**
+ * SELinux/AppArmor 是否可能拒绝写入（仅 Linux）
+ */
+mac_denial_likely: boolean | null, 
+/**
+ * 读取或写入无法进行的原因，全部能力可用时为空
+ */
+issues: Array<CapabilityIssue>, };
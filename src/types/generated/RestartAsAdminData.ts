@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RestartAsAdminData = { message: string, platform: string, 
+/**
+ * 为 false 时无需重启，前端直接继续操作
+ */
+restarting: boolean, };
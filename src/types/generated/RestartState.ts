@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PendingAction } from "./PendingAction";
+
+/**
+ * 重启状态
+ */
+export type RestartState = { 
+/**
+ * 保存时间（Unix 秒）
+ */
+timestamp: number, platform: string, pending_action: PendingAction | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PendingAction } from "./PendingAction";
+
+export type RestartStateData = { was_restarted: boolean, timestamp: number | null, platform: string | null, 
+/**
+ * 重启前未完成的操作，经用户确认后继续
+ */
+pending_action: PendingAction | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MachineIdBackup } from "./MachineIdBackup";
+
+export type RestoreBackupData = { previous_guid: string, restored_guid: string, pre_backup: MachineIdBackup | null, restored_from: MachineIdBackup, message: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 自动恢复记录的状态
+ */
+export type RevertStatus = "pending" | "running" | "paused";
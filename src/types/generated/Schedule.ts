@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScheduleAction } from "./ScheduleAction";
+import type { ScheduleRun } from "./ScheduleRun";
+import type { ScheduleTrigger } from "./ScheduleTrigger";
+
+export type Schedule = { id: string, name: string | null, action: ScheduleAction, trigger: ScheduleTrigger, paused: boolean, created_at: number, last_run: number | null, 
+/**
+ * 最近的运行记录，最新的在前
+ */
+history: Array<ScheduleRun>, };
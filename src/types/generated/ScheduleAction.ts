@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 计划任务执行的操作
+ */
+export type ScheduleAction = { "type": "generate_random" } | { "type": "restore_backup", backup_id: string, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Schedule } from "./Schedule";
+
+export type ScheduleListData = { schedules: Array<Schedule>, count: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 单次运行记录
+ */
+export type ScheduleRun = { timestamp: number, success: boolean, message: string, };
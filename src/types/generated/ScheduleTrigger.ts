@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 计划任务触发条件
+ */
+export type ScheduleTrigger = { "type": "interval", minutes: number, } | { "type": "at_startup" } | { "type": "cron", expression: string, };
@@ -0,0 +1,66 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataDirMode } from "./DataDirMode";
+import type { Locale } from "./Locale";
+import type { LogLevel } from "./LogLevel";
+import type { WatchAction } from "./WatchAction";
+
+/**
+ * 应用配置
+ */
+export type Settings = { 
+/**
+ * 自定义数据目录（备份、计划任务等文件的位置），设置后忽略 `data_dir_mode`
+ */
+data_dir?: string, 
+/**
+ * 数据目录模式，未设置时根据标记文件自动判断，见 [`crate::data_dir`]
+ */
+data_dir_mode?: DataDirMode, 
+/**
+ * 备份、计划任务和身份配置描述的最大字符数
+ */
+max_description_length: number, 
+/**
+ * 日志级别，设置了 `RUST_LOG` 时以其为准；修改后重启生效
+ */
+log_level: LogLevel, 
+/**
+ * 单个日志文件的大小上限（MB），超过后轮转；修改后重启生效
+ */
+log_max_size_mb: bigint, 
+/**
+ * 保留的已轮转日志文件数，见 [`crate::logging`]
+ */
+log_max_files: number, 
+/**
+ * 调试模式：日志中保留完整的机器码，仅在排查问题时开启；修改后重启生效
+ */
+debug_mode: boolean, 
+/**
+ * 界面和消息语言，未设置时按系统语言自动选择
+ */
+locale?: Locale, 
+/**
+ * 本地 HTTP 接口端口
+ */
+http_port: number, 
+/**
+ * 是否监视其他程序对机器码的修改，见 [`crate::watcher`]
+ */
+watch_identity: boolean, 
+/**
+ * 无法使用文件系统通知时轮询机器码的间隔（秒）
+ */
+watch_interval_secs: bigint, 
+/**
+ * 检测到外部修改时是否自动备份新的机器码
+ */
+watch_auto_backup: boolean, 
+/**
+ * 检测到外部修改后的处理方式
+ */
+watch_action: WatchAction, 
+/**
+ * 关闭主窗口时隐藏到系统托盘，程序保持常驻
+ */
+minimize_to_tray: boolean, };
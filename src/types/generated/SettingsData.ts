@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Settings } from "./Settings";
+
+export type SettingsData = { 
+/**
+ * 合并所有配置层后实际生效的配置
+ */
+settings: Settings, 
+/**
+ * 仅由配置文件决定的配置，编辑时以此为基础
+ */
+saved: Settings, 
+/**
+ * 用户配置文件路径，修改的配置保存在此
+ */
+user_config_path: string | null, 
+/**
+ * 系统配置文件路径
+ */
+system_config_path: string | null, };
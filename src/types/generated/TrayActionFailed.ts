@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 托盘快捷操作失败的通知
+ */
+export type TrayActionFailed = { 
+/**
+ * 操作名称，使用当前语言
+ */
+action: string, 
+/**
+ * 脱敏后的错误提示
+ */
+message: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 检测到外部修改后的处理方式
+ */
+export type WatchAction = "log" | "alert" | "revert";
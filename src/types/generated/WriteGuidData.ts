@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MachineIdBackup } from "./MachineIdBackup";
+import type { PendingRevert } from "./PendingRevert";
+
+export type WriteGuidData = { previous_guid: string, new_guid: string, pre_backup: MachineIdBackup | null, post_backup: MachineIdBackup | null, 
+/**
+ * 临时写入时登记的自动恢复
+ */
+pending_revert: PendingRevert | null, message: string, };
@@ -2,6 +2,8 @@
  * MachineID-Manage 类型定义
  */
 
+import type { ErrorCode } from './api';
+
 // 弹窗类型
 export type AlertType = 'info' | 'success' | 'error' | 'warning' | 'permission';
 export type ConfirmType = 'confirm' | 'delete' | 'restore' | 'permission' | 'restart' | 'warning';
@@ -18,7 +20,7 @@ export interface BackupItem {
   guid: string;
   source: string;
   timestamp: number;
-  description?: string;
+  description?: string | null;
   formattedDate?: string;
 }
 
@@ -27,6 +29,8 @@ export interface OperationResult<T = void> {
   success: boolean;
   data?: T;
   error?: string;
+  /** 后端返回的机器可读错误码，失败时可用于区分处理 */
+  errorCode?: ErrorCode;
   message?: string;
 }
 
@@ -4,6 +4,7 @@
 
 import { type ClassValue, clsx } from 'clsx';
 import { twMerge } from 'tailwind-merge';
+import type { CapabilityIssue } from '@/types/api';
 
 /**
  * 合并 Tailwind CSS 类名
@@ -20,6 +21,26 @@ export function isValidGuid(guid: string): boolean {
   return guidPattern.test(guid);
 }
 
+/**
+ * 描述读取或写入无法进行的原因
+ */
+export function describeCapabilityIssue(issue: CapabilityIssue): string {
+  switch (issue.kind) {
+    case 'unreadable':
+      return `无法读取机器码：${issue.reason}`;
+    case 'write_unsupported':
+      return '当前平台不支持修改机器码';
+    case 'missing_privilege':
+      return '缺少管理员权限，且没有可用的提权方式';
+    case 'read_only_filesystem':
+      return `${issue.path} 所在的文件系统为只读挂载`;
+    case 'immutable_attribute':
+      return `${issue.path} 设置了不可修改属性，请先执行 chattr -i`;
+    case 'mac_denial_likely':
+      return `当前进程受 ${issue.module === 'selinux' ? 'SELinux' : 'AppArmor'} 限制（${issue.context}），写入可能被拒绝`;
+  }
+}
+
 /**
  * 格式化时间戳
  */
@@ -1,5 +1,5 @@
 import { describe, it, expect } from 'vitest';
-import { isValidGuid, generateGuid, cn } from './index';
+import { isValidGuid, generateGuid, cn, describeCapabilityIssue } from './index';
 
 describe('工具函数测试', () => {
   describe('isValidGuid', () => {
@@ -61,4 +61,18 @@ describe('工具函数测试', () => {
       expect(cn('class1', { class2: true, class3: false })).toBe('class1 class2');
     });
   });
+
+  describe('describeCapabilityIssue', () => {
+    it('应该说明写入失败的原因', () => {
+      expect(describeCapabilityIssue({ kind: 'immutable_attribute', path: '/etc/machine-id' })).toContain(
+        'chattr -i'
+      );
+      expect(describeCapabilityIssue({ kind: 'read_only_filesystem', path: '/etc/machine-id' })).toContain(
+        '/etc/machine-id'
+      );
+      expect(
+        describeCapabilityIssue({ kind: 'mac_denial_likely', module: 'selinux', context: 'httpd_t' })
+      ).toContain('SELinux');
+    });
+  });
 });
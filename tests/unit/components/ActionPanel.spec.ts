@@ -21,6 +21,7 @@ vi.mock('lucide-vue-next', () => ({
 
 vi.mock('@utils', () => ({
   cn: (...args: unknown[]) => args.filter(Boolean).join(' '),
+  describeCapabilityIssue: (issue: { kind: string }) => issue.kind,
 }));
 
 describe('ActionPanel Component', () => {
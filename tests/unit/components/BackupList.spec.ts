@@ -17,6 +17,7 @@ vi.mock('lucide-vue-next', () => ({
   RotateCcw: { render: () => null },
   Clock: { render: () => null },
   FileText: { render: () => null },
+  Download: { render: () => null },
 }));
 
 vi.mock('@utils', () => ({
@@ -16,6 +16,16 @@ import { invoke } from '@tauri-apps/api/core';
 
 const mockedInvoke = vi.mocked(invoke);
 
+/** 构造成功响应 */
+const ok = <T>(data: T) => ({ success: true, data, error: null });
+
+/** 构造失败响应 */
+const fail = (message: string, code = 'storage_error') => ({
+  success: false,
+  data: null,
+  error: { code, message, details: null },
+});
+
 describe('Backup Store', () => {
   beforeEach(() => {
     setActivePinia(createPinia());
@@ -51,11 +61,7 @@ describe('Backup Store', () => {
         },
       ];
 
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        backups: mockBackups,
-        count: 1,
-      });
+      mockedInvoke.mockResolvedValueOnce(ok({ backups: mockBackups, count: 1 }));
 
       const store = useBackupStore();
       const result = await store.loadBackups();
@@ -68,16 +74,14 @@ describe('Backup Store', () => {
     });
 
     it('应处理加载失败的情况', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        error: '加载失败',
-      });
+      mockedInvoke.mockResolvedValueOnce(fail('加载失败'));
 
       const store = useBackupStore();
       const result = await store.loadBackups();
 
       expect(result.success).toBe(false);
       expect(result.error).toBe('加载失败');
+      expect(result.errorCode).toBe('storage_error');
       expect(store.error).toBe('加载失败');
       expect(store.isLoading).toBe(false);
     });
@@ -105,11 +109,7 @@ describe('Backup Store', () => {
       };
 
       // Mock createBackup 调用（使用 force=true 跳过重复检查）
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        backup: mockBackup,
-        skipped: false,
-      });
+      mockedInvoke.mockResolvedValueOnce(ok({ backup: mockBackup, skipped: false }));
 
       const store = useBackupStore();
       const result = await store.createBackup('手动备份', true); // 使用 force=true
@@ -129,18 +129,12 @@ describe('Backup Store', () => {
       };
 
       // Mock loadBackups 调用
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        backups: [existingBackup],
-        count: 1,
-      });
+      mockedInvoke.mockResolvedValueOnce(ok({ backups: [existingBackup], count: 1 }));
 
       // Mock read_machine_id 调用
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        guid: '550E8400-E29B-41D4-A716-446655440000',
-        source: 'manual',
-      });
+      mockedInvoke.mockResolvedValueOnce(
+        ok({ guid: '550E8400-E29B-41D4-A716-446655440000', source: 'manual' })
+      );
 
       const store = useBackupStore();
       const result = await store.createBackup();
@@ -151,24 +145,15 @@ describe('Backup Store', () => {
 
     it('应处理创建失败的情况', async () => {
       // Mock loadBackups 调用
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        backups: [],
-        count: 0,
-      });
+      mockedInvoke.mockResolvedValueOnce(ok({ backups: [], count: 0 }));
 
       // Mock read_machine_id 调用
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        guid: '550E8400-E29B-41D4-A716-446655440000',
-        source: 'manual',
-      });
+      mockedInvoke.mockResolvedValueOnce(
+        ok({ guid: '550E8400-E29B-41D4-A716-446655440000', source: 'manual' })
+      );
 
       // Mock createBackup 失败
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        error: '备份失败',
-      });
+      mockedInvoke.mockResolvedValueOnce(fail('备份失败'));
 
       const store = useBackupStore();
       const result = await store.createBackup();
@@ -187,11 +172,7 @@ describe('Backup Store', () => {
       };
 
       // Mock createBackup 调用（不调用 loadBackups 和 read_machine_id）
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        backup: mockBackup,
-        skipped: false,
-      });
+      mockedInvoke.mockResolvedValueOnce(ok({ backup: mockBackup, skipped: false }));
 
       const store = useBackupStore();
       const result = await store.createBackup('强制备份', true);
@@ -203,9 +184,7 @@ describe('Backup Store', () => {
 
   describe('deleteBackup - 删除备份', () => {
     it('应成功删除备份', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-      });
+      mockedInvoke.mockResolvedValueOnce(ok(null));
 
       const store = useBackupStore();
       store.backups = [
@@ -225,9 +204,7 @@ describe('Backup Store', () => {
     });
 
     it('删除备份时应清除选中状态', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-      });
+      mockedInvoke.mockResolvedValueOnce(ok(null));
 
       const store = useBackupStore();
       store.backups = [
@@ -246,10 +223,7 @@ describe('Backup Store', () => {
     });
 
     it('应处理删除失败的情况', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        error: '删除失败',
-      });
+      mockedInvoke.mockResolvedValueOnce(fail('删除失败'));
 
       const store = useBackupStore();
       const result = await store.deleteBackup('1');
@@ -261,9 +235,7 @@ describe('Backup Store', () => {
 
   describe('clearAllBackups - 清空所有备份', () => {
     it('应成功清空所有备份', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-      });
+      mockedInvoke.mockResolvedValueOnce(ok(null));
 
       const store = useBackupStore();
       store.backups = [
@@ -291,10 +263,7 @@ describe('Backup Store', () => {
     });
 
     it('应处理清空失败的情况', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        error: '清空失败',
-      });
+      mockedInvoke.mockResolvedValueOnce(fail('清空失败'));
 
       const store = useBackupStore();
       const result = await store.clearAllBackups();
@@ -306,31 +275,28 @@ describe('Backup Store', () => {
 
   describe('restoreBackup - 恢复备份', () => {
     it('应成功恢复备份', async () => {
-      const mockResult = {
-        success: true,
-        previousGuid: 'OLD-GUID-HERE',
-        restoredGuid: '550E8400-E29B-41D4-A716-446655440000',
-        preBackup: {
+      const mockResult = ok({
+        previous_guid: 'OLD-GUID-HERE',
+        restored_guid: '550E8400-E29B-41D4-A716-446655440000',
+        pre_backup: {
           id: '2',
           guid: 'OLD-GUID-HERE',
           source: 'auto',
           timestamp: 1700000001,
+          description: null,
         },
-        restoredFrom: {
+        restored_from: {
           id: '1',
           guid: '550E8400-E29B-41D4-A716-446655440000',
           source: 'manual',
           timestamp: 1700000000,
+          description: null,
         },
         message: '恢复成功',
-      };
+      });
 
       mockedInvoke.mockResolvedValueOnce(mockResult);
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        backups: [],
-        count: 0,
-      });
+      mockedInvoke.mockResolvedValueOnce(ok({ backups: [], count: 0 }));
 
       const store = useBackupStore();
       const result = await store.restoreBackup('1');
@@ -342,10 +308,7 @@ describe('Backup Store', () => {
     });
 
     it('应处理恢复失败的情况', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        error: '恢复失败',
-      });
+      mockedInvoke.mockResolvedValueOnce(fail('恢复失败'));
 
       const store = useBackupStore();
       const result = await store.restoreBackup('1');
@@ -401,4 +364,81 @@ describe('Backup Store', () => {
       expect(formatted[0].guid).toBe('550E8400-E29B-41D4-A716-446655440000');
     });
   });
+
+  describe('exportBackups - 导出备份', () => {
+    it('应该把导出请求原样传给后端', async () => {
+      const store = useBackupStore();
+      const request = {
+        path: '/tmp/backups.json',
+        format: 'json' as const,
+        ids: ['1', '2'],
+        password: 'secret',
+      };
+      mockedInvoke.mockResolvedValueOnce(
+        ok({ path: request.path, format: 'json', count: 2, encrypted: true, size_bytes: 512 })
+      );
+
+      const result = await store.exportBackups(request);
+
+      expect(mockedInvoke).toHaveBeenCalledWith('export_backups_command', { request });
+      expect(result.success).toBe(true);
+      expect(result.data?.count).toBe(2);
+      expect(store.isLoading).toBe(false);
+    });
+
+    it('导出失败时应该返回错误信息和错误码', async () => {
+      const store = useBackupStore();
+      mockedInvoke.mockResolvedValueOnce(fail('.reg 文件只能包含一个备份', 'invalid_export'));
+
+      const result = await store.exportBackups({
+        path: '/tmp/backups.reg',
+        format: 'reg',
+        ids: ['1', '2'],
+      });
+
+      expect(result.success).toBe(false);
+      expect(result.errorCode).toBe('invalid_export');
+      expect(store.error).toBe('.reg 文件只能包含一个备份');
+    });
+  });
+
+  describe('applyChangeEvent - 应用后端变更事件', () => {
+    const backup = {
+      id: '2',
+      guid: '550E8400-E29B-41D4-A716-446655440000',
+      source: 'manual',
+      timestamp: 1700000000,
+      description: null,
+    };
+
+    it('backup-added 应添加到列表开头且不重复', async () => {
+      const store = useBackupStore();
+
+      await store.applyChangeEvent({ type: 'backup-added', backup });
+      await store.applyChangeEvent({ type: 'backup-added', backup });
+
+      expect(store.backups).toEqual([backup]);
+    });
+
+    it('backup-deleted 应移除备份并清除选中状态', async () => {
+      const store = useBackupStore();
+      store.backups = [backup];
+      store.selectedBackupId = '2';
+
+      await store.applyChangeEvent({ type: 'backup-deleted', id: '2' });
+
+      expect(store.backups).toHaveLength(0);
+      expect(store.selectedBackupId).toBeNull();
+    });
+
+    it('store-reloaded 应重新加载备份列表', async () => {
+      mockedInvoke.mockResolvedValueOnce(ok({ backups: [backup], count: 1 }));
+      const store = useBackupStore();
+
+      await store.applyChangeEvent({ type: 'store-reloaded', count: 1 });
+
+      expect(mockedInvoke).toHaveBeenCalledWith('list_backups', undefined);
+      expect(store.backups).toEqual([backup]);
+    });
+  });
 });
@@ -20,6 +20,27 @@ import { invoke } from '@tauri-apps/api/core';
 
 const mockedInvoke = vi.mocked(invoke);
 
+/** 构造成功响应 */
+const ok = <T>(data: T) => ({ success: true, data, error: null });
+
+/** 构造失败响应 */
+const fail = (message: string, code = 'registry_error') => ({
+  success: false,
+  data: null,
+  error: { code, message, details: null },
+});
+
+/** 权限检测结果 */
+const permission = (hasPermission: boolean, method: string) =>
+  ok({
+    has_permission: hasPermission,
+    check_success: true,
+    method,
+    error_type: null,
+    error_message: null,
+    debug_info: null,
+  });
+
 describe('MachineId Store', () => {
   beforeEach(() => {
     setActivePinia(createPinia());
@@ -47,11 +68,9 @@ describe('MachineId Store', () => {
 
   describe('readMachineId - 读取机器码', () => {
     it('应成功读取机器码', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        guid: '550E8400-E29B-41D4-A716-446655440000',
-        source: 'registry',
-      });
+      mockedInvoke.mockResolvedValueOnce(
+        ok({ guid: '550E8400-E29B-41D4-A716-446655440000', source: 'registry' })
+      );
 
       const store = useMachineIdStore();
       const result = await store.readMachineId();
@@ -67,10 +86,7 @@ describe('MachineId Store', () => {
     });
 
     it('应处理读取失败的情况', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        error: '读取失败',
-      });
+      mockedInvoke.mockResolvedValueOnce(fail('读取失败'));
 
       const store = useMachineIdStore();
       const result = await store.readMachineId();
@@ -95,14 +111,7 @@ describe('MachineId Store', () => {
 
   describe('checkPermission - 检查权限', () => {
     it('应成功检查到管理员权限', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        has_permission: true,
-        method: 'token',
-        error_type: null,
-        error_message: null,
-        debug_info: null,
-      });
+      mockedInvoke.mockResolvedValueOnce(permission(true, 'token'));
 
       const store = useMachineIdStore();
       const result = await store.checkPermission();
@@ -115,14 +124,7 @@ describe('MachineId Store', () => {
     });
 
     it('应检测到无管理员权限', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        has_permission: false,
-        method: 'none',
-        error_type: null,
-        error_message: null,
-        debug_info: null,
-      });
+      mockedInvoke.mockResolvedValueOnce(permission(false, 'none'));
 
       const store = useMachineIdStore();
       const result = await store.checkPermission();
@@ -134,14 +136,16 @@ describe('MachineId Store', () => {
     });
 
     it('应处理权限检查失败的情况', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        has_permission: false,
-        method: 'unknown',
-        error_type: 'check_failed',
-        error_message: '权限检查失败',
-        debug_info: null,
-      });
+      mockedInvoke.mockResolvedValueOnce(
+        ok({
+          has_permission: false,
+          check_success: false,
+          method: 'unknown',
+          error_type: 'check_failed',
+          error_message: '权限检查失败',
+          debug_info: null,
+        })
+      );
 
       const store = useMachineIdStore();
       const result = await store.checkPermission();
@@ -153,11 +157,7 @@ describe('MachineId Store', () => {
 
     it('应使用缓存的权限检查结果', async () => {
       // 第一次调用
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        has_permission: true,
-        method: 'token',
-      });
+      mockedInvoke.mockResolvedValueOnce(permission(true, 'token'));
 
       const store = useMachineIdStore();
       await store.checkPermission();
@@ -172,11 +172,7 @@ describe('MachineId Store', () => {
     });
 
     it('强制刷新时应忽略缓存', async () => {
-      mockedInvoke.mockResolvedValue({
-        success: true,
-        has_permission: true,
-        method: 'token',
-      });
+      mockedInvoke.mockResolvedValue(permission(true, 'token'));
 
       const store = useMachineIdStore();
       await store.checkPermission();
@@ -188,11 +184,7 @@ describe('MachineId Store', () => {
 
   describe('refreshPermission - 刷新权限', () => {
     it('应强制刷新权限状态', async () => {
-      mockedInvoke.mockResolvedValue({
-        success: true,
-        has_permission: true,
-        method: 'token',
-      });
+      mockedInvoke.mockResolvedValue(permission(true, 'token'));
 
       const store = useMachineIdStore();
       await store.refreshPermission();
@@ -201,14 +193,61 @@ describe('MachineId Store', () => {
     });
   });
 
+  describe('checkCapabilities - 能力检测', () => {
+    const provider = (canWrite: boolean, issues: unknown[]) =>
+      ok({
+        platform: 'linux',
+        providers: [
+          {
+            identifier: 'MachineGuid',
+            location: '/etc/machine-id',
+            can_read: true,
+            can_write: canWrite,
+            write_method: canWrite ? 'direct' : null,
+            can_write_dbus_id: null,
+            read_only_filesystem: false,
+            immutable: !canWrite,
+            cap_dac_override: true,
+            mac_denial_likely: false,
+            issues,
+          },
+        ],
+      });
+
+    it('无法写入时应给出原因', async () => {
+      const issue = { kind: 'immutable_attribute', path: '/etc/machine-id' };
+      mockedInvoke.mockResolvedValueOnce(provider(false, [issue]));
+
+      const store = useMachineIdStore();
+      const result = await store.checkCapabilities();
+
+      expect(result.success).toBe(true);
+      expect(mockedInvoke).toHaveBeenCalledWith('check_capabilities_command');
+      expect(store.writeIssues).toEqual([issue]);
+    });
+
+    it('可以写入时不应有原因', async () => {
+      mockedInvoke.mockResolvedValueOnce(provider(true, []));
+
+      const store = useMachineIdStore();
+      await store.checkCapabilities();
+
+      expect(store.writeIssues).toEqual([]);
+    });
+  });
+
   describe('writeMachineId - 写入机器码', () => {
     it('应成功写入机器码', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        previous_guid: 'OLD-GUID-HERE',
-        new_guid: '550E8400-E29B-41D4-A716-446655440000',
-        message: '写入成功',
-      });
+      mockedInvoke.mockResolvedValueOnce(
+        ok({
+          previous_guid: 'OLD-GUID-HERE',
+          new_guid: '550E8400-E29B-41D4-A716-446655440000',
+          pre_backup: null,
+          post_backup: null,
+          pending_revert: null,
+          message: '写入成功',
+        })
+      );
 
       const store = useMachineIdStore();
       store.hasPermission = true;
@@ -225,10 +264,7 @@ describe('MachineId Store', () => {
     });
 
     it('应处理写入失败的情况', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        error: '写入失败',
-      });
+      mockedInvoke.mockResolvedValueOnce(fail('写入失败', 'insufficient_permissions'));
 
       const store = useMachineIdStore();
       store.hasPermission = true;
@@ -236,6 +272,7 @@ describe('MachineId Store', () => {
 
       expect(result.success).toBe(false);
       expect(result.error).toBe('写入失败');
+      expect(result.errorCode).toBe('insufficient_permissions');
     });
 
     it('应处理异常情况', async () => {
@@ -252,12 +289,16 @@ describe('MachineId Store', () => {
 
   describe('generateRandomMachineId - 生成随机机器码', () => {
     it('应成功生成随机机器码', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        previous_guid: 'OLD-GUID-HERE',
-        new_guid: 'NEW-RANDOM-GUID',
-        message: '生成成功',
-      });
+      mockedInvoke.mockResolvedValueOnce(
+        ok({
+          previous_guid: 'OLD-GUID-HERE',
+          new_guid: 'NEW-RANDOM-GUID',
+          pre_backup: null,
+          post_backup: null,
+          rejected_candidates: 0,
+          message: '生成成功',
+        })
+      );
 
       const store = useMachineIdStore();
       store.hasPermission = true;
@@ -273,10 +314,7 @@ describe('MachineId Store', () => {
     });
 
     it('应处理生成失败的情况', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        error: '生成失败',
-      });
+      mockedInvoke.mockResolvedValueOnce(fail('生成失败'));
 
       const store = useMachineIdStore();
       store.hasPermission = true;
@@ -289,30 +327,41 @@ describe('MachineId Store', () => {
 
   describe('restartAsAdmin - 以管理员身份重启', () => {
     it('应成功触发重启', async () => {
-      // 模拟新的 API 响应格式
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        message: '程序将以管理员身份重启',
-        platform: 'windows',
-        error: null,
+      mockedInvoke.mockResolvedValueOnce(
+        ok({ message: '程序将以管理员身份重启', platform: 'windows', restarting: true })
+      );
+
+      const store = useMachineIdStore();
+      const result = await store.restartAsAdmin({ kind: 'restore_backup', backup_id: 'backup_1' });
+
+      expect(result.success).toBe(true);
+      expect(result.data?.restarting).toBe(true);
+      expect(result.message).toBe('程序将以管理员身份重启');
+      expect(mockedInvoke).toHaveBeenCalledWith('restart_as_admin_command', {
+        pendingAction: { kind: 'restore_backup', backup_id: 'backup_1' },
       });
+    });
+
+    it('无需重启时应刷新权限状态', async () => {
+      mockedInvoke
+        .mockResolvedValueOnce(
+          ok({ message: '已通过特权辅助程序获得写入权限', platform: 'linux', restarting: false })
+        )
+        .mockResolvedValueOnce(permission(true, 'polkit_helper'));
 
       const store = useMachineIdStore();
       const result = await store.restartAsAdmin();
 
       expect(result.success).toBe(true);
-      expect(result.message).toBe('程序将以管理员身份重启');
-      expect(mockedInvoke).toHaveBeenCalledWith('restart_as_admin_command');
+      expect(result.data?.restarting).toBe(false);
+      expect(mockedInvoke).toHaveBeenCalledWith('restart_as_admin_command', { pendingAction: null });
+      expect(store.hasPermission).toBe(true);
+      expect(store.permissionMethod).toBe('polkit_helper');
     });
 
     it('应处理重启失败的情况', async () => {
       // 模拟后端返回的失败响应
-      mockedInvoke.mockResolvedValueOnce({
-        success: false,
-        message: '重启失败',
-        platform: 'windows',
-        error: '无法以管理员身份启动',
-      });
+      mockedInvoke.mockResolvedValueOnce(fail('无法以管理员身份启动', 'unsupported_platform'));
 
       const store = useMachineIdStore();
       const result = await store.restartAsAdmin();
@@ -332,6 +381,38 @@ describe('MachineId Store', () => {
     });
   });
 
+  describe('checkRestartState - 领取重启状态', () => {
+    it('应返回重启前未完成的操作', async () => {
+      mockedInvoke.mockResolvedValueOnce(
+        ok({
+          was_restarted: true,
+          timestamp: 1000,
+          platform: 'windows',
+          pending_action: { kind: 'write_guid', guid: 'ABC', description: null },
+        })
+      );
+
+      const store = useMachineIdStore();
+      const result = await store.checkRestartState();
+
+      expect(result.success).toBe(true);
+      expect(result.data?.wasRestarted).toBe(true);
+      expect(result.data?.pendingAction).toEqual({ kind: 'write_guid', guid: 'ABC', description: null });
+    });
+
+    it('没有重启状态时不应返回待执行操作', async () => {
+      mockedInvoke.mockResolvedValueOnce(
+        ok({ was_restarted: false, timestamp: null, platform: null, pending_action: null })
+      );
+
+      const store = useMachineIdStore();
+      const result = await store.checkRestartState();
+
+      expect(result.data?.wasRestarted).toBe(false);
+      expect(result.data?.pendingAction).toBeUndefined();
+    });
+  });
+
   describe('copyToClipboard - 复制到剪贴板', () => {
     it('应有 GUID 时才能复制', async () => {
       const store = useMachineIdStore();
@@ -354,18 +435,45 @@ describe('MachineId Store', () => {
     });
   });
 
+  describe('exportDiagnostics - 导出诊断包', () => {
+    it('默认应导出脱敏的诊断包', async () => {
+      mockedInvoke.mockResolvedValueOnce(
+        ok({
+          path: '/data/diagnostics/machineid-diagnostics-1.zip',
+          files: ['report.json'],
+          size_bytes: 512,
+          include_identifiers: false,
+        })
+      );
+
+      const store = useMachineIdStore();
+      const result = await store.exportDiagnostics();
+
+      expect(mockedInvoke).toHaveBeenCalledWith('export_diagnostics_command', {
+        path: null,
+        includeIdentifiers: false,
+      });
+      expect(result.success).toBe(true);
+      expect(result.data?.path).toBe('/data/diagnostics/machineid-diagnostics-1.zip');
+    });
+
+    it('导出失败时应返回错误', async () => {
+      mockedInvoke.mockResolvedValueOnce(fail('存储失败', 'storage_error'));
+
+      const store = useMachineIdStore();
+      const result = await store.exportDiagnostics(true);
+
+      expect(result.success).toBe(false);
+      expect(result.errorCode).toBe('storage_error');
+    });
+  });
+
   describe('initialize - 初始化', () => {
     it('应正确初始化应用', async () => {
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        has_permission: true,
-        method: 'token',
-      });
-      mockedInvoke.mockResolvedValueOnce({
-        success: true,
-        guid: '550E8400-E29B-41D4-A716-446655440000',
-        source: 'registry',
-      });
+      mockedInvoke.mockResolvedValueOnce(permission(true, 'token'));
+      mockedInvoke.mockResolvedValueOnce(
+        ok({ guid: '550E8400-E29B-41D4-A716-446655440000', source: 'registry' })
+      );
 
       const store = useMachineIdStore();
       await store.initialize();